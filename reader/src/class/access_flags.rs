@@ -18,3 +18,27 @@ impl Default for ClassAccessFlags {
         ClassAccessFlags::empty()
     }
 }
+
+bitflags! {
+    /// Flags of a nested class, as declared in the source and recorded in the `InnerClasses`
+    /// attribute. Unlike the class flags, they can include the visibility and `static` modifiers.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct InnerClassAccessFlags: u16 {
+        const PUBLIC = 0x0001;
+        const PRIVATE = 0x0002;
+        const PROTECTED = 0x0004;
+        const STATIC = 0x0008;
+        const FINAL = 0x0010;
+        const INTERFACE = 0x0200;
+        const ABSTRACT = 0x0400;
+        const SYNTHETIC = 0x1000;
+        const ANNOTATION = 0x2000;
+        const ENUM = 0x4000;
+    }
+}
+
+impl Default for InnerClassAccessFlags {
+    fn default() -> InnerClassAccessFlags {
+        InnerClassAccessFlags::empty()
+    }
+}
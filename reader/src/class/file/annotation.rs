@@ -0,0 +1,52 @@
+/// An annotation applied to a class, field or method, as stored in the
+/// `RuntimeVisibleAnnotations` and `RuntimeInvisibleAnnotations` attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// Field descriptor of the annotation interface, i.e. `Ljava/lang/Deprecated;`
+    pub type_descriptor: String,
+    /// The elements given explicitly at the use site. Elements left to their default value
+    /// do not appear.
+    pub elements: Vec<AnnotationElement>,
+}
+
+impl Annotation {
+    /// Returns the value of the element with the given name, if it was given explicitly
+    pub fn element(&self, name: &str) -> Option<&ElementValue> {
+        self.elements
+            .iter()
+            .find(|element| element.name == name)
+            .map(|element| &element.value)
+    }
+}
+
+/// A name-value pair of an [Annotation]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotationElement {
+    pub name: String,
+    pub value: ElementValue,
+}
+
+/// The value of an annotation element, with all the constant pool references resolved
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementValue {
+    Byte(i8),
+    /// A UTF-16 code unit, like the Java `char`
+    Char(u16),
+    Double(f64),
+    Float(f32),
+    Int(i32),
+    Long(i64),
+    Short(i16),
+    Boolean(bool),
+    String(String),
+    /// An enum constant
+    Enum {
+        /// Field descriptor of the enum class, i.e. `Ljava/lang/annotation/RetentionPolicy;`
+        type_descriptor: String,
+        constant_name: String,
+    },
+    /// A class literal, stored as a return descriptor: `Ljava/lang/String;`, `I` or `V`
+    Class(String),
+    Annotation(Annotation),
+    Array(Vec<ElementValue>),
+}
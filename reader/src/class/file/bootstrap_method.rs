@@ -0,0 +1,9 @@
+/// An entry of the `BootstrapMethods` attribute of a class, referred to by the
+/// `invokedynamic` instructions and the dynamically-computed constants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BootstrapMethod {
+    /// Index in the constant pool of the `MethodHandle` of the bootstrap method
+    pub method_handle_index: u16,
+    /// Indexes in the constant pool of the static arguments of the bootstrap method
+    pub arguments: Vec<u16>,
+}
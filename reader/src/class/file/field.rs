@@ -1,6 +1,11 @@
 use std::{fmt, fmt::Formatter};
 
-use crate::{field_flags::FieldFlags, field_type::FieldType};
+use crate::{
+    class::file::{annotation::Annotation, type_annotation::TypeAnnotation},
+    field_flags::FieldFlags,
+    field_type::FieldType,
+    signature::{Signature, TypeSignature},
+};
 
 /// Models a field in a class
 #[derive(Debug, PartialEq)]
@@ -11,6 +16,12 @@ pub struct ClassFileField {
     /// Fields which model a constant (final) will have an attribute specifying the value
     pub constant_value: Option<FieldConstantValue>,
     pub deprecated: bool,
+    /// Generic type of the field, present only if it involves type variables or parameterized types
+    pub signature: Option<Signature<TypeSignature>>,
+    pub visible_annotations: Vec<Annotation>,
+    pub invisible_annotations: Vec<Annotation>,
+    pub visible_type_annotations: Vec<TypeAnnotation>,
+    pub invisible_type_annotations: Vec<TypeAnnotation>,
 }
 
 impl fmt::Display for ClassFileField {
@@ -0,0 +1,25 @@
+use crate::class::access_flags::InnerClassAccessFlags;
+
+/// An entry of the `InnerClasses` attribute, describing a nested class that is either
+/// declared by the class or referred to by it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InnerClass {
+    /// Binary name of the nested class, for example `Foo$Bar` or `Foo$1`
+    pub inner_class: String,
+    /// Binary name of the class declaring the nested class as a member.
+    /// It is absent for local and anonymous classes.
+    pub outer_class: Option<String>,
+    /// The simple name of the nested class in the source. It is absent for anonymous classes.
+    pub name: Option<String>,
+    pub flags: InnerClassAccessFlags,
+}
+
+/// The `EnclosingMethod` attribute, present on local and anonymous classes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnclosingMethod {
+    /// Binary name of the innermost class enclosing the declaration
+    pub class: String,
+    /// Name and descriptor of the enclosing method. It is absent when the class is declared
+    /// in an initializer of the enclosing class rather than in a method or constructor.
+    pub method: Option<(String, String)>,
+}
@@ -2,12 +2,17 @@ use std::{fmt, fmt::Formatter};
 
 use crate::{
     attr::Attribute,
+    class::file::{
+        annotation::{Annotation, ElementValue},
+        type_annotation::TypeAnnotation,
+    },
     exception_table::ExceptionTable,
     field_type::{BaseType, FieldType},
     instruction::Instruction,
     line_number_table::LineNumberTable,
     method_descriptor::MethodDescriptor,
     method_flags::MethodFlags,
+    signature::{MethodSignature, Signature},
 };
 
 /// Models a method in a class
@@ -26,6 +31,22 @@ pub struct ClassFileMethod {
     pub deprecated: bool,
     /// List of exceptions in the `throws` clause of the method
     pub thrown_exceptions: Vec<String>,
+    /// Generic signature of the method, present only if it involves type variables
+    /// or parameterized types
+    pub signature: Option<Signature<MethodSignature>>,
+    pub visible_annotations: Vec<Annotation>,
+    pub invisible_annotations: Vec<Annotation>,
+    /// Annotations of each formal parameter. Note that the compiler can omit synthetic and
+    /// implicit parameters, so the length may differ from the number of parameters
+    /// in the descriptor.
+    pub visible_parameter_annotations: Vec<Vec<Annotation>>,
+    pub invisible_parameter_annotations: Vec<Vec<Annotation>>,
+    /// Type annotations on the signature of the method. The ones on types used in the body
+    /// are in [ClassFileMethodCode].
+    pub visible_type_annotations: Vec<TypeAnnotation>,
+    pub invisible_type_annotations: Vec<TypeAnnotation>,
+    /// Default value of an element of an annotation interface
+    pub annotation_default: Option<ElementValue>,
 }
 
 impl fmt::Display for ClassFileMethod {
@@ -55,6 +76,10 @@ impl ClassFileMethod {
         self.flags.contains(MethodFlags::NATIVE)
     }
 
+    pub fn is_varargs(&self) -> bool {
+        self.flags.contains(MethodFlags::VARARGS)
+    }
+
     pub fn is_void(&self) -> bool {
         self.parsed_type_descriptor.return_type.is_none()
     }
@@ -84,6 +109,9 @@ pub struct ClassFileMethodCode {
     pub code: Vec<u8>,
     pub exception_table: ExceptionTable,
     pub line_number_table: Option<LineNumberTable>,
+    /// Type annotations on local variables and expressions
+    pub visible_type_annotations: Vec<TypeAnnotation>,
+    pub invisible_type_annotations: Vec<TypeAnnotation>,
 
     /// Generic unmapped attributes of the code
     // TODO: replace with some proper struct
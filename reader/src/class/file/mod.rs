@@ -1,13 +1,24 @@
+pub mod annotation;
+pub mod bootstrap_method;
 pub mod field;
+pub mod inner_class;
 pub mod method;
+pub mod type_annotation;
 pub mod version;
 
+pub use annotation::*;
+pub use bootstrap_method::*;
 pub use field::*;
+pub use inner_class::*;
 pub use method::*;
+pub use type_annotation::*;
 pub use version::*;
 
 use super::access_flags::ClassAccessFlags;
-use crate::constant_pool::ConstantPool;
+use crate::{
+    constant_pool::ConstantPool,
+    signature::{ClassSignature, Signature},
+};
 use std::fmt;
 
 /// Represents the content of a .class file.
@@ -23,6 +34,16 @@ pub struct ClassFile {
     pub methods: Vec<ClassFileMethod>,
     pub deprecated: bool,
     pub source_file: Option<String>,
+    /// Generic signature of the class, if it declares type parameters or extends
+    /// or implements parameterized types
+    pub signature: Option<Signature<ClassSignature>>,
+    pub visible_annotations: Vec<Annotation>,
+    pub invisible_annotations: Vec<Annotation>,
+    pub visible_type_annotations: Vec<TypeAnnotation>,
+    pub invisible_type_annotations: Vec<TypeAnnotation>,
+    pub bootstrap_methods: Vec<BootstrapMethod>,
+    pub inner_classes: Vec<InnerClass>,
+    pub enclosing_method: Option<EnclosingMethod>,
 }
 
 impl fmt::Display for ClassFile {
@@ -47,6 +68,21 @@ impl fmt::Display for ClassFile {
         for method in self.methods.iter() {
             writeln!(f, "  - {method}")?;
         }
+        writeln!(f, "bootstrap methods:")?;
+        let text_of = |index: u16| {
+            self.constants
+                .text_of(index)
+                .unwrap_or_else(|_| format!("<invalid constant {index}>"))
+        };
+        for (index, bootstrap_method) in self.bootstrap_methods.iter().enumerate() {
+            let method_handle = text_of(bootstrap_method.method_handle_index);
+            let arguments = bootstrap_method
+                .arguments
+                .iter()
+                .map(|argument| text_of(*argument))
+                .collect::<Vec<String>>();
+            writeln!(f, "  - {index}: {method_handle} arguments: {arguments:?}")?;
+        }
         Ok(())
     }
 }
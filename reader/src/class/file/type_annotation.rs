@@ -0,0 +1,110 @@
+use std::ops::Range;
+
+use crate::{class::file::annotation::Annotation, program_counter::ProgramCounter};
+
+/// An annotation on a use of a type, as stored in the `RuntimeVisibleTypeAnnotations` and
+/// `RuntimeInvisibleTypeAnnotations` attributes. For example, `@NonNull String[]` on a field
+/// has a [TypeAnnotationTarget::Field] target and a path with one [TypePathEntry::Array] entry,
+/// since the annotation applies to the element type of the array.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAnnotation {
+    /// The kind of type that is annotated, and where it appears in the declaration or code
+    pub target: TypeAnnotationTarget,
+    /// The part of the target type that is annotated. An empty path means the whole type.
+    pub type_path: Vec<TypePathEntry>,
+    pub annotation: Annotation,
+}
+
+/// The `target_type` and `target_info` items of a type annotation. The first group can appear
+/// on classes, fields and methods; the ones referring to a program counter or to a local
+/// variable only appear in the attributes of the code of a method.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeAnnotationTarget {
+    /// Type parameter declaration of a generic class or interface
+    ClassTypeParameter(u8),
+    /// Type parameter declaration of a generic method or constructor
+    MethodTypeParameter(u8),
+    /// Type in the `extends` clause, if absent, or in the `implements` clause with the
+    /// given index in the interfaces of the class
+    Supertype(Option<u16>),
+    /// Bound of a type parameter of a generic class or interface
+    ClassTypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    /// Bound of a type parameter of a generic method or constructor
+    MethodTypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    /// Type of a field or record component
+    Field,
+    /// Return type of a method, or type of a newly constructed object
+    Return,
+    /// Receiver type of a method or constructor
+    Receiver,
+    /// Type of the formal parameter with the given index
+    FormalParameter(u8),
+    /// Type in the `throws` clause, with the given index in the exceptions of the method
+    Throws(u16),
+    /// Type of a local variable, live in the given ranges
+    LocalVariable(Vec<LocalVariableRange>),
+    /// Type of a resource variable of a `try`-with-resources statement
+    ResourceVariable(Vec<LocalVariableRange>),
+    /// Type of the parameter of the exception handler with the given index in the exception table
+    ExceptionParameter(u16),
+    /// Type in an `instanceof` expression
+    InstanceOf(ProgramCounter),
+    /// Type in a `new` expression
+    New(ProgramCounter),
+    /// Type in a constructor reference expression, such as `String::new`
+    ConstructorReference(ProgramCounter),
+    /// Type in a method reference expression, such as `String::length`
+    MethodReference(ProgramCounter),
+    /// Type in a cast expression, with the index of the type in an intersection cast
+    Cast {
+        offset: ProgramCounter,
+        type_argument_index: u8,
+    },
+    /// Type argument of an explicit generic constructor invocation
+    ConstructorInvocationTypeArgument {
+        offset: ProgramCounter,
+        type_argument_index: u8,
+    },
+    /// Type argument of an explicit generic method invocation
+    MethodInvocationTypeArgument {
+        offset: ProgramCounter,
+        type_argument_index: u8,
+    },
+    /// Type argument of a generic constructor reference expression
+    ConstructorReferenceTypeArgument {
+        offset: ProgramCounter,
+        type_argument_index: u8,
+    },
+    /// Type argument of a generic method reference expression
+    MethodReferenceTypeArgument {
+        offset: ProgramCounter,
+        type_argument_index: u8,
+    },
+}
+
+/// A range of code in which a local variable has a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalVariableRange {
+    pub range: Range<ProgramCounter>,
+    /// Index of the local variable in the frame
+    pub index: u16,
+}
+
+/// One step of the path from a type to the part of it that is annotated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypePathEntry {
+    /// Deeper into the element type of an array
+    Array,
+    /// Deeper into a nested type, i.e. from `Outer` to `Inner` in `Outer.Inner`
+    Nested,
+    /// Into the bound of a wildcard type argument
+    WildcardBound,
+    /// Into the type argument with the given index of a parameterized type
+    TypeArgument(u8),
+}
@@ -17,6 +17,8 @@ pub enum ClassReaderError {
     UnsupportedVersion(u16, u16),
     /// Error while parsing a given type descriptor in the file
     InvalidTypeDescriptor(String),
+    /// Error while parsing a generic signature in the file
+    InvalidSignature(String),
     InvalidMethodKind(u8),
 }
 
@@ -41,6 +43,9 @@ impl Display for ClassReaderError {
             ClassReaderError::InvalidTypeDescriptor(descriptor) => {
                 write!(f, "invalid type descriptor: {descriptor}")
             }
+            ClassReaderError::InvalidSignature(signature) => {
+                write!(f, "invalid generic signature: {signature}")
+            }
             ClassReaderError::InvalidMethodKind(it) => {
                 write!(f, "invalid method handle kind: {it}")
             }
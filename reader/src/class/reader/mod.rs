@@ -16,21 +16,32 @@ use crate::{
     method_descriptor::MethodDescriptor,
     method_flags::MethodFlags,
     program_counter::ProgramCounter,
+    signature::{ClassSignature, MethodSignature, Signature, TypeSignature},
     type_conversion::ToUsizeSafe,
 };
 
 use super::{
-    access_flags::ClassAccessFlags,
+    access_flags::{ClassAccessFlags, InnerClassAccessFlags},
     file::{
+        annotation::{Annotation, AnnotationElement, ElementValue},
+        bootstrap_method::BootstrapMethod,
         field::{ClassFileField, FieldConstantValue},
+        inner_class::{EnclosingMethod, InnerClass},
         method::{ClassFileMethod, ClassFileMethodCode},
+        type_annotation::{
+            LocalVariableRange, TypeAnnotation, TypeAnnotationTarget, TypePathEntry,
+        },
         version::ClassFileVersion,
         ClassFile,
     },
 };
 
-/// A reader of a byte array representing a class. Supports only a subset of Java 7 class format,
-/// in particular it does not support generics.
+/// Maximum nesting of annotations and arrays inside an annotation element value.
+/// Deeper values are rejected rather than recursed into, so that a crafted class
+/// cannot overflow the native stack of the reader.
+const MAX_ANNOTATION_DEPTH: usize = 64;
+
+/// A reader of a byte array representing a class. Supports only a subset of Java 7 class format.
 pub struct ClassFileReader<'a> {
     buffer: Buffer<'a>,
     /// The class being read, created empty and updated in place
@@ -39,7 +50,7 @@ pub struct ClassFileReader<'a> {
 
 /// Reference: https://docs.oracle.com/javase/specs/jvms/se7/html/jvms-4.html
 impl<'a> ClassFileReader<'a> {
-    pub fn new(data: &[u8]) -> ClassFileReader {
+    pub fn new(data: &[u8]) -> ClassFileReader<'_> {
         ClassFileReader {
             buffer: Buffer::new(data),
             class_file: Default::default(),
@@ -57,6 +68,7 @@ impl<'a> ClassFileReader<'a> {
         self.read_fields()?;
         self.read_methods()?;
         self.read_class_attributes()?;
+        self.validate_bootstrap_methods()?;
 
         Ok(self.class_file)
     }
@@ -226,17 +238,21 @@ impl<'a> ClassFileReader<'a> {
 
     fn read_class_reference_optional(&mut self) -> Result<Option<String>> {
         let super_constant_idx = self.buffer.read_u16()?;
-        if super_constant_idx == 0 {
-            Ok(None)
-        } else {
-            Ok(Some(self.read_string_reference(super_constant_idx)?))
-        }
+        self.read_string_reference_optional(super_constant_idx)
     }
 
     fn read_string_reference(&self, index: u16) -> Result<String> {
         Self::read_string_reference_from(&self.class_file.constants, index)
     }
 
+    fn read_string_reference_optional(&self, index: u16) -> Result<Option<String>> {
+        if index == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(self.read_string_reference(index)?))
+        }
+    }
+
     fn read_string_reference_from(constants_pool: &ConstantPool, index: u16) -> Result<String> {
         constants_pool.text_of(index).map_err(|err| err.into())
     }
@@ -268,6 +284,15 @@ impl<'a> ClassFileReader<'a> {
         let raw_attributes = self.read_raw_attributes()?;
         let constant_value = self.extract_constant_value(&raw_attributes)?;
         let deprecated = self.search_deprecated_attribute(&raw_attributes);
+        let signature = self.extract_signature(&raw_attributes, TypeSignature::parse)?;
+        let visible_annotations =
+            self.extract_annotations(&raw_attributes, "RuntimeVisibleAnnotations");
+        let invisible_annotations =
+            self.extract_annotations(&raw_attributes, "RuntimeInvisibleAnnotations");
+        let visible_type_annotations =
+            self.extract_type_annotations(&raw_attributes, "RuntimeVisibleTypeAnnotations");
+        let invisible_type_annotations =
+            self.extract_type_annotations(&raw_attributes, "RuntimeInvisibleTypeAnnotations");
 
         Ok(ClassFileField {
             flags,
@@ -275,6 +300,11 @@ impl<'a> ClassFileReader<'a> {
             type_descriptor,
             constant_value,
             deprecated,
+            signature,
+            visible_annotations,
+            invisible_annotations,
+            visible_type_annotations,
+            invisible_type_annotations,
         })
     }
 
@@ -353,6 +383,20 @@ impl<'a> ClassFileReader<'a> {
         };
         let deprecated = self.search_deprecated_attribute(&raw_attributes);
         let thrown_exceptions = self.extract_thrown_exceptions(&raw_attributes)?;
+        let signature = self.extract_signature(&raw_attributes, MethodSignature::parse)?;
+        let visible_annotations =
+            self.extract_annotations(&raw_attributes, "RuntimeVisibleAnnotations");
+        let invisible_annotations =
+            self.extract_annotations(&raw_attributes, "RuntimeInvisibleAnnotations");
+        let visible_parameter_annotations = self
+            .extract_parameter_annotations(&raw_attributes, "RuntimeVisibleParameterAnnotations");
+        let invisible_parameter_annotations = self
+            .extract_parameter_annotations(&raw_attributes, "RuntimeInvisibleParameterAnnotations");
+        let visible_type_annotations =
+            self.extract_type_annotations(&raw_attributes, "RuntimeVisibleTypeAnnotations");
+        let invisible_type_annotations =
+            self.extract_type_annotations(&raw_attributes, "RuntimeInvisibleTypeAnnotations");
+        let annotation_default = self.extract_annotation_default(&raw_attributes);
 
         Ok(ClassFileMethod {
             flags,
@@ -363,6 +407,14 @@ impl<'a> ClassFileReader<'a> {
             code,
             deprecated,
             thrown_exceptions,
+            signature,
+            visible_annotations,
+            invisible_annotations,
+            visible_parameter_annotations,
+            invisible_parameter_annotations,
+            visible_type_annotations,
+            invisible_type_annotations,
+            annotation_default,
         })
     }
 
@@ -427,6 +479,10 @@ impl<'a> ClassFileReader<'a> {
                 let attributes =
                     Self::read_raw_attributes_from(&self.class_file.constants, &mut buf)?;
                 let line_number_table = self.extract_line_number_table(&attributes)?;
+                let visible_type_annotations =
+                    self.extract_type_annotations(&attributes, "RuntimeVisibleTypeAnnotations");
+                let invisible_type_annotations =
+                    self.extract_type_annotations(&attributes, "RuntimeInvisibleTypeAnnotations");
 
                 Result::<ClassFileMethodCode>::Ok(ClassFileMethodCode {
                     max_stack,
@@ -434,6 +490,8 @@ impl<'a> ClassFileReader<'a> {
                     code,
                     exception_table,
                     line_number_table,
+                    visible_type_annotations,
+                    invisible_type_annotations,
                     attributes,
                 })
             })
@@ -514,9 +572,452 @@ impl<'a> ClassFileReader<'a> {
         let raw_attributes = self.read_raw_attributes()?;
         self.class_file.deprecated = self.search_deprecated_attribute(&raw_attributes);
         self.class_file.source_file = self.search_source_file_attribute(&raw_attributes)?;
+        self.class_file.signature =
+            self.extract_signature(&raw_attributes, ClassSignature::parse)?;
+        self.class_file.visible_annotations =
+            self.extract_annotations(&raw_attributes, "RuntimeVisibleAnnotations");
+        self.class_file.invisible_annotations =
+            self.extract_annotations(&raw_attributes, "RuntimeInvisibleAnnotations");
+        self.class_file.visible_type_annotations =
+            self.extract_type_annotations(&raw_attributes, "RuntimeVisibleTypeAnnotations");
+        self.class_file.invisible_type_annotations =
+            self.extract_type_annotations(&raw_attributes, "RuntimeInvisibleTypeAnnotations");
+        self.class_file.bootstrap_methods = self.extract_bootstrap_methods(&raw_attributes)?;
+        self.class_file.inner_classes = self.extract_inner_classes(&raw_attributes)?;
+        self.class_file.enclosing_method = self.extract_enclosing_method(&raw_attributes)?;
         Ok(())
     }
 
+    /// Checks that the dynamically-computed constants and call sites refer to
+    /// an existing entry of the `BootstrapMethods` attribute, and that every entry
+    /// refers to a method handle and to loadable constants as its arguments
+    fn validate_bootstrap_methods(&self) -> Result<()> {
+        for (index, entry) in self.class_file.constants.iter() {
+            if let ConstantPoolEntry::DynamicInfo(bootstrap_method_index, _)
+            | ConstantPoolEntry::InvokeDynamicInfo(bootstrap_method_index, _) = entry
+            {
+                if bootstrap_method_index.into_usize_safe()
+                    >= self.class_file.bootstrap_methods.len()
+                {
+                    return Err(ClassReaderError::invalid_class_data(format!(
+                        "invalid bootstrap method index {bootstrap_method_index} in constant {index}"
+                    )));
+                }
+            }
+        }
+
+        for (index, bootstrap_method) in self.class_file.bootstrap_methods.iter().enumerate() {
+            let method_handle_index = bootstrap_method.method_handle_index;
+            if !matches!(
+                self.class_file.constants.get(method_handle_index),
+                Ok(ConstantPoolEntry::MethodHandle(..))
+            ) {
+                return Err(ClassReaderError::invalid_class_data(format!(
+                    "invalid method handle {method_handle_index} in bootstrap method {index}"
+                )));
+            }
+            for &argument in bootstrap_method.arguments.iter() {
+                if !matches!(
+                    self.class_file.constants.get(argument),
+                    Ok(ConstantPoolEntry::Integer(_)
+                        | ConstantPoolEntry::Float(_)
+                        | ConstantPoolEntry::Long(_)
+                        | ConstantPoolEntry::Double(_)
+                        | ConstantPoolEntry::ClassReference(_)
+                        | ConstantPoolEntry::StringReference(_)
+                        | ConstantPoolEntry::MethodHandle(..)
+                        | ConstantPoolEntry::MethodType(_)
+                        | ConstantPoolEntry::DynamicInfo(..))
+                ) {
+                    return Err(ClassReaderError::invalid_class_data(format!(
+                        "invalid argument {argument} in bootstrap method {index}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn extract_bootstrap_methods(
+        &self,
+        raw_attributes: &[Attribute],
+    ) -> Result<Vec<BootstrapMethod>> {
+        raw_attributes
+            .iter()
+            .find(|attr| attr.name == "BootstrapMethods")
+            .map(|attr| {
+                let mut buf = Buffer::new(&attr.bytes);
+                let num_entries = buf.read_u16()?.into_usize_safe();
+                let mut bootstrap_methods = Vec::with_capacity(num_entries);
+                for _ in 0..num_entries {
+                    let method_handle_index = buf.read_u16()?;
+                    let num_arguments = buf.read_u16()?.into_usize_safe();
+                    let mut arguments = Vec::with_capacity(num_arguments);
+                    for _ in 0..num_arguments {
+                        arguments.push(buf.read_u16()?);
+                    }
+                    bootstrap_methods.push(BootstrapMethod {
+                        method_handle_index,
+                        arguments,
+                    });
+                }
+                Ok(bootstrap_methods)
+            })
+            .unwrap_or(Ok(Vec::new()))
+    }
+
+    fn extract_inner_classes(&self, raw_attributes: &[Attribute]) -> Result<Vec<InnerClass>> {
+        raw_attributes
+            .iter()
+            .find(|attr| attr.name == "InnerClasses")
+            .map(|attr| {
+                let mut buf = Buffer::new(&attr.bytes);
+                let num_entries = buf.read_u16()?.into_usize_safe();
+                let mut inner_classes = Vec::with_capacity(num_entries);
+                for _ in 0..num_entries {
+                    let inner_class_index = buf.read_u16()?;
+                    let outer_class_index = buf.read_u16()?;
+                    let name_index = buf.read_u16()?;
+                    let flags_bits = buf.read_u16()?;
+
+                    inner_classes.push(InnerClass {
+                        inner_class: self.read_string_reference(inner_class_index)?,
+                        outer_class: self.read_string_reference_optional(outer_class_index)?,
+                        name: self.read_string_reference_optional(name_index)?,
+                        // Unassigned bits are reserved for future use, and must be ignored
+                        flags: InnerClassAccessFlags::from_bits_truncate(flags_bits),
+                    });
+                }
+                Ok(inner_classes)
+            })
+            .unwrap_or(Ok(Vec::new()))
+    }
+
+    fn extract_enclosing_method(
+        &self,
+        raw_attributes: &[Attribute],
+    ) -> Result<Option<EnclosingMethod>> {
+        raw_attributes
+            .iter()
+            .find(|attr| attr.name == "EnclosingMethod")
+            .map(|attr| {
+                let mut buf = Buffer::new(&attr.bytes);
+                let class_index = buf.read_u16()?;
+                let method_index = buf.read_u16()?;
+
+                let class = self.read_string_reference(class_index)?;
+                let method = if method_index == 0 {
+                    None
+                } else {
+                    match self.class_file.constants.get(method_index)? {
+                        ConstantPoolEntry::NameAndTypeDescriptor(name_index, type_index) => Some((
+                            self.read_string_reference(*name_index)?,
+                            self.read_string_reference(*type_index)?,
+                        )),
+                        _ => {
+                            return Err(ClassReaderError::invalid_class_data(
+                                "invalid EnclosingMethod attribute".to_string(),
+                            ))
+                        }
+                    }
+                };
+                Ok(EnclosingMethod { class, method })
+            })
+            .invert()
+    }
+
+    fn extract_signature<T>(
+        &self,
+        raw_attributes: &[Attribute],
+        parse: impl FnOnce(&str) -> Result<T>,
+    ) -> Result<Option<Signature<T>>> {
+        raw_attributes
+            .iter()
+            .find(|attr| attr.name == "Signature")
+            .map(|attr| {
+                let mut buf = Buffer::new(&attr.bytes);
+                let signature_index = buf.read_u16()?;
+                let signature = self.read_string_reference(signature_index)?;
+                Ok(Signature::parse(signature, parse))
+            })
+            .invert()
+    }
+
+    /// Reads an attribute with annotations, which are used only by reflection and compilers.
+    /// Like for signatures, the JVM does not check them when loading a class: thus a malformed
+    /// attribute does not make the class file invalid, and we ignore it instead.
+    fn extract_annotations_attribute<T: Default>(
+        &self,
+        raw_attributes: &[Attribute],
+        attribute_name: &str,
+        read: impl FnOnce(&mut Buffer) -> Result<T>,
+    ) -> T {
+        raw_attributes
+            .iter()
+            .find(|attr| attr.name == attribute_name)
+            .map(|attr| {
+                read(&mut Buffer::new(&attr.bytes)).unwrap_or_else(|err| {
+                    warn!("ignoring malformed attribute {attribute_name}: {err}");
+                    T::default()
+                })
+            })
+            .unwrap_or_default()
+    }
+
+    fn extract_annotations(
+        &self,
+        raw_attributes: &[Attribute],
+        attribute_name: &str,
+    ) -> Vec<Annotation> {
+        self.extract_annotations_attribute(raw_attributes, attribute_name, |buf| {
+            let num_annotations = buf.read_u16()?.into_usize_safe();
+            (0..num_annotations)
+                .map(|_| self.read_annotation(buf, 0))
+                .collect()
+        })
+    }
+
+    fn extract_parameter_annotations(
+        &self,
+        raw_attributes: &[Attribute],
+        attribute_name: &str,
+    ) -> Vec<Vec<Annotation>> {
+        self.extract_annotations_attribute(raw_attributes, attribute_name, |buf| {
+            let num_parameters = buf.read_u8()?;
+            (0..num_parameters)
+                .map(|_| {
+                    let num_annotations = buf.read_u16()?.into_usize_safe();
+                    (0..num_annotations)
+                        .map(|_| self.read_annotation(buf, 0))
+                        .collect()
+                })
+                .collect()
+        })
+    }
+
+    fn extract_annotation_default(&self, raw_attributes: &[Attribute]) -> Option<ElementValue> {
+        self.extract_annotations_attribute(raw_attributes, "AnnotationDefault", |buf| {
+            self.read_element_value(buf, 0).map(Some)
+        })
+    }
+
+    fn extract_type_annotations(
+        &self,
+        raw_attributes: &[Attribute],
+        attribute_name: &str,
+    ) -> Vec<TypeAnnotation> {
+        self.extract_annotations_attribute(raw_attributes, attribute_name, |buf| {
+            let num_annotations = buf.read_u16()?.into_usize_safe();
+            (0..num_annotations)
+                .map(|_| self.read_type_annotation(buf))
+                .collect()
+        })
+    }
+
+    fn read_type_annotation(&self, buf: &mut Buffer) -> Result<TypeAnnotation> {
+        let target = Self::read_type_annotation_target(buf)?;
+
+        let path_length = buf.read_u8()?;
+        let type_path = (0..path_length)
+            .map(|_| {
+                let type_path_kind = buf.read_u8()?;
+                let type_argument_index = buf.read_u8()?;
+                match type_path_kind {
+                    0 => Ok(TypePathEntry::Array),
+                    1 => Ok(TypePathEntry::Nested),
+                    2 => Ok(TypePathEntry::WildcardBound),
+                    3 => Ok(TypePathEntry::TypeArgument(type_argument_index)),
+                    _ => Err(ClassReaderError::invalid_class_data(format!(
+                        "invalid type path kind: {type_path_kind}"
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<TypePathEntry>>>()?;
+
+        let annotation = self.read_annotation(buf, 0)?;
+        Ok(TypeAnnotation {
+            target,
+            type_path,
+            annotation,
+        })
+    }
+
+    fn read_type_annotation_target(buf: &mut Buffer) -> Result<TypeAnnotationTarget> {
+        let target_type = buf.read_u8()?;
+        Ok(match target_type {
+            0x00 => TypeAnnotationTarget::ClassTypeParameter(buf.read_u8()?),
+            0x01 => TypeAnnotationTarget::MethodTypeParameter(buf.read_u8()?),
+            0x10 => {
+                // The superclass is encoded as index 65535
+                let supertype_index = buf.read_u16()?;
+                TypeAnnotationTarget::Supertype(if supertype_index == u16::MAX {
+                    None
+                } else {
+                    Some(supertype_index)
+                })
+            }
+            0x11 => TypeAnnotationTarget::ClassTypeParameterBound {
+                type_parameter_index: buf.read_u8()?,
+                bound_index: buf.read_u8()?,
+            },
+            0x12 => TypeAnnotationTarget::MethodTypeParameterBound {
+                type_parameter_index: buf.read_u8()?,
+                bound_index: buf.read_u8()?,
+            },
+            0x13 => TypeAnnotationTarget::Field,
+            0x14 => TypeAnnotationTarget::Return,
+            0x15 => TypeAnnotationTarget::Receiver,
+            0x16 => TypeAnnotationTarget::FormalParameter(buf.read_u8()?),
+            0x17 => TypeAnnotationTarget::Throws(buf.read_u16()?),
+            0x40 => TypeAnnotationTarget::LocalVariable(Self::read_local_variable_ranges(buf)?),
+            0x41 => TypeAnnotationTarget::ResourceVariable(Self::read_local_variable_ranges(buf)?),
+            0x42 => TypeAnnotationTarget::ExceptionParameter(buf.read_u16()?),
+            0x43 => TypeAnnotationTarget::InstanceOf(ProgramCounter(buf.read_u16()?)),
+            0x44 => TypeAnnotationTarget::New(ProgramCounter(buf.read_u16()?)),
+            0x45 => TypeAnnotationTarget::ConstructorReference(ProgramCounter(buf.read_u16()?)),
+            0x46 => TypeAnnotationTarget::MethodReference(ProgramCounter(buf.read_u16()?)),
+            0x47..=0x4B => {
+                let offset = ProgramCounter(buf.read_u16()?);
+                let type_argument_index = buf.read_u8()?;
+                match target_type {
+                    0x47 => TypeAnnotationTarget::Cast {
+                        offset,
+                        type_argument_index,
+                    },
+                    0x48 => TypeAnnotationTarget::ConstructorInvocationTypeArgument {
+                        offset,
+                        type_argument_index,
+                    },
+                    0x49 => TypeAnnotationTarget::MethodInvocationTypeArgument {
+                        offset,
+                        type_argument_index,
+                    },
+                    0x4A => TypeAnnotationTarget::ConstructorReferenceTypeArgument {
+                        offset,
+                        type_argument_index,
+                    },
+                    _ => TypeAnnotationTarget::MethodReferenceTypeArgument {
+                        offset,
+                        type_argument_index,
+                    },
+                }
+            }
+            _ => {
+                return Err(ClassReaderError::invalid_class_data(format!(
+                    "invalid type annotation target: {target_type:#0x}"
+                )))
+            }
+        })
+    }
+
+    fn read_local_variable_ranges(buf: &mut Buffer) -> Result<Vec<LocalVariableRange>> {
+        let table_length = buf.read_u16()?;
+        (0..table_length)
+            .map(|_| {
+                let start_pc = buf.read_u16()?;
+                let length = buf.read_u16()?;
+                let index = buf.read_u16()?;
+                Ok(LocalVariableRange {
+                    range: ProgramCounter(start_pc)
+                        ..ProgramCounter(start_pc.saturating_add(length)),
+                    index,
+                })
+            })
+            .collect()
+    }
+
+    fn read_annotation(&self, buf: &mut Buffer, depth: usize) -> Result<Annotation> {
+        let type_index = buf.read_u16()?;
+        let type_descriptor = self.read_string_reference(type_index)?;
+        let num_elements = buf.read_u16()?.into_usize_safe();
+        let mut elements = Vec::with_capacity(num_elements);
+        for _ in 0..num_elements {
+            let name_index = buf.read_u16()?;
+            let name = self.read_string_reference(name_index)?;
+            let value = self.read_element_value(buf, depth + 1)?;
+            elements.push(AnnotationElement { name, value });
+        }
+        Ok(Annotation {
+            type_descriptor,
+            elements,
+        })
+    }
+
+    fn read_element_value(&self, buf: &mut Buffer, depth: usize) -> Result<ElementValue> {
+        if depth > MAX_ANNOTATION_DEPTH {
+            return Err(ClassReaderError::invalid_class_data(
+                "annotation element values are nested too deeply".to_string(),
+            ));
+        }
+        let tag = buf.read_u8()?;
+        Ok(match tag {
+            b'B' => ElementValue::Byte(self.read_int_element_constant(buf)? as i8),
+            b'C' => ElementValue::Char(self.read_int_element_constant(buf)? as u16),
+            b'I' => ElementValue::Int(self.read_int_element_constant(buf)?),
+            b'S' => ElementValue::Short(self.read_int_element_constant(buf)? as i16),
+            b'Z' => ElementValue::Boolean(self.read_int_element_constant(buf)? != 0),
+            b'D' => ElementValue::Double(self.read_element_constant(buf, |entry| match entry {
+                ConstantPoolEntry::Double(value) => Some(*value),
+                _ => None,
+            })?),
+            b'F' => ElementValue::Float(self.read_element_constant(buf, |entry| match entry {
+                ConstantPoolEntry::Float(value) => Some(*value),
+                _ => None,
+            })?),
+            b'J' => ElementValue::Long(self.read_element_constant(buf, |entry| match entry {
+                ConstantPoolEntry::Long(value) => Some(*value),
+                _ => None,
+            })?),
+            b's' => ElementValue::String(self.read_utf8_element_constant(buf)?),
+            b'e' => ElementValue::Enum {
+                type_descriptor: self.read_utf8_element_constant(buf)?,
+                constant_name: self.read_utf8_element_constant(buf)?,
+            },
+            b'c' => ElementValue::Class(self.read_utf8_element_constant(buf)?),
+            b'@' => ElementValue::Annotation(self.read_annotation(buf, depth)?),
+            b'[' => {
+                let num_values = buf.read_u16()?.into_usize_safe();
+                ElementValue::Array(
+                    (0..num_values)
+                        .map(|_| self.read_element_value(buf, depth + 1))
+                        .collect::<Result<Vec<ElementValue>>>()?,
+                )
+            }
+            _ => {
+                return Err(ClassReaderError::invalid_class_data(format!(
+                    "invalid annotation element value tag: {tag}"
+                )))
+            }
+        })
+    }
+
+    fn read_int_element_constant(&self, buf: &mut Buffer) -> Result<i32> {
+        self.read_element_constant(buf, |entry| match entry {
+            ConstantPoolEntry::Integer(value) => Some(*value),
+            _ => None,
+        })
+    }
+
+    fn read_utf8_element_constant(&self, buf: &mut Buffer) -> Result<String> {
+        self.read_element_constant(buf, |entry| match entry {
+            ConstantPoolEntry::Utf8(value) => Some(value.clone()),
+            _ => None,
+        })
+    }
+
+    fn read_element_constant<T>(
+        &self,
+        buf: &mut Buffer,
+        extract: impl FnOnce(&ConstantPoolEntry) -> Option<T>,
+    ) -> Result<T> {
+        let constant_index = buf.read_u16()?;
+        extract(self.class_file.constants.get(constant_index)?).ok_or_else(|| {
+            ClassReaderError::invalid_class_data(format!(
+                "invalid constant for annotation element value: {constant_index}"
+            ))
+        })
+    }
+
     fn search_source_file_attribute(&self, raw_attributes: &[Attribute]) -> Result<Option<String>> {
         raw_attributes
             .iter()
@@ -571,7 +1072,15 @@ pub fn read_buffer(buf: &[u8]) -> Result<ClassFile> {
 
 #[cfg(test)]
 mod tests {
-    use crate::class::reader::{error::ClassReaderError, read_buffer};
+    use crate::{
+        attr::Attribute,
+        buf::Buffer,
+        class::{
+            file::{annotation::Annotation, bootstrap_method::BootstrapMethod},
+            reader::{error::ClassReaderError, read_buffer, ClassFileReader, MAX_ANNOTATION_DEPTH},
+        },
+        constant_pool::ConstantPoolEntry,
+    };
 
     #[test]
     fn magic_number_is_required() {
@@ -581,4 +1090,142 @@ mod tests {
             Err(ClassReaderError::InvalidClassData(s, None)) if s == "invalid magic number"
         ));
     }
+
+    #[test]
+    fn bootstrap_method_indexes_must_be_in_range() {
+        let mut reader = ClassFileReader::new(&[]);
+        reader
+            .class_file
+            .constants
+            .add(ConstantPoolEntry::MethodHandle(6, 1));
+        reader.class_file.bootstrap_methods.push(BootstrapMethod {
+            method_handle_index: 1,
+            arguments: vec![],
+        });
+        reader
+            .class_file
+            .constants
+            .add(ConstantPoolEntry::InvokeDynamicInfo(0, 1));
+        assert_eq!(Ok(()), reader.validate_bootstrap_methods());
+
+        reader
+            .class_file
+            .constants
+            .add(ConstantPoolEntry::DynamicInfo(1, 1));
+        assert!(matches!(
+            reader.validate_bootstrap_methods(),
+            Err(ClassReaderError::InvalidClassData(s, None))
+                if s == "invalid bootstrap method index 1 in constant 3"
+        ));
+    }
+
+    #[test]
+    fn bootstrap_methods_must_reference_a_method_handle_and_loadable_arguments() {
+        let mut reader = ClassFileReader::new(&[]);
+        reader
+            .class_file
+            .constants
+            .add(ConstantPoolEntry::MethodHandle(6, 1));
+        reader
+            .class_file
+            .constants
+            .add(ConstantPoolEntry::Utf8("hey".to_string()));
+        reader
+            .class_file
+            .constants
+            .add(ConstantPoolEntry::Integer(42));
+        reader.class_file.bootstrap_methods.push(BootstrapMethod {
+            method_handle_index: 1,
+            arguments: vec![1, 3],
+        });
+        assert_eq!(Ok(()), reader.validate_bootstrap_methods());
+
+        reader.class_file.bootstrap_methods[0].arguments.push(2);
+        assert!(matches!(
+            reader.validate_bootstrap_methods(),
+            Err(ClassReaderError::InvalidClassData(s, None))
+                if s == "invalid argument 2 in bootstrap method 0"
+        ));
+
+        reader.class_file.bootstrap_methods[0].method_handle_index = 3;
+        assert!(matches!(
+            reader.validate_bootstrap_methods(),
+            Err(ClassReaderError::InvalidClassData(s, None))
+                if s == "invalid method handle 3 in bootstrap method 0"
+        ));
+    }
+
+    #[test]
+    fn deeply_nested_element_values_are_rejected() {
+        let reader = ClassFileReader::new(&[]);
+        let mut data = Vec::new();
+        for _ in 0..=MAX_ANNOTATION_DEPTH {
+            data.extend_from_slice(&[b'[', 0x00, 0x01]);
+        }
+        data.extend_from_slice(&[b'[', 0x00, 0x00]);
+        assert!(matches!(
+            reader.read_element_value(&mut Buffer::new(&data), 0),
+            Err(ClassReaderError::InvalidClassData(s, None))
+                if s == "annotation element values are nested too deeply"
+        ));
+
+        let nested_within_limit = &data[3..];
+        assert!(reader
+            .read_element_value(&mut Buffer::new(nested_within_limit), 0)
+            .is_ok());
+    }
+
+    #[test]
+    fn malformed_annotation_attributes_are_ignored() {
+        let mut reader = ClassFileReader::new(&[]);
+        reader
+            .class_file
+            .constants
+            .add(ConstantPoolEntry::Utf8("Lrjvm/Marker;".to_string()));
+        let attributes = vec![
+            Attribute {
+                name: "RuntimeVisibleAnnotations".to_string(),
+                bytes: vec![0x00, 0x01, 0x00, 0x01, 0x00, 0x00],
+            },
+            Attribute {
+                name: "RuntimeInvisibleAnnotations".to_string(),
+                bytes: vec![0x00, 0x02, 0x00, 0x01, 0x00, 0x00],
+            },
+        ];
+
+        let annotations = reader.extract_annotations(&attributes, "RuntimeVisibleAnnotations");
+        assert_eq!(1, annotations.len());
+        assert_eq!("Lrjvm/Marker;", annotations[0].type_descriptor);
+        assert_eq!(
+            Vec::<Annotation>::new(),
+            reader.extract_annotations(&attributes, "RuntimeInvisibleAnnotations")
+        );
+    }
+
+    #[test]
+    fn malformed_parameter_and_type_annotations_are_ignored() {
+        let reader = ClassFileReader::new(&[]);
+        let attributes = vec![
+            Attribute {
+                name: "RuntimeInvisibleParameterAnnotations".to_string(),
+                bytes: vec![0x01, 0x00, 0x01],
+            },
+            Attribute {
+                name: "RuntimeInvisibleTypeAnnotations".to_string(),
+                bytes: vec![0x00, 0x01, 0xff],
+            },
+            Attribute {
+                name: "AnnotationDefault".to_string(),
+                bytes: vec![b'?'],
+            },
+        ];
+
+        assert!(reader
+            .extract_parameter_annotations(&attributes, "RuntimeInvisibleParameterAnnotations")
+            .is_empty());
+        assert!(reader
+            .extract_type_annotations(&attributes, "RuntimeInvisibleTypeAnnotations")
+            .is_empty());
+        assert_eq!(None, reader.extract_annotation_default(&attributes));
+    }
 }
@@ -105,6 +105,18 @@ impl ConstantPool {
         }
     }
 
+    /// Iterates over the entries together with their 1-based index, skipping the unused
+    /// slots following long and double constants.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &ConstantPoolEntry)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(raw_idx, entry)| match entry {
+                ConstantPoolPhysicalEntry::Entry(entry) => Some(((raw_idx + 1) as u16, entry)),
+                ConstantPoolPhysicalEntry::MultiByteEntryTombstone() => None,
+            })
+    }
+
     fn fmt_entry(&self, idx: u16) -> Result<String, ConstantPoolFormattingError> {
         let entry = self.get(idx)?;
         let text = match entry {
@@ -167,21 +179,17 @@ impl ConstantPool {
             &ConstantPoolEntry::MethodType(i) => {
                 format!("MethodType: {} => ({})", i, self.fmt_entry(i)?)
             }
+            // The first index refers to the bootstrap methods attribute, not to the pool
             &ConstantPoolEntry::DynamicInfo(i, j) => {
                 format!(
-                    "DynamicInfo: {}, {} => ({}), ({})",
-                    i,
-                    j,
-                    self.fmt_entry(i)?,
+                    "DynamicInfo: {i}, {j} => (bootstrap method {i}), ({})",
                     self.fmt_entry(j)?
                 )
             }
+            // The first index refers to the bootstrap methods attribute, not to the pool
             &ConstantPoolEntry::InvokeDynamicInfo(i, j) => {
                 format!(
-                    "InvokeDynamicInfo: {}, {} => ({}), ({})",
-                    i,
-                    j,
-                    self.fmt_entry(i)?,
+                    "InvokeDynamicInfo: {i}, {j} => (bootstrap method {i}), ({})",
                     self.fmt_entry(j)?
                 )
             }
@@ -222,10 +230,10 @@ impl ConstantPool {
             }
             ConstantPoolEntry::MethodType(i) => self.text_of(*i)?,
             ConstantPoolEntry::DynamicInfo(i, j) => {
-                format!("{}: {}", self.text_of(*i)?, self.text_of(*j)?)
+                format!("bootstrap method {i}: {}", self.text_of(*j)?)
             }
             ConstantPoolEntry::InvokeDynamicInfo(i, j) => {
-                format!("{}: {}", self.text_of(*i)?, self.text_of(*j)?)
+                format!("bootstrap method {i}: {}", self.text_of(*j)?)
             }
             ConstantPoolEntry::ModuleInfo(i) => self.text_of(*i)?,
             ConstantPoolEntry::PackageInfo(i) => self.text_of(*i)?,
@@ -253,9 +261,8 @@ impl ConstantPool {
 impl fmt::Display for ConstantPool {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Constant pool: (size: {})", self.entries.len())?;
-        for (raw_idx, _) in self.entries.iter().enumerate() {
-            let index = (raw_idx + 1) as u16;
-            let entry_text = self.fmt_entry(index).map_err(|_| fmt::Error::default())?;
+        for (index, _) in self.iter() {
+            let entry_text = self.fmt_entry(index).map_err(|_| fmt::Error)?;
             writeln!(f, "    {}, {}", index, entry_text)?;
         }
         Ok(())
@@ -318,6 +325,11 @@ mod tests {
             *cp.get(14).unwrap()
         );
 
+        assert_eq!(
+            vec![1, 2, 3, 4, 6, 8, 9, 10, 11, 12, 13, 14],
+            cp.iter().map(|(index, _)| index).collect::<Vec<_>>()
+        );
+
         assert_eq!("hey", cp.text_of(1).unwrap());
         assert_eq!("1", cp.text_of(2).unwrap());
         assert_eq!("2.1", cp.text_of(3).unwrap());
@@ -342,5 +354,32 @@ mod tests {
         assert_eq!("hey.joe", cp.text_of(12).unwrap());
         assert_eq!("hey.joe", cp.text_of(13).unwrap());
         assert_eq!("hey: joe", cp.text_of(14).unwrap());
+
+        let text = cp.to_string();
+        assert!(text.contains("    4, Long: 123\n    6, Double: 3.56\n    8, "));
+    }
+
+    #[test]
+    fn dynamic_constants_refer_to_bootstrap_methods() {
+        let mut cp = ConstantPool::new();
+        cp.add(ConstantPoolEntry::Utf8("run".to_string()));
+        cp.add(ConstantPoolEntry::Utf8(
+            "()Ljava/lang/Runnable;".to_string(),
+        ));
+        cp.add(ConstantPoolEntry::NameAndTypeDescriptor(1, 2));
+        cp.add(ConstantPoolEntry::InvokeDynamicInfo(0, 3));
+        cp.add(ConstantPoolEntry::DynamicInfo(7, 3));
+
+        assert_eq!(
+            "bootstrap method 0: run: ()Ljava/lang/Runnable;",
+            cp.text_of(4).unwrap()
+        );
+        assert_eq!(
+            "bootstrap method 7: run: ()Ljava/lang/Runnable;",
+            cp.text_of(5).unwrap()
+        );
+        assert!(cp.to_string().contains(
+            "4, InvokeDynamicInfo: 0, 3 => (bootstrap method 0), (NameAndTypeDescriptor"
+        ));
     }
 }
@@ -84,6 +84,45 @@ impl FieldType {
             _ => return Err(InvalidTypeDescriptor(type_descriptor.to_string())),
         })
     }
+
+    /// Returns the type descriptor, i.e. the inverse of [FieldType::parse]
+    pub fn to_descriptor(&self) -> String {
+        match self {
+            FieldType::Base(base_type) => base_type.to_descriptor().to_string(),
+            FieldType::Object(class) => format!("L{class};"),
+            FieldType::Array(component_type) => format!("[{}", component_type.to_descriptor()),
+        }
+    }
+}
+
+impl BaseType {
+    /// Returns the name of the type in Java source code, i.e. `int`
+    pub fn to_source_name(&self) -> &'static str {
+        match self {
+            BaseType::Byte => "byte",
+            BaseType::Char => "char",
+            BaseType::Double => "double",
+            BaseType::Float => "float",
+            BaseType::Int => "int",
+            BaseType::Long => "long",
+            BaseType::Short => "short",
+            BaseType::Boolean => "boolean",
+        }
+    }
+
+    /// Returns the type descriptor, i.e. `I` for `int`
+    pub fn to_descriptor(&self) -> char {
+        match self {
+            BaseType::Byte => 'B',
+            BaseType::Char => 'C',
+            BaseType::Double => 'D',
+            BaseType::Float => 'F',
+            BaseType::Int => 'I',
+            BaseType::Long => 'J',
+            BaseType::Short => 'S',
+            BaseType::Boolean => 'Z',
+        }
+    }
 }
 
 #[cfg(test)]
@@ -186,4 +225,14 @@ mod tests {
     fn can_format_array() {
         assert_eq!("Int[]", format!("{}", FieldType::parse("[I").unwrap()));
     }
+
+    #[test]
+    fn can_convert_to_descriptor() {
+        for descriptor in ["Z", "Ljava/lang/String;", "[I", "[[Lrjvm/Test;"] {
+            assert_eq!(
+                descriptor,
+                FieldType::parse(descriptor).unwrap().to_descriptor()
+            );
+        }
+    }
 }
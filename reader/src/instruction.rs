@@ -1,9 +1,11 @@
 use crate::class::reader::error::ClassReaderError;
 
 /// Represents a Java bytecode instruction.
+/// The arguments of branch instructions are the absolute address of the jump target,
+/// computed from the instruction's (16 or 32 bits) relative offset.
 //noinspection SpellCheckingInspection
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Instruction {
     Aaload,
     Aastore,
@@ -89,8 +91,8 @@ pub enum Instruction {
     Fsub,
     Getfield(u16),
     Getstatic(u16),
-    Goto(u16),
-    Goto_w,
+    Goto(u32),
+    Goto_w(u32),
     I2b,
     I2c,
     I2d,
@@ -109,22 +111,22 @@ pub enum Instruction {
     Iconst_4,
     Iconst_5,
     Idiv,
-    If_acmpeq(u16),
-    If_acmpne(u16),
-    If_icmpeq(u16),
-    If_icmpne(u16),
-    If_icmplt(u16),
-    If_icmpge(u16),
-    If_icmpgt(u16),
-    If_icmple(u16),
-    Ifeq(u16),
-    Ifne(u16),
-    Iflt(u16),
-    Ifge(u16),
-    Ifgt(u16),
-    Ifle(u16),
-    Ifnonnull(u16),
-    Ifnull(u16),
+    If_acmpeq(u32),
+    If_acmpne(u32),
+    If_icmpeq(u32),
+    If_icmpne(u32),
+    If_icmplt(u32),
+    If_icmpge(u32),
+    If_icmpgt(u32),
+    If_icmple(u32),
+    Ifeq(u32),
+    Ifne(u32),
+    Iflt(u32),
+    Ifge(u32),
+    Ifgt(u32),
+    Ifle(u32),
+    Ifnonnull(u32),
+    Ifnull(u32),
     Iinc(u8, i8),
     Iload(u8),
     Iload_0,
@@ -152,8 +154,8 @@ pub enum Instruction {
     Isub,
     Iushr,
     Ixor,
-    Jsr(u16),
-    Jsr_w,
+    Jsr(u32),
+    Jsr_w(u32),
     L2d,
     L2f,
     L2i,
@@ -175,7 +177,7 @@ pub enum Instruction {
     Lload_3,
     Lmul,
     Lneg,
-    Lookupswitch,
+    Lookupswitch(LookupSwitch),
     Lor,
     Lrem,
     Lreturn,
@@ -205,8 +207,68 @@ pub enum Instruction {
     Sastore,
     Sipush(i16),
     Swap,
-    Tableswitch,
-    Wide,
+    Tableswitch(TableSwitch),
+    Wide(WideInstruction),
+}
+
+/// Instructions that can be modified by the `wide` prefix, which extends their
+/// local variable index to two bytes (and the constant of `iinc` to a signed short)
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WideInstruction {
+    Aload(u16),
+    Astore(u16),
+    Dload(u16),
+    Dstore(u16),
+    Fload(u16),
+    Fstore(u16),
+    Iinc(u16, i16),
+    Iload(u16),
+    Istore(u16),
+    Lload(u16),
+    Lstore(u16),
+    Ret(u16),
+}
+
+/// Arguments of instruction `tableswitch`. All jump targets are absolute addresses.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TableSwitch {
+    /// Address to jump to if the key is not in the range `low..=high`
+    pub default: u32,
+    pub low: i32,
+    pub high: i32,
+    /// Jump addresses for the keys `low..=high`, in order
+    pub jump_addresses: Vec<u32>,
+}
+
+impl TableSwitch {
+    /// Returns the address to jump to for the given key
+    pub fn jump_address(&self, key: i32) -> u32 {
+        if key < self.low || key > self.high {
+            self.default
+        } else {
+            self.jump_addresses[(key as i64 - self.low as i64) as usize]
+        }
+    }
+}
+
+/// Arguments of instruction `lookupswitch`. All jump targets are absolute addresses.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LookupSwitch {
+    /// Address to jump to if the key does not match any pair
+    pub default: u32,
+    /// Pairs of (match, jump address), sorted by match as required by the specs
+    pub pairs: Vec<(i32, u32)>,
+}
+
+impl LookupSwitch {
+    /// Returns the address to jump to for the given key
+    pub fn jump_address(&self, key: i32) -> u32 {
+        self.pairs
+            .binary_search_by_key(&key, |(value, _)| *value)
+            .map(|index| self.pairs[index].1)
+            .unwrap_or(self.default)
+    }
 }
 
 /// Possible arguments of instruction `newarray`
@@ -226,6 +288,7 @@ impl Instruction {
     /// Reads one instruction from the bytecode, and returns it along
     /// with the address of the start of the next instruction
     pub fn parse(raw_code: &[u8], address: usize) -> Result<(Self, usize), ClassReaderError> {
+        let instruction_address = address;
         let op_byte = Self::byte_at(raw_code, address)?;
         let mut address = address + 1;
         let op_code = match op_byte {
@@ -314,7 +377,11 @@ impl Instruction {
             0xb4 => Instruction::Getfield(Self::read_u16(raw_code, &mut address)?),
             0xb2 => Instruction::Getstatic(Self::read_u16(raw_code, &mut address)?),
             0xa7 => Instruction::Goto(Self::read_offset(raw_code, &mut address)?),
-            0xc8 => todo!("OpCode::Goto_w"),
+            0xc8 => Instruction::Goto_w(Self::read_wide_offset(
+                raw_code,
+                instruction_address,
+                &mut address,
+            )?),
             0x91 => Instruction::I2b,
             0x92 => Instruction::I2c,
             0x87 => Instruction::I2d,
@@ -397,7 +464,11 @@ impl Instruction {
             0x7c => Instruction::Iushr,
             0x82 => Instruction::Ixor,
             0xa8 => Instruction::Jsr(Self::read_offset(raw_code, &mut address)?),
-            0xc9 => todo!("OpCode::Jsr_w"),
+            0xc9 => Instruction::Jsr_w(Self::read_wide_offset(
+                raw_code,
+                instruction_address,
+                &mut address,
+            )?),
             0x8a => Instruction::L2d,
             0x89 => Instruction::L2f,
             0x88 => Instruction::L2i,
@@ -419,7 +490,11 @@ impl Instruction {
             0x21 => Instruction::Lload_3,
             0x69 => Instruction::Lmul,
             0x75 => Instruction::Lneg,
-            0xab => todo!("OpCode::Lookupswitch"),
+            0xab => Instruction::Lookupswitch(Self::read_lookupswitch(
+                raw_code,
+                instruction_address,
+                &mut address,
+            )?),
             0x81 => Instruction::Lor,
             0x71 => Instruction::Lrem,
             0xad => Instruction::Lreturn,
@@ -470,8 +545,12 @@ impl Instruction {
             0x56 => Instruction::Sastore,
             0x11 => Instruction::Sipush(Self::read_i16(raw_code, &mut address)?),
             0x5f => Instruction::Swap,
-            0xaa => todo!("OpCode::Tableswitch"),
-            0xc4 => todo!("OpCode::Wide"),
+            0xaa => Instruction::Tableswitch(Self::read_tableswitch(
+                raw_code,
+                instruction_address,
+                &mut address,
+            )?),
+            0xc4 => Instruction::Wide(Self::read_wide(raw_code, &mut address)?),
             _ => {
                 return Err(ClassReaderError::invalid_class_data(format!(
                     "invalid op code: {op_byte:#04x} at address {address}"
@@ -519,7 +598,7 @@ impl Instruction {
 
     fn read_i8(raw_code: &[u8], address: &mut usize) -> Result<i8, ClassReaderError> {
         let value = Self::read_u8(raw_code, address)?;
-        Ok(unsafe { std::mem::transmute(value) })
+        Ok(value as i8)
     }
 
     fn read_u16(raw_code: &[u8], address: &mut usize) -> Result<u16, ClassReaderError> {
@@ -530,17 +609,285 @@ impl Instruction {
 
     fn read_i16(raw_code: &[u8], address: &mut usize) -> Result<i16, ClassReaderError> {
         let value = Self::read_u16(raw_code, address)?;
-        Ok(unsafe { std::mem::transmute(value) })
+        Ok(value as i16)
     }
 
-    fn read_offset(raw_code: &[u8], address: &mut usize) -> Result<u16, ClassReaderError> {
+    fn read_wide(
+        raw_code: &[u8],
+        address: &mut usize,
+    ) -> Result<WideInstruction, ClassReaderError> {
+        let op_byte = Self::read_u8(raw_code, address)?;
+        let index = Self::read_u16(raw_code, address)?;
+        let wide_instruction = match op_byte {
+            0x19 => WideInstruction::Aload(index),
+            0x3a => WideInstruction::Astore(index),
+            0x18 => WideInstruction::Dload(index),
+            0x39 => WideInstruction::Dstore(index),
+            0x17 => WideInstruction::Fload(index),
+            0x38 => WideInstruction::Fstore(index),
+            0x84 => WideInstruction::Iinc(index, Self::read_i16(raw_code, address)?),
+            0x15 => WideInstruction::Iload(index),
+            0x36 => WideInstruction::Istore(index),
+            0x16 => WideInstruction::Lload(index),
+            0x37 => WideInstruction::Lstore(index),
+            0xa9 => WideInstruction::Ret(index),
+            _ => {
+                return Err(ClassReaderError::invalid_class_data(format!(
+                    "invalid op code for wide: {op_byte:#04x} at address {address}"
+                )))
+            }
+        };
+        Ok(wide_instruction)
+    }
+
+    fn read_i32(raw_code: &[u8], address: &mut usize) -> Result<i32, ClassReaderError> {
+        let high = Self::read_u16(raw_code, address)? as u32;
+        let low = Self::read_u16(raw_code, address)? as u32;
+        Ok(((high << 16) | low) as i32)
+    }
+
+    fn read_offset(raw_code: &[u8], address: &mut usize) -> Result<u32, ClassReaderError> {
         let instruction_address = *address - 1;
         let offset = Self::read_i16(raw_code, address)?;
-        let jump_address = (instruction_address as i32) + (offset as i32);
-        u16::try_from(jump_address).map_err(|_| {
+        Self::jump_address(instruction_address, offset as i32, *address)
+    }
+
+    fn read_wide_offset(
+        raw_code: &[u8],
+        instruction_address: usize,
+        address: &mut usize,
+    ) -> Result<u32, ClassReaderError> {
+        let offset = Self::read_i32(raw_code, address)?;
+        Self::jump_address(instruction_address, offset, *address)
+    }
+
+    fn jump_address(
+        instruction_address: usize,
+        offset: i32,
+        address: usize,
+    ) -> Result<u32, ClassReaderError> {
+        let jump_address = (instruction_address as i64) + (offset as i64);
+        u32::try_from(jump_address).map_err(|_| {
             ClassReaderError::invalid_class_data(format!(
                 "invalid jump offset at address {address}"
             ))
         })
     }
+
+    /// The arguments of `tableswitch` and `lookupswitch` start at an address
+    /// that is a multiple of four, relative to the start of the code
+    fn skip_switch_padding(address: &mut usize) {
+        *address = (*address + 3) & !3;
+    }
+
+    fn read_tableswitch(
+        raw_code: &[u8],
+        instruction_address: usize,
+        address: &mut usize,
+    ) -> Result<TableSwitch, ClassReaderError> {
+        Self::skip_switch_padding(address);
+        let default = Self::read_wide_offset(raw_code, instruction_address, address)?;
+        let low = Self::read_i32(raw_code, address)?;
+        let high = Self::read_i32(raw_code, address)?;
+        if low > high {
+            return Err(ClassReaderError::invalid_class_data(format!(
+                "invalid tableswitch bounds {low}..{high} at address {instruction_address}"
+            )));
+        }
+
+        let jump_addresses = (low..=high)
+            .map(|_| Self::read_wide_offset(raw_code, instruction_address, address))
+            .collect::<Result<Vec<u32>, ClassReaderError>>()?;
+        Ok(TableSwitch {
+            default,
+            low,
+            high,
+            jump_addresses,
+        })
+    }
+
+    fn read_lookupswitch(
+        raw_code: &[u8],
+        instruction_address: usize,
+        address: &mut usize,
+    ) -> Result<LookupSwitch, ClassReaderError> {
+        Self::skip_switch_padding(address);
+        let default = Self::read_wide_offset(raw_code, instruction_address, address)?;
+        let num_pairs = Self::read_i32(raw_code, address)?;
+        if num_pairs < 0 {
+            return Err(ClassReaderError::invalid_class_data(format!(
+                "invalid lookupswitch pairs count {num_pairs} at address {instruction_address}"
+            )));
+        }
+
+        let pairs = (0..num_pairs)
+            .map(|_| {
+                let key = Self::read_i32(raw_code, address)?;
+                let jump_address = Self::read_wide_offset(raw_code, instruction_address, address)?;
+                Ok((key, jump_address))
+            })
+            .collect::<Result<Vec<(i32, u32)>, ClassReaderError>>()?;
+        // The keys must be strictly increasing, since we binary search them
+        if pairs.windows(2).any(|window| window[0].0 >= window[1].0) {
+            return Err(ClassReaderError::invalid_class_data(format!(
+                "lookupswitch keys are not sorted at address {instruction_address}"
+            )));
+        }
+        Ok(LookupSwitch { default, pairs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instruction::{Instruction, LookupSwitch, TableSwitch, WideInstruction};
+
+    #[test]
+    fn can_parse_tableswitch_with_padding() {
+        let code = [
+            0x00, // nop
+            0xaa, // tableswitch
+            0x00, 0x00, // padding
+            0x00, 0x00, 0x00, 0x20, // default: +32
+            0xff, 0xff, 0xff, 0xff, // low: -1
+            0x00, 0x00, 0x00, 0x01, // high: 1
+            0x00, 0x00, 0x00, 0x1c, // -1: +28
+            0x00, 0x00, 0x00, 0x1d, // 0: +29
+            0x00, 0x00, 0x00, 0x1e, // 1: +30
+        ];
+        let (instruction, next_address) = Instruction::parse(&code, 1).unwrap();
+
+        let table_switch = TableSwitch {
+            default: 33,
+            low: -1,
+            high: 1,
+            jump_addresses: vec![29, 30, 31],
+        };
+        assert_eq!(Instruction::Tableswitch(table_switch.clone()), instruction);
+        assert_eq!(code.len(), next_address);
+        assert_eq!(29, table_switch.jump_address(-1));
+        assert_eq!(31, table_switch.jump_address(1));
+        assert_eq!(33, table_switch.jump_address(2));
+        assert_eq!(33, table_switch.jump_address(i32::MIN));
+    }
+
+    #[test]
+    fn can_parse_lookupswitch_without_padding() {
+        let code = [
+            0x00, 0x00, 0x00, // nop
+            0xab, // lookupswitch
+            0x00, 0x00, 0x00, 0x10, // default: +16
+            0x00, 0x00, 0x00, 0x02, // npairs: 2
+            0xff, 0xff, 0xff, 0x9c, // -100
+            0x00, 0x00, 0x00, 0x14, // +20
+            0x00, 0x01, 0x86, 0xa0, // 100000
+            0x00, 0x00, 0x00, 0x18, // +24
+        ];
+        let (instruction, next_address) = Instruction::parse(&code, 3).unwrap();
+
+        let lookup_switch = LookupSwitch {
+            default: 19,
+            pairs: vec![(-100, 23), (100000, 27)],
+        };
+        assert_eq!(
+            Instruction::Lookupswitch(lookup_switch.clone()),
+            instruction
+        );
+        assert_eq!(code.len(), next_address);
+        assert_eq!(23, lookup_switch.jump_address(-100));
+        assert_eq!(27, lookup_switch.jump_address(100000));
+        assert_eq!(19, lookup_switch.jump_address(0));
+    }
+
+    #[test]
+    fn cannot_parse_tableswitch_with_inverted_bounds() {
+        let code = [
+            0xaa, 0x00, 0x00, 0x00, // tableswitch + padding
+            0x00, 0x00, 0x00, 0x10, // default
+            0x00, 0x00, 0x00, 0x01, // low: 1
+            0x00, 0x00, 0x00, 0x00, // high: 0
+        ];
+        assert!(Instruction::parse(&code, 0).is_err());
+    }
+
+    #[test]
+    fn cannot_parse_lookupswitch_with_unsorted_keys() {
+        for keys in [[0x02, 0x01], [0x01, 0x01]] {
+            let code = [
+                0xab, 0x00, 0x00, 0x00, // lookupswitch + padding
+                0x00, 0x00, 0x00, 0x10, // default
+                0x00, 0x00, 0x00, 0x02, // npairs: 2
+                0x00, 0x00, 0x00, keys[0], // first key
+                0x00, 0x00, 0x00, 0x14, // +20
+                0x00, 0x00, 0x00, keys[1], // second key, not greater than the first
+                0x00, 0x00, 0x00, 0x18, // +24
+            ];
+            assert!(Instruction::parse(&code, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn can_parse_wide_instructions() {
+        let code = [
+            0xc4, 0x15, 0x01, 0x2c, // wide iload 300
+            0xc4, 0x84, 0x01, 0x00, 0xfc, 0x18, // wide iinc 256 -1000
+        ];
+
+        let (instruction, next_address) = Instruction::parse(&code, 0).unwrap();
+        assert_eq!(Instruction::Wide(WideInstruction::Iload(300)), instruction);
+        assert_eq!(4, next_address);
+
+        let (instruction, next_address) = Instruction::parse(&code, 4).unwrap();
+        assert_eq!(
+            Instruction::Wide(WideInstruction::Iinc(256, -1000)),
+            instruction
+        );
+        assert_eq!(code.len(), next_address);
+    }
+
+    #[test]
+    fn cannot_parse_wide_of_non_widenable_instruction() {
+        let code = [0xc4, 0x60, 0x00, 0x00];
+        assert!(Instruction::parse(&code, 0).is_err());
+    }
+
+    #[test]
+    fn can_parse_jsr_w() {
+        let code = [
+            0x00, // nop
+            0xc9, 0x00, 0x00, 0x00, 0x06, // jsr_w +6
+            0xa9, 0x01, // ret 1
+        ];
+        let (instruction, next_address) = Instruction::parse(&code, 1).unwrap();
+        assert_eq!(Instruction::Jsr_w(7), instruction);
+        assert_eq!(6, next_address);
+    }
+
+    #[test]
+    fn can_parse_goto_w_backwards() {
+        let code = [
+            0x00, 0x00, // nop
+            0xc8, 0xff, 0xff, 0xff, 0xfe, // goto_w -2
+        ];
+        let (instruction, next_address) = Instruction::parse(&code, 2).unwrap();
+        assert_eq!(Instruction::Goto_w(0), instruction);
+        assert_eq!(code.len(), next_address);
+    }
+
+    #[test]
+    fn can_parse_goto_w_beyond_16_bits() {
+        let code = [
+            0xc8, 0x00, 0x01, 0x00, 0x00, // goto_w +65536
+        ];
+        let (instruction, _) = Instruction::parse(&code, 0).unwrap();
+        assert_eq!(Instruction::Goto_w(65536), instruction);
+    }
+
+    #[test]
+    fn cannot_parse_jump_before_start_of_code() {
+        let code = [
+            0x00, // nop
+            0xa7, 0xff, 0xf0, // goto -16
+        ];
+        assert!(Instruction::parse(&code, 1).is_err());
+    }
 }
@@ -14,6 +14,7 @@ pub mod line_number_table;
 pub mod method_descriptor;
 pub mod method_flags;
 pub mod program_counter;
+pub mod signature;
 pub mod type_conversion;
 
 pub use attr::*;
@@ -29,4 +30,5 @@ pub use line_number_table::*;
 pub use method_descriptor::*;
 pub use method_flags::*;
 pub use program_counter::*;
+pub use signature::*;
 pub use type_conversion::*;
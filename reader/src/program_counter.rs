@@ -4,7 +4,7 @@ use std::{
 };
 
 /// Models the program counter, i.e. the address of an instruction in the bytecode of a method
-#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash)]
 pub struct ProgramCounter(pub u16);
 
 impl Display for ProgramCounter {
@@ -0,0 +1,753 @@
+use std::{fmt, fmt::Formatter, str::Chars};
+
+use crate::{
+    class::reader::error::ClassReaderError::{self, InvalidSignature},
+    field_type::BaseType,
+};
+
+/// Maximum nesting of array, class and type argument signatures. It allows for the 255
+/// dimensions of an array and its element type, but it stops crafted signatures from
+/// overflowing the stack.
+const MAX_SIGNATURE_DEPTH: usize = 256;
+
+/// Models a type in a generic signature, i.e. the `JavaTypeSignature` of the JVM specs:
+/// https://docs.oracle.com/javase/specs/jvms/se21/html/jvms-4.html#jvms-4.7.9.1
+///
+/// Formatting with `{}` renders the Java source syntax with fully qualified class names,
+/// i.e. `java.util.Map<java.lang.String, ? extends java.util.List<T>>`, while the alternate
+/// form `{:#}` uses simple names, i.e. `Map<String, ? extends List<T>>`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeSignature {
+    /// Primitive types
+    Base(BaseType),
+
+    /// A class or interface, possibly parameterized
+    Class(ClassTypeSignature),
+
+    /// A type variable, such as `T`
+    TypeVariable(String),
+
+    /// Array
+    Array(Box<TypeSignature>),
+}
+
+/// The content of a `Signature` attribute. The JVM does not check it when loading a class,
+/// since it is used only by reflection and compilers: thus a malformed signature does not
+/// make the class file invalid, and we keep its raw text instead. See
+/// https://docs.oracle.com/javase/specs/jvms/se21/html/jvms-4.html#jvms-4.7.9.1
+#[derive(Debug, Clone, PartialEq)]
+pub enum Signature<T> {
+    Parsed(T),
+    Malformed(String),
+}
+
+/// A class type, such as `java/util/Map<TK;TV;>.Entry<TK;TV;>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassTypeSignature {
+    /// The outermost class, whose name includes the package, i.e. `java/util/Map`
+    pub class: SimpleClassTypeSignature,
+    /// The chain of inner classes, i.e. `Entry` in `java/util/Map<TK;TV;>.Entry`
+    pub inner_classes: Vec<SimpleClassTypeSignature>,
+}
+
+/// One class in a [ClassTypeSignature], with its type arguments
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimpleClassTypeSignature {
+    pub name: String,
+    pub type_arguments: Vec<TypeArgument>,
+}
+
+/// A type argument of a parameterized class type
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeArgument {
+    /// A concrete type, such as `String` in `List<String>`
+    Exact(TypeSignature),
+    /// An upper bounded wildcard, such as `? extends Number`
+    Extends(TypeSignature),
+    /// A lower bounded wildcard, such as `? super Integer`
+    Super(TypeSignature),
+    /// The unbounded wildcard `?`
+    Any,
+}
+
+/// A type parameter declared by a generic class or method, such as `T extends Comparable<T>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeParameter {
+    pub name: String,
+    /// Absent when the parameter is bounded only by interfaces
+    pub class_bound: Option<TypeSignature>,
+    pub interface_bounds: Vec<TypeSignature>,
+}
+
+/// The generic signature of a class
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub superclass: ClassTypeSignature,
+    pub interfaces: Vec<ClassTypeSignature>,
+}
+
+/// The generic signature of a method
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub parameters: Vec<TypeSignature>,
+    /// Absent for `void` methods
+    pub return_type: Option<TypeSignature>,
+    /// Either class types or type variables
+    pub thrown_exceptions: Vec<TypeSignature>,
+}
+
+impl<T> Signature<T> {
+    /// Parses the content of a `Signature` attribute, keeping it as is if it is malformed
+    pub fn parse(
+        signature: String,
+        parse: impl FnOnce(&str) -> Result<T, ClassReaderError>,
+    ) -> Self {
+        match parse(&signature) {
+            Ok(parsed) => Signature::Parsed(parsed),
+            Err(_) => Signature::Malformed(signature),
+        }
+    }
+
+    /// Returns the parsed signature, unless it is malformed
+    pub fn parsed(&self) -> Option<&T> {
+        match self {
+            Signature::Parsed(parsed) => Some(parsed),
+            Signature::Malformed(_) => None,
+        }
+    }
+}
+
+impl TypeSignature {
+    /// Parses a type signature, such as the content of the `Signature` attribute of a field
+    pub fn parse(signature: &str) -> Result<TypeSignature, ClassReaderError> {
+        let mut parser = SignatureParser::new(signature);
+        let type_signature = parser.parse_java_type()?;
+        parser.expect_end()?;
+        Ok(type_signature)
+    }
+}
+
+impl ClassSignature {
+    /// Parses the content of the `Signature` attribute of a class
+    pub fn parse(signature: &str) -> Result<ClassSignature, ClassReaderError> {
+        let mut parser = SignatureParser::new(signature);
+        let type_parameters = parser.parse_type_parameters()?;
+        let superclass = parser.parse_class_type()?;
+        let mut interfaces = Vec::new();
+        while parser.peek().is_some() {
+            interfaces.push(parser.parse_class_type()?);
+        }
+        Ok(ClassSignature {
+            type_parameters,
+            superclass,
+            interfaces,
+        })
+    }
+}
+
+impl MethodSignature {
+    /// Parses the content of the `Signature` attribute of a method
+    pub fn parse(signature: &str) -> Result<MethodSignature, ClassReaderError> {
+        let mut parser = SignatureParser::new(signature);
+        let type_parameters = parser.parse_type_parameters()?;
+
+        parser.expect('(')?;
+        let mut parameters = Vec::new();
+        while parser.peek() != Some(')') {
+            parameters.push(parser.parse_java_type()?);
+        }
+        parser.expect(')')?;
+
+        let return_type = if parser.peek() == Some('V') {
+            parser.next()?;
+            None
+        } else {
+            Some(parser.parse_java_type()?)
+        };
+
+        let mut thrown_exceptions = Vec::new();
+        while parser.peek().is_some() {
+            parser.expect('^')?;
+            let exception = match parser.peek() {
+                Some('L') => TypeSignature::Class(parser.parse_class_type()?),
+                Some('T') => parser.parse_type_variable()?,
+                _ => return Err(parser.error()),
+            };
+            thrown_exceptions.push(exception);
+        }
+
+        Ok(MethodSignature {
+            type_parameters,
+            parameters,
+            return_type,
+            thrown_exceptions,
+        })
+    }
+}
+
+/// A recursive descent parser of the signature grammar
+struct SignatureParser<'a> {
+    signature: &'a str,
+    chars: Chars<'a>,
+    /// Number of reference types currently being parsed, one inside the other
+    depth: usize,
+}
+
+impl<'a> SignatureParser<'a> {
+    fn new(signature: &'a str) -> Self {
+        Self {
+            signature,
+            chars: signature.chars(),
+            depth: 0,
+        }
+    }
+
+    fn error(&self) -> ClassReaderError {
+        InvalidSignature(self.signature.to_string())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.clone().next()
+    }
+
+    fn next(&mut self) -> Result<char, ClassReaderError> {
+        self.chars.next().ok_or_else(|| self.error())
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ClassReaderError> {
+        if self.next()? == expected {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), ClassReaderError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error()),
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, ClassReaderError> {
+        let mut identifier = String::new();
+        while let Some(c) = self.peek() {
+            if matches!(c, '.' | ';' | '[' | '/' | '<' | '>' | ':') {
+                break;
+            }
+            identifier.push(c);
+            self.chars.next();
+        }
+        if identifier.is_empty() {
+            Err(self.error())
+        } else {
+            Ok(identifier)
+        }
+    }
+
+    fn parse_type_parameters(&mut self) -> Result<Vec<TypeParameter>, ClassReaderError> {
+        let mut type_parameters = Vec::new();
+        if self.peek() == Some('<') {
+            self.next()?;
+            while self.peek() != Some('>') {
+                type_parameters.push(self.parse_type_parameter()?);
+            }
+            self.next()?;
+            if type_parameters.is_empty() {
+                return Err(self.error());
+            }
+        }
+        Ok(type_parameters)
+    }
+
+    fn parse_type_parameter(&mut self) -> Result<TypeParameter, ClassReaderError> {
+        let name = self.parse_identifier()?;
+        self.expect(':')?;
+        let class_bound = match self.peek() {
+            Some('L' | 'T' | '[') => Some(self.parse_reference_type()?),
+            _ => None,
+        };
+        let mut interface_bounds = Vec::new();
+        while self.peek() == Some(':') {
+            self.next()?;
+            interface_bounds.push(self.parse_reference_type()?);
+        }
+        Ok(TypeParameter {
+            name,
+            class_bound,
+            interface_bounds,
+        })
+    }
+
+    fn parse_java_type(&mut self) -> Result<TypeSignature, ClassReaderError> {
+        let base_type = match self.peek() {
+            Some('B') => BaseType::Byte,
+            Some('C') => BaseType::Char,
+            Some('D') => BaseType::Double,
+            Some('F') => BaseType::Float,
+            Some('I') => BaseType::Int,
+            Some('J') => BaseType::Long,
+            Some('S') => BaseType::Short,
+            Some('Z') => BaseType::Boolean,
+            _ => return self.parse_reference_type(),
+        };
+        self.next()?;
+        Ok(TypeSignature::Base(base_type))
+    }
+
+    fn parse_reference_type(&mut self) -> Result<TypeSignature, ClassReaderError> {
+        if self.depth == MAX_SIGNATURE_DEPTH {
+            return Err(self.error());
+        }
+        self.depth += 1;
+        let reference_type = match self.peek() {
+            Some('L') => TypeSignature::Class(self.parse_class_type()?),
+            Some('T') => self.parse_type_variable()?,
+            Some('[') => {
+                self.next()?;
+                let component_type = self.parse_java_type()?;
+                TypeSignature::Array(Box::new(component_type))
+            }
+            _ => return Err(self.error()),
+        };
+        self.depth -= 1;
+        Ok(reference_type)
+    }
+
+    fn parse_type_variable(&mut self) -> Result<TypeSignature, ClassReaderError> {
+        self.expect('T')?;
+        let name = self.parse_identifier()?;
+        self.expect(';')?;
+        Ok(TypeSignature::TypeVariable(name))
+    }
+
+    fn parse_class_type(&mut self) -> Result<ClassTypeSignature, ClassReaderError> {
+        self.expect('L')?;
+
+        // The package specifier is folded into the name of the outermost class
+        let mut name = self.parse_identifier()?;
+        while self.peek() == Some('/') {
+            self.next()?;
+            name.push('/');
+            name.push_str(&self.parse_identifier()?);
+        }
+        let class = SimpleClassTypeSignature {
+            name,
+            type_arguments: self.parse_type_arguments()?,
+        };
+
+        let mut inner_classes = Vec::new();
+        while self.peek() == Some('.') {
+            self.next()?;
+            let name = self.parse_identifier()?;
+            inner_classes.push(SimpleClassTypeSignature {
+                name,
+                type_arguments: self.parse_type_arguments()?,
+            });
+        }
+        self.expect(';')?;
+
+        Ok(ClassTypeSignature {
+            class,
+            inner_classes,
+        })
+    }
+
+    fn parse_type_arguments(&mut self) -> Result<Vec<TypeArgument>, ClassReaderError> {
+        let mut type_arguments = Vec::new();
+        if self.peek() == Some('<') {
+            self.next()?;
+            while self.peek() != Some('>') {
+                type_arguments.push(self.parse_type_argument()?);
+            }
+            self.next()?;
+            if type_arguments.is_empty() {
+                return Err(self.error());
+            }
+        }
+        Ok(type_arguments)
+    }
+
+    fn parse_type_argument(&mut self) -> Result<TypeArgument, ClassReaderError> {
+        match self.peek() {
+            Some('*') => {
+                self.next()?;
+                Ok(TypeArgument::Any)
+            }
+            Some('+') => {
+                self.next()?;
+                Ok(TypeArgument::Extends(self.parse_reference_type()?))
+            }
+            Some('-') => {
+                self.next()?;
+                Ok(TypeArgument::Super(self.parse_reference_type()?))
+            }
+            _ => Ok(TypeArgument::Exact(self.parse_reference_type()?)),
+        }
+    }
+}
+
+/// Writes a nested value, propagating the alternate flag that selects simple class names
+fn write_nested(f: &mut Formatter<'_>, value: &impl fmt::Display) -> fmt::Result {
+    if f.alternate() {
+        write!(f, "{value:#}")
+    } else {
+        write!(f, "{value}")
+    }
+}
+
+fn write_separated<T: fmt::Display>(
+    f: &mut Formatter<'_>,
+    values: &[T],
+    separator: &str,
+) -> fmt::Result {
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            f.write_str(separator)?;
+        }
+        write_nested(f, value)?;
+    }
+    Ok(())
+}
+
+fn write_type_parameters(f: &mut Formatter<'_>, type_parameters: &[TypeParameter]) -> fmt::Result {
+    if !type_parameters.is_empty() {
+        f.write_str("<")?;
+        write_separated(f, type_parameters, ", ")?;
+        f.write_str("> ")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for TypeSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeSignature::Base(base_type) => f.write_str(base_type.to_source_name()),
+            TypeSignature::Class(class_type) => write_nested(f, class_type),
+            TypeSignature::TypeVariable(name) => f.write_str(name),
+            TypeSignature::Array(component_type) => {
+                write_nested(f, component_type.as_ref())?;
+                f.write_str("[]")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ClassTypeSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Nested classes are separated by `$` in binary names, but by `.` in the source
+        if f.alternate() {
+            let simple_name = match self.class.name.rsplit_once('/') {
+                Some((_, simple_name)) => simple_name,
+                None => &self.class.name,
+            };
+            f.write_str(&simple_name.replace('$', "."))?;
+        } else {
+            f.write_str(&self.class.name.replace(['/', '$'], "."))?;
+        }
+        write_type_arguments(f, &self.class.type_arguments)?;
+
+        for inner_class in self.inner_classes.iter() {
+            write!(f, ".{}", inner_class.name)?;
+            write_type_arguments(f, &inner_class.type_arguments)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_type_arguments(f: &mut Formatter<'_>, type_arguments: &[TypeArgument]) -> fmt::Result {
+    if !type_arguments.is_empty() {
+        f.write_str("<")?;
+        write_separated(f, type_arguments, ", ")?;
+        f.write_str(">")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for TypeArgument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeArgument::Exact(type_signature) => write_nested(f, type_signature),
+            TypeArgument::Extends(type_signature) => {
+                f.write_str("? extends ")?;
+                write_nested(f, type_signature)
+            }
+            TypeArgument::Super(type_signature) => {
+                f.write_str("? super ")?;
+                write_nested(f, type_signature)
+            }
+            TypeArgument::Any => f.write_str("?"),
+        }
+    }
+}
+
+impl fmt::Display for TypeParameter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        let bounds: Vec<&TypeSignature> = self
+            .class_bound
+            .iter()
+            .chain(self.interface_bounds.iter())
+            .collect();
+        if !bounds.is_empty() {
+            f.write_str(" extends ")?;
+            write_separated(f, &bounds, " & ")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ClassSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_type_parameters(f, &self.type_parameters)?;
+        f.write_str("extends ")?;
+        write_nested(f, &self.superclass)?;
+        if !self.interfaces.is_empty() {
+            f.write_str(" implements ")?;
+            write_separated(f, &self.interfaces, ", ")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MethodSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_type_parameters(f, &self.type_parameters)?;
+        match &self.return_type {
+            Some(return_type) => write_nested(f, return_type)?,
+            None => f.write_str("void")?,
+        }
+        f.write_str("(")?;
+        write_separated(f, &self.parameters, ", ")?;
+        f.write_str(")")?;
+        if !self.thrown_exceptions.is_empty() {
+            f.write_str(" throws ")?;
+            write_separated(f, &self.thrown_exceptions, ", ")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Signature<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Signature::Parsed(parsed) => write_nested(f, parsed),
+            Signature::Malformed(signature) => f.write_str(signature),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        class::reader::error::ClassReaderError,
+        field_type::BaseType,
+        signature::{
+            ClassSignature, ClassTypeSignature, MethodSignature, Signature,
+            SimpleClassTypeSignature, TypeArgument, TypeParameter, TypeSignature,
+        },
+    };
+
+    fn class_type(name: &str, type_arguments: Vec<TypeArgument>) -> ClassTypeSignature {
+        ClassTypeSignature {
+            class: SimpleClassTypeSignature {
+                name: name.to_string(),
+                type_arguments,
+            },
+            inner_classes: vec![],
+        }
+    }
+
+    fn type_variable(name: &str) -> TypeSignature {
+        TypeSignature::TypeVariable(name.to_string())
+    }
+
+    #[test]
+    fn can_parse_parameterized_type() {
+        assert_eq!(
+            Ok(TypeSignature::Class(class_type(
+                "java/util/Map",
+                vec![
+                    TypeArgument::Exact(TypeSignature::Class(class_type(
+                        "java/lang/String",
+                        vec![]
+                    ))),
+                    TypeArgument::Extends(TypeSignature::Class(class_type(
+                        "java/util/List",
+                        vec![TypeArgument::Exact(type_variable("T"))]
+                    ))),
+                ]
+            ))),
+            TypeSignature::parse("Ljava/util/Map<Ljava/lang/String;+Ljava/util/List<TT;>;>;")
+        );
+    }
+
+    #[test]
+    fn can_parse_wildcards_and_arrays() {
+        assert_eq!(
+            Ok(TypeSignature::Array(Box::new(TypeSignature::Class(
+                class_type(
+                    "java/util/Map",
+                    vec![
+                        TypeArgument::Any,
+                        TypeArgument::Super(TypeSignature::Array(Box::new(type_variable("E")))),
+                    ]
+                )
+            )))),
+            TypeSignature::parse("[Ljava/util/Map<*-[TE;>;")
+        );
+        assert_eq!(
+            Ok(TypeSignature::Array(Box::new(TypeSignature::Base(
+                BaseType::Int
+            )))),
+            TypeSignature::parse("[I")
+        );
+    }
+
+    #[test]
+    fn can_parse_inner_class_suffixes() {
+        let signature = TypeSignature::parse("Lrjvm/Outer<TT;>.Inner.Deepest<TU;>;").unwrap();
+        let TypeSignature::Class(class_type) = &signature else {
+            panic!("expected a class type but got {signature:?}");
+        };
+        assert_eq!("rjvm/Outer", class_type.class.name);
+        assert_eq!(
+            vec!["Inner", "Deepest"],
+            class_type
+                .inner_classes
+                .iter()
+                .map(|inner_class| inner_class.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("rjvm.Outer<T>.Inner.Deepest<U>", signature.to_string());
+        assert_eq!("Outer<T>.Inner.Deepest<U>", format!("{signature:#}"));
+    }
+
+    #[test]
+    fn can_parse_class_signature() {
+        let signature = ClassSignature::parse(
+            "<K::Ljava/lang/Comparable<TK;>;V:Ljava/lang/Object;>Ljava/util/AbstractMap<TK;TV;>;Ljava/io/Serializable;",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                TypeParameter {
+                    name: "K".to_string(),
+                    class_bound: None,
+                    interface_bounds: vec![TypeSignature::Class(class_type(
+                        "java/lang/Comparable",
+                        vec![TypeArgument::Exact(type_variable("K"))]
+                    ))],
+                },
+                TypeParameter {
+                    name: "V".to_string(),
+                    class_bound: Some(TypeSignature::Class(class_type("java/lang/Object", vec![]))),
+                    interface_bounds: vec![],
+                },
+            ],
+            signature.type_parameters
+        );
+        assert_eq!(
+            "<K extends Comparable<K>, V extends Object> extends AbstractMap<K, V> implements Serializable",
+            format!("{signature:#}")
+        );
+    }
+
+    #[test]
+    fn can_parse_method_signature() {
+        let signature = MethodSignature::parse(
+            "<T:Ljava/lang/Object;E:Ljava/lang/Exception;>(Ljava/util/List<-TT;>;I)TT;^TE;^Ljava/io/IOException;",
+        )
+        .unwrap();
+        assert_eq!(2, signature.parameters.len());
+        assert_eq!(Some(type_variable("T")), signature.return_type);
+        assert_eq!(
+            vec![
+                type_variable("E"),
+                TypeSignature::Class(class_type("java/io/IOException", vec![])),
+            ],
+            signature.thrown_exceptions
+        );
+        assert_eq!(
+            "<T extends java.lang.Object, E extends java.lang.Exception> T(java.util.List<? super T>, int) throws E, java.io.IOException",
+            signature.to_string()
+        );
+    }
+
+    #[test]
+    fn can_parse_void_method_signature() {
+        let signature = MethodSignature::parse("(Ljava/util/Set<+Ljava/lang/Number;>;)V").unwrap();
+        assert_eq!(None, signature.return_type);
+        assert_eq!("void(Set<? extends Number>)", format!("{signature:#}"));
+    }
+
+    #[test]
+    fn can_format_with_simple_names() {
+        let signature =
+            TypeSignature::parse("Ljava/util/Map<Ljava/lang/String;+Ljava/util/List<TT;>;>;")
+                .unwrap();
+        assert_eq!("Map<String, ? extends List<T>>", format!("{signature:#}"));
+        assert_eq!(
+            "java.util.Map<java.lang.String, ? extends java.util.List<T>>",
+            signature.to_string()
+        );
+    }
+
+    #[test]
+    fn cannot_parse_invalid_signatures() {
+        for signature in [
+            "",
+            "Ljava/util/List<>;",
+            "Ljava/util/List<TT;>",
+            "TT",
+            "Ljava/lang/String;I",
+            "<>Ljava/lang/Object;",
+            "(I)",
+            "(I)V^I",
+            "(I)VV",
+        ] {
+            assert_eq!(
+                Err(ClassReaderError::InvalidSignature(signature.to_string())),
+                TypeSignature::parse(signature)
+                    .map(|_| ())
+                    .or_else(|_| ClassSignature::parse(signature).map(|_| ()))
+                    .or_else(|_| MethodSignature::parse(signature).map(|_| ())),
+                "{signature} should not be valid"
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_signatures_are_kept_as_is() {
+        assert_eq!(
+            Signature::Parsed(type_variable("T")),
+            Signature::parse("TT;".to_string(), TypeSignature::parse)
+        );
+        let malformed = Signature::parse("TT".to_string(), TypeSignature::parse);
+        assert_eq!(Signature::Malformed("TT".to_string()), malformed);
+        assert_eq!(None, malformed.parsed());
+        assert_eq!("TT", malformed.to_string());
+    }
+
+    #[test]
+    fn deeply_nested_signatures_are_malformed() {
+        let arrays = TypeSignature::parse(&format!("{}I", "[".repeat(255))).unwrap();
+        assert_eq!(format!("int{}", "[]".repeat(255)), arrays.to_string());
+
+        for signature in [
+            format!("{}I", "[".repeat(20_000)),
+            format!("{}{}", "La<".repeat(5_000), ">;".repeat(5_000)),
+        ] {
+            assert_eq!(
+                Err(ClassReaderError::InvalidSignature(signature.clone())),
+                TypeSignature::parse(&signature)
+            );
+            assert_eq!(
+                Signature::Malformed(signature.clone()),
+                Signature::parse(signature, TypeSignature::parse)
+            );
+        }
+    }
+}
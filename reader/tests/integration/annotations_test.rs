@@ -0,0 +1,127 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::class::file::{Annotation, AnnotationElement, ClassFile, ElementValue};
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+fn read_annotated_class() -> ClassFile {
+    read_class_from_bytes(include_bytes!("../resources/rjvm/Annotated.class"))
+}
+
+fn marker() -> Annotation {
+    Annotation {
+        type_descriptor: "Lrjvm/Annotated$Marker;".to_string(),
+        elements: vec![],
+    }
+}
+
+fn invisible(value: &str) -> Annotation {
+    Annotation {
+        type_descriptor: "Lrjvm/Annotated$Invisible;".to_string(),
+        elements: vec![AnnotationElement {
+            name: "value".to_string(),
+            value: ElementValue::String(value.to_string()),
+        }],
+    }
+}
+
+#[test_log::test]
+fn can_read_class_annotations_with_all_element_value_kinds() {
+    let class = read_annotated_class();
+    assert!(class.invisible_annotations.is_empty());
+    assert_eq!(2, class.visible_annotations.len());
+    assert_eq!(marker(), class.visible_annotations[0]);
+
+    let everything = &class.visible_annotations[1];
+    assert_eq!("Lrjvm/Annotated$Everything;", everything.type_descriptor);
+    let expected_values = [
+        ("byteValue", ElementValue::Byte(1)),
+        ("charValue", ElementValue::Char('x' as u16)),
+        ("doubleValue", ElementValue::Double(2.5)),
+        ("floatValue", ElementValue::Float(3.5)),
+        ("intValue", ElementValue::Int(42)),
+        ("longValue", ElementValue::Long(10000000000)),
+        ("shortValue", ElementValue::Short(-7)),
+        ("booleanValue", ElementValue::Boolean(true)),
+        ("stringValue", ElementValue::String("text".to_string())),
+        (
+            "enumValue",
+            ElementValue::Enum {
+                type_descriptor: "Ljava/lang/annotation/ElementType;".to_string(),
+                constant_name: "METHOD".to_string(),
+            },
+        ),
+        (
+            "classValue",
+            ElementValue::Class("Ljava/lang/String;".to_string()),
+        ),
+        ("primitiveClassValue", ElementValue::Class("V".to_string())),
+        ("annotationValue", ElementValue::Annotation(marker())),
+        (
+            "arrayValue",
+            ElementValue::Array(vec![
+                ElementValue::String("a".to_string()),
+                ElementValue::String("b".to_string()),
+            ]),
+        ),
+    ];
+    assert_eq!(expected_values.len(), everything.elements.len());
+    for (name, value) in expected_values.iter() {
+        assert_eq!(Some(value), everything.element(name), "element {name}");
+    }
+    assert_eq!(None, everything.element("defaulted"));
+}
+
+#[test_log::test]
+fn can_read_field_annotations() {
+    let class = read_annotated_class();
+    let field = class.fields.iter().find(|f| f.name == "field").unwrap();
+    assert_eq!(
+        vec![Annotation {
+            type_descriptor: "Ljava/lang/Deprecated;".to_string(),
+            elements: vec![AnnotationElement {
+                name: "forRemoval".to_string(),
+                value: ElementValue::Boolean(true),
+            }],
+        }],
+        field.visible_annotations
+    );
+    assert_eq!(vec![invisible("field")], field.invisible_annotations);
+
+    let plain_field = class
+        .fields
+        .iter()
+        .find(|f| f.name == "plainField")
+        .unwrap();
+    assert!(plain_field.visible_annotations.is_empty());
+    assert!(plain_field.invisible_annotations.is_empty());
+}
+
+#[test_log::test]
+fn can_read_method_annotations() {
+    let class = read_annotated_class();
+    let method = class.methods.iter().find(|m| m.name == "method").unwrap();
+    assert_eq!(vec![marker()], method.visible_annotations);
+    assert_eq!(vec![invisible("method")], method.invisible_annotations);
+}
+
+#[test_log::test]
+fn can_read_annotations_on_annotation_interfaces() {
+    let class = read_class_from_bytes(include_bytes!(
+        "../resources/rjvm/Annotated$Invisible.class"
+    ));
+    assert_eq!(
+        vec![Annotation {
+            type_descriptor: "Ljava/lang/annotation/Retention;".to_string(),
+            elements: vec![AnnotationElement {
+                name: "value".to_string(),
+                value: ElementValue::Enum {
+                    type_descriptor: "Ljava/lang/annotation/RetentionPolicy;".to_string(),
+                    constant_name: "CLASS".to_string(),
+                },
+            }],
+        }],
+        class.visible_annotations
+    );
+}
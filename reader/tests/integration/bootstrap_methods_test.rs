@@ -0,0 +1,49 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::{class::file::BootstrapMethod, constant_pool::ConstantPoolEntry};
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+#[test_log::test]
+fn can_read_bootstrap_methods_attribute() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/Lambdas.class"));
+    assert_eq!(3, class.bootstrap_methods.len());
+
+    for bootstrap_method in class.bootstrap_methods.iter() {
+        assert_eq!(
+            "invokeStatic(java/lang/invoke/LambdaMetafactory.metafactory: (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;)",
+            class
+                .constants
+                .text_of(bootstrap_method.method_handle_index)
+                .unwrap()
+        );
+        assert_eq!(3, bootstrap_method.arguments.len());
+    }
+
+    let BootstrapMethod { arguments, .. } = &class.bootstrap_methods[2];
+    assert!(matches!(
+        class.constants.get(arguments[0]),
+        Ok(ConstantPoolEntry::MethodType(_))
+    ));
+    assert_eq!(
+        "invokeVirtual(java/lang/String.length: ()I)",
+        class.constants.text_of(arguments[1]).unwrap()
+    );
+    assert_eq!(
+        "(Ljava/lang/String;)Ljava/lang/Integer;",
+        class.constants.text_of(arguments[2]).unwrap()
+    );
+}
+
+#[test_log::test]
+fn bootstrap_methods_are_displayed() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/Lambdas.class"));
+    let text = class.to_string();
+    assert!(text.contains(
+        "bootstrap methods:\n  - 0: invokeStatic(java/lang/invoke/LambdaMetafactory.metafactory: "
+    ));
+    assert!(text.contains(
+        "  - 2: invokeStatic(java/lang/invoke/LambdaMetafactory.metafactory: (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;) arguments: [\"(Ljava/lang/Object;)Ljava/lang/Object;\", \"invokeVirtual(java/lang/String.length: ()I)\", \"(Ljava/lang/String;)Ljava/lang/Integer;\"]\n"
+    ));
+}
@@ -20,6 +20,11 @@ fn can_read_constants() {
                 type_descriptor: FieldType::Base(BaseType::Int),
                 constant_value: Some(FieldConstantValue::Int(2023)),
                 deprecated: false,
+                signature: None,
+                visible_annotations: vec![],
+                invisible_annotations: vec![],
+                visible_type_annotations: vec![],
+                invisible_type_annotations: vec![],
             },
             ClassFileField {
                 flags: FieldFlags::PROTECTED | FieldFlags::STATIC | FieldFlags::FINAL,
@@ -27,6 +32,11 @@ fn can_read_constants() {
                 type_descriptor: FieldType::Base(BaseType::Float),
                 constant_value: Some(FieldConstantValue::Float(20.23)),
                 deprecated: false,
+                signature: None,
+                visible_annotations: vec![],
+                invisible_annotations: vec![],
+                visible_type_annotations: vec![],
+                invisible_type_annotations: vec![],
             },
             ClassFileField {
                 flags: FieldFlags::PRIVATE | FieldFlags::STATIC | FieldFlags::FINAL,
@@ -34,6 +44,11 @@ fn can_read_constants() {
                 type_descriptor: FieldType::Base(BaseType::Long),
                 constant_value: Some(FieldConstantValue::Long(2023)),
                 deprecated: false,
+                signature: None,
+                visible_annotations: vec![],
+                invisible_annotations: vec![],
+                visible_type_annotations: vec![],
+                invisible_type_annotations: vec![],
             },
             ClassFileField {
                 flags: FieldFlags::PUBLIC | FieldFlags::STATIC | FieldFlags::FINAL,
@@ -41,6 +56,11 @@ fn can_read_constants() {
                 type_descriptor: FieldType::Base(BaseType::Double),
                 constant_value: Some(FieldConstantValue::Double(20.23)),
                 deprecated: false,
+                signature: None,
+                visible_annotations: vec![],
+                invisible_annotations: vec![],
+                visible_type_annotations: vec![],
+                invisible_type_annotations: vec![],
             },
             ClassFileField {
                 flags: FieldFlags::PUBLIC | FieldFlags::STATIC | FieldFlags::FINAL,
@@ -48,6 +68,11 @@ fn can_read_constants() {
                 type_descriptor: FieldType::Object("java/lang/String".to_string()),
                 constant_value: Some(FieldConstantValue::String("2023".to_string())),
                 deprecated: false,
+                signature: None,
+                visible_annotations: vec![],
+                invisible_annotations: vec![],
+                visible_type_annotations: vec![],
+                invisible_type_annotations: vec![],
             }
         ),
         class.fields
@@ -8,7 +8,7 @@ fn can_read_deprecated_attribute() {
     let class = read_class_from_bytes(include_bytes!("../resources/rjvm/DeprecatedClass.class"));
     assert!(class.deprecated);
 
-    class.fields.get(0).unwrap();
+    class.fields.first().unwrap();
 
     let field = class
         .fields
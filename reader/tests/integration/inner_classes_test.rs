@@ -0,0 +1,118 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::class::{
+    access_flags::InnerClassAccessFlags,
+    file::{EnclosingMethod, InnerClass},
+};
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+#[test_log::test]
+fn can_read_inner_classes_attribute() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/NestedClasses.class"));
+    assert_eq!(None, class.enclosing_method);
+    assert_eq!(
+        vec![
+            InnerClass {
+                inner_class: "rjvm/NestedClasses$2".to_string(),
+                outer_class: None,
+                name: None,
+                flags: InnerClassAccessFlags::empty(),
+            },
+            InnerClass {
+                inner_class: "rjvm/NestedClasses$1Local".to_string(),
+                outer_class: None,
+                name: Some("Local".to_string()),
+                flags: InnerClassAccessFlags::FINAL,
+            },
+            InnerClass {
+                inner_class: "rjvm/NestedClasses$1".to_string(),
+                outer_class: None,
+                name: None,
+                flags: InnerClassAccessFlags::empty(),
+            },
+            InnerClass {
+                inner_class: "rjvm/NestedClasses$InnerMember".to_string(),
+                outer_class: Some("rjvm/NestedClasses".to_string()),
+                name: Some("InnerMember".to_string()),
+                flags: InnerClassAccessFlags::PROTECTED,
+            },
+            InnerClass {
+                inner_class: "rjvm/NestedClasses$StaticMember".to_string(),
+                outer_class: Some("rjvm/NestedClasses".to_string()),
+                name: Some("StaticMember".to_string()),
+                flags: InnerClassAccessFlags::PUBLIC | InnerClassAccessFlags::STATIC,
+            },
+            InnerClass {
+                inner_class: "rjvm/NestedClasses$InnerMember$Deep".to_string(),
+                outer_class: Some("rjvm/NestedClasses$InnerMember".to_string()),
+                name: Some("Deep".to_string()),
+                flags: InnerClassAccessFlags::PRIVATE
+                    | InnerClassAccessFlags::STATIC
+                    | InnerClassAccessFlags::INTERFACE
+                    | InnerClassAccessFlags::ABSTRACT,
+            },
+        ],
+        class.inner_classes
+    );
+}
+
+#[test_log::test]
+fn can_read_inner_class_flags_of_nested_member() {
+    let class = read_class_from_bytes(include_bytes!(
+        "../resources/rjvm/NestedClasses$InnerMember$Deep.class"
+    ));
+    let deep = class
+        .inner_classes
+        .iter()
+        .find(|inner_class| inner_class.inner_class == class.name)
+        .unwrap();
+    assert_eq!(
+        Some("rjvm/NestedClasses$InnerMember"),
+        deep.outer_class.as_deref()
+    );
+    assert_eq!(Some("Deep"), deep.name.as_deref());
+    assert_eq!(
+        InnerClassAccessFlags::PRIVATE
+            | InnerClassAccessFlags::STATIC
+            | InnerClassAccessFlags::INTERFACE
+            | InnerClassAccessFlags::ABSTRACT,
+        deep.flags
+    );
+}
+
+#[test_log::test]
+fn can_read_enclosing_method_of_local_class() {
+    let class = read_class_from_bytes(include_bytes!(
+        "../resources/rjvm/NestedClasses$1Local.class"
+    ));
+    assert_eq!(
+        Some(EnclosingMethod {
+            class: "rjvm/NestedClasses".to_string(),
+            method: Some(("local".to_string(), "()Ljava/lang/Object;".to_string())),
+        }),
+        class.enclosing_method
+    );
+}
+
+#[test_log::test]
+fn can_read_enclosing_method_of_anonymous_class_in_initializer() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/NestedClasses$1.class"));
+    assert_eq!(
+        Some(EnclosingMethod {
+            class: "rjvm/NestedClasses".to_string(),
+            method: None,
+        }),
+        class.enclosing_method
+    );
+    assert_eq!(None, class.inner_classes[0].name);
+}
+
+#[test_log::test]
+fn member_classes_have_no_enclosing_method() {
+    let class = read_class_from_bytes(include_bytes!(
+        "../resources/rjvm/NestedClasses$StaticMember.class"
+    ));
+    assert_eq!(None, class.enclosing_method);
+}
@@ -1,6 +1,11 @@
+mod annotations_test;
 mod assertions;
+mod bootstrap_methods_test;
 mod constants_class_test;
 mod deprecated_class_test;
 mod exceptions;
+mod inner_classes_test;
 mod pojo_class_test;
+mod signatures_test;
+mod type_annotations_test;
 mod utils;
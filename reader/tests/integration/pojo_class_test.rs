@@ -48,6 +48,11 @@ fn check_fields(class: &ClassFile) {
                 type_descriptor: FieldType::Base(BaseType::Double),
                 constant_value: None,
                 deprecated: false,
+                signature: None,
+                visible_annotations: vec![],
+                invisible_annotations: vec![],
+                visible_type_annotations: vec![],
+                invisible_type_annotations: vec![],
             },
             ClassFileField {
                 flags: FieldFlags::PRIVATE | FieldFlags::FINAL,
@@ -55,6 +60,11 @@ fn check_fields(class: &ClassFile) {
                 type_descriptor: FieldType::Base(BaseType::Double),
                 constant_value: None,
                 deprecated: false,
+                signature: None,
+                visible_annotations: vec![],
+                invisible_annotations: vec![],
+                visible_type_annotations: vec![],
+                invisible_type_annotations: vec![],
             }
         ),
         class.fields
@@ -0,0 +1,94 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::{
+    class::file::ClassFile,
+    signature::{Signature, TypeArgument, TypeSignature},
+};
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+fn read_generics_class() -> ClassFile {
+    read_class_from_bytes(include_bytes!("../resources/rjvm/Generics.class"))
+}
+
+#[test_log::test]
+fn can_read_class_signature() {
+    let class = read_generics_class();
+    let signature = class
+        .signature
+        .as_ref()
+        .and_then(Signature::parsed)
+        .unwrap();
+    assert_eq!(2, signature.type_parameters.len());
+    assert_eq!("java/util/AbstractMap", signature.superclass.class.name);
+    assert_eq!(
+        "<K extends Comparable<K>, V extends Object> extends AbstractMap<K, V> implements Comparable<Generics<K, ?>>",
+        format!("{signature:#}")
+    );
+}
+
+#[test_log::test]
+fn can_read_field_signatures() {
+    let class = read_generics_class();
+    let field_signature = |name: &str| {
+        class
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .unwrap()
+            .signature
+            .as_ref()
+            .map(|signature| format!("{signature:#}"))
+    };
+
+    assert_eq!(
+        Some("Map<String, ? extends List<V>>".to_string()),
+        field_signature("index")
+    );
+    assert_eq!(
+        Some("Generics<K, V>.Node<String>[]".to_string()),
+        field_signature("nodes")
+    );
+    assert_eq!(None, field_signature("count"));
+}
+
+#[test_log::test]
+fn can_read_method_signatures() {
+    let class = read_generics_class();
+    let method_signature = |name: &str| {
+        class
+            .methods
+            .iter()
+            .find(|method| method.name == name)
+            .unwrap()
+            .signature
+            .as_ref()
+            .and_then(Signature::parsed)
+            .cloned()
+    };
+
+    let lookup = method_signature("lookup").unwrap();
+    assert_eq!(
+        vec![TypeSignature::TypeVariable("E".to_string())],
+        lookup.thrown_exceptions[..1]
+    );
+    assert_eq!(
+        "<E extends java.lang.Exception> V(java.util.List<? super K>, int) throws E, java.io.IOException",
+        lookup.to_string()
+    );
+
+    let entry_set = method_signature("entrySet").unwrap();
+    let Some(TypeSignature::Class(set)) = &entry_set.return_type else {
+        panic!("unexpected return type {:?}", entry_set.return_type);
+    };
+    let TypeArgument::Exact(TypeSignature::Class(entry)) = &set.class.type_arguments[0] else {
+        panic!("unexpected type argument {:?}", set.class.type_arguments);
+    };
+    // Inner class suffixes are only used when the outer class is parameterized
+    assert_eq!("java/util/Map$Entry", entry.class.name);
+    assert!(entry.inner_classes.is_empty());
+    assert_eq!("Set<Map.Entry<K, V>>()", format!("{entry_set:#}"));
+
+    assert_eq!(None, method_signature("size"));
+}
@@ -0,0 +1,157 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::{
+    class::file::{
+        Annotation, AnnotationElement, ClassFile, ClassFileMethod, ElementValue,
+        LocalVariableRange, TypeAnnotation, TypeAnnotationTarget, TypePathEntry,
+    },
+    program_counter::ProgramCounter,
+};
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+fn read_type_annotated_class() -> ClassFile {
+    read_class_from_bytes(include_bytes!("../resources/rjvm/TypeAnnotated.class"))
+}
+
+fn annotation(type_descriptor: &str) -> Annotation {
+    Annotation {
+        type_descriptor: type_descriptor.to_string(),
+        elements: vec![],
+    }
+}
+
+fn non_null(target: TypeAnnotationTarget, type_path: Vec<TypePathEntry>) -> TypeAnnotation {
+    TypeAnnotation {
+        target,
+        type_path,
+        annotation: annotation("Lrjvm/NonNull;"),
+    }
+}
+
+fn find_method<'a>(class: &'a ClassFile, name: &str) -> &'a ClassFileMethod {
+    class.methods.iter().find(|m| m.name == name).unwrap()
+}
+
+#[test_log::test]
+fn can_read_class_type_annotations() {
+    let class = read_type_annotated_class();
+    assert_eq!(
+        vec![
+            non_null(TypeAnnotationTarget::Supertype(Some(0)), vec![]),
+            non_null(TypeAnnotationTarget::ClassTypeParameter(0), vec![]),
+        ],
+        class.visible_type_annotations
+    );
+    assert!(class.invisible_type_annotations.is_empty());
+}
+
+#[test_log::test]
+fn can_read_field_type_annotations_with_paths() {
+    let class = read_type_annotated_class();
+    let field = |name: &str| class.fields.iter().find(|f| f.name == name).unwrap();
+
+    // `@NonNull String[]` annotates the element type, `String @NonNull []` the array itself
+    assert_eq!(
+        vec![non_null(
+            TypeAnnotationTarget::Field,
+            vec![TypePathEntry::Array]
+        )],
+        field("names").visible_type_annotations
+    );
+    assert_eq!(
+        vec![non_null(TypeAnnotationTarget::Field, vec![])],
+        field("array").visible_type_annotations
+    );
+
+    let numbers = field("numbers");
+    assert!(numbers.visible_type_annotations.is_empty());
+    assert_eq!(
+        vec![TypeAnnotation {
+            target: TypeAnnotationTarget::Field,
+            type_path: vec![TypePathEntry::TypeArgument(0), TypePathEntry::WildcardBound],
+            annotation: annotation("Lrjvm/Tainted;"),
+        }],
+        numbers.invisible_type_annotations
+    );
+}
+
+#[test_log::test]
+fn can_read_method_and_code_type_annotations() {
+    let class = read_type_annotated_class();
+    let greet = find_method(&class, "greet");
+    assert_eq!(
+        vec![non_null(TypeAnnotationTarget::Throws(0), vec![])],
+        greet.visible_type_annotations
+    );
+
+    let code = greet.code.as_ref().unwrap();
+    assert_eq!(
+        vec![non_null(
+            TypeAnnotationTarget::LocalVariable(vec![LocalVariableRange {
+                range: ProgramCounter(3)..ProgramCounter(20),
+                index: 4,
+            }]),
+            vec![]
+        )],
+        code.visible_type_annotations
+    );
+    assert_eq!(
+        vec![TypeAnnotation {
+            target: TypeAnnotationTarget::Cast {
+                offset: ProgramCounter(10),
+                type_argument_index: 0,
+            },
+            type_path: vec![],
+            annotation: annotation("Lrjvm/Tainted;"),
+        }],
+        code.invisible_type_annotations
+    );
+}
+
+#[test_log::test]
+fn can_read_parameter_annotations() {
+    let class = read_type_annotated_class();
+    let greet = find_method(&class, "greet");
+    assert_eq!(
+        vec![
+            vec![Annotation {
+                type_descriptor: "Lrjvm/Named;".to_string(),
+                elements: vec![AnnotationElement {
+                    name: "value".to_string(),
+                    value: ElementValue::String("first".to_string()),
+                }],
+            }],
+            vec![],
+            vec![
+                annotation("Ljava/lang/Deprecated;"),
+                annotation("Lrjvm/Named;")
+            ],
+        ],
+        greet.visible_parameter_annotations
+    );
+    assert!(greet.invisible_parameter_annotations.is_empty());
+    assert!(find_method(&class, "<init>")
+        .visible_parameter_annotations
+        .is_empty());
+}
+
+#[test_log::test]
+fn can_read_annotation_default() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/Named.class"));
+    assert_eq!(
+        Some(ElementValue::String("unnamed".to_string())),
+        find_method(&class, "value").annotation_default
+    );
+    assert_eq!(
+        Some(ElementValue::Array(vec![
+            ElementValue::Int(1),
+            ElementValue::Int(2)
+        ])),
+        find_method(&class, "priorities").annotation_default
+    );
+
+    let class = read_type_annotated_class();
+    assert_eq!(None, find_method(&class, "greet").annotation_default);
+}
@@ -0,0 +1,62 @@
+package rjvm;
+
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+@Annotated.Marker
+@Annotated.Everything(
+        byteValue = 1,
+        charValue = 'x',
+        doubleValue = 2.5,
+        floatValue = 3.5f,
+        intValue = 42,
+        longValue = 10000000000L,
+        shortValue = -7,
+        booleanValue = true,
+        stringValue = "text",
+        enumValue = ElementType.METHOD,
+        classValue = String.class,
+        primitiveClassValue = void.class,
+        annotationValue = @Annotated.Marker,
+        arrayValue = {"a", "b"})
+public class Annotated {
+    @Retention(RetentionPolicy.RUNTIME)
+    public @interface Marker {
+    }
+
+    @Retention(RetentionPolicy.CLASS)
+    public @interface Invisible {
+        String value();
+    }
+
+    @Retention(RetentionPolicy.RUNTIME)
+    public @interface Everything {
+        byte byteValue();
+        char charValue();
+        double doubleValue();
+        float floatValue();
+        int intValue();
+        long longValue();
+        short shortValue();
+        boolean booleanValue();
+        String stringValue();
+        ElementType enumValue();
+        Class<?> classValue();
+        Class<?> primitiveClassValue();
+        Marker annotationValue();
+        String[] arrayValue();
+        int defaulted() default 5;
+    }
+
+    @Deprecated(forRemoval = true)
+    @Invisible("field")
+    public int field;
+
+    public int plainField;
+
+    @Marker
+    @Invisible("method")
+    public void method() {
+    }
+}
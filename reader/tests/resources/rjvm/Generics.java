@@ -0,0 +1,25 @@
+package rjvm;
+
+import java.io.IOException;
+import java.util.AbstractMap;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+
+public abstract class Generics<K extends Comparable<K>, V> extends AbstractMap<K, V>
+        implements Comparable<Generics<K, ?>> {
+    public class Node<T> {
+    }
+
+    private Map<String, ? extends List<V>> index;
+    private Generics<K, V>.Node<String>[] nodes;
+    private int count;
+
+    public abstract <E extends Exception> V lookup(List<? super K> keys, int limit) throws E, IOException;
+
+    public abstract Set<Map.Entry<K, V>> entrySet();
+
+    public int size() {
+        return count;
+    }
+}
@@ -0,0 +1,12 @@
+package rjvm;
+
+import java.util.function.Function;
+import java.util.function.Supplier;
+
+public class Lambdas {
+    public static void main(String[] args) {
+        Supplier<String> supplier = () -> "hello";
+        Function<Integer, Integer> increment = x -> x + 1;
+        Function<String, Integer> length = String::length;
+    }
+}
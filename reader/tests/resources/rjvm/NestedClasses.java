@@ -0,0 +1,27 @@
+package rjvm;
+
+public class NestedClasses {
+    private static final Runnable INITIALIZER = new Runnable() {
+        public void run() {
+        }
+    };
+
+    public static class StaticMember {
+    }
+
+    protected class InnerMember {
+        private interface Deep {
+        }
+    }
+
+    Object anonymous() {
+        return new Object() {
+        };
+    }
+
+    Object local() {
+        final class Local {
+        }
+        return new Local();
+    }
+}
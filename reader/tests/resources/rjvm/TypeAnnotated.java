@@ -0,0 +1,41 @@
+package rjvm;
+
+import java.io.Serializable;
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+import java.lang.annotation.Target;
+import java.util.List;
+
+public class TypeAnnotated<@NonNull T> implements @NonNull Serializable {
+    @NonNull String[] names;
+
+    String @NonNull [] array;
+
+    List<? extends @Tainted Number> numbers;
+
+    public String greet(@Named("first") String first, String second, @Deprecated @Named int third)
+            throws @NonNull IllegalStateException {
+        @NonNull String local = first;
+        Object object = second;
+        return local + (@Tainted String) object + third;
+    }
+}
+
+@Retention(RetentionPolicy.RUNTIME)
+@Target(ElementType.TYPE_USE)
+@interface NonNull {
+}
+
+@Retention(RetentionPolicy.CLASS)
+@Target(ElementType.TYPE_USE)
+@interface Tainted {
+}
+
+@Retention(RetentionPolicy.RUNTIME)
+@Target(ElementType.PARAMETER)
+@interface Named {
+    String value() default "unnamed";
+
+    int[] priorities() default {1, 2};
+}
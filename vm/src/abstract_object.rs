@@ -94,14 +94,31 @@ pub(crate) struct AllocHeader {
     pub(crate) size: usize,
 }
 
-/// The second word of an allocated "classical" object
+/// Identifies an object for its whole lifetime. Unlike the identity hash code, which is
+/// derived from the address, it is never reused for another object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(transparent)]
+pub struct ObjectId(u64);
+
+impl ObjectId {
+    pub fn new(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+/// The header of an allocated "classical" object, following the [AllocHeader]
+#[repr(C)]
 struct ObjectHeader {
     class_id: ClassId,
+    id: ObjectId,
 }
 
-/// The second word of an allocated array
+/// The header of an allocated array. Starts with the class id and the object id, exactly
+/// like [ObjectHeader], so that we can read them in the same way for any object.
+#[repr(C)]
 struct ArrayHeader {
+    class_id: ClassId,
+    id: ObjectId,
     elements_type: ArrayEntryType,
     length: u32,
 }
@@ -133,30 +150,35 @@ impl<'a> AbstractObject<'a> {
         ALLOC_HEADER_SIZE + ARRAY_HEADER_SIZE + length * 8
     }
 
-    pub fn new_object(class: &Class<'a>, alloc_entry: AllocEntry) -> Self {
-        Self::write_object_header(class, &alloc_entry);
+    pub fn new_object(class: &Class<'a>, id: ObjectId, alloc_entry: AllocEntry) -> Self {
+        Self::write_object_header(class, id, &alloc_entry);
         Self {
             data: alloc_entry.ptr,
             marker: PhantomData,
         }
     }
 
-    fn write_object_header(class: &Class, alloc_entry: &AllocEntry) {
+    fn write_object_header(class: &Class, id: ObjectId, alloc_entry: &AllocEntry) {
         unsafe {
             let next_ptr = Self::write_alloc_header(alloc_entry, ObjectKind::Object);
             std::ptr::write(
                 next_ptr as *mut ObjectHeader,
-                ObjectHeader { class_id: class.id },
+                ObjectHeader {
+                    class_id: class.id,
+                    id,
+                },
             );
         }
     }
 
     pub fn new_array(
+        array_class: &Class<'a>,
+        id: ObjectId,
         elements_type: ArrayEntryType,
         array_length: usize,
         alloc_entry: &AllocEntry,
     ) -> Self {
-        Self::write_array_header(elements_type, array_length, alloc_entry);
+        Self::write_array_header(array_class, id, elements_type, array_length, alloc_entry);
         Self {
             data: alloc_entry.ptr,
             marker: PhantomData,
@@ -164,6 +186,8 @@ impl<'a> AbstractObject<'a> {
     }
 
     fn write_array_header(
+        array_class: &Class,
+        id: ObjectId,
         elements_type: ArrayEntryType,
         array_length: usize,
         alloc_entry: &AllocEntry,
@@ -173,6 +197,8 @@ impl<'a> AbstractObject<'a> {
             std::ptr::write(
                 next_ptr as *mut ArrayHeader,
                 ArrayHeader {
+                    class_id: array_class.id,
+                    id,
                     elements_type,
                     length: array_length as u32,
                 },
@@ -213,6 +239,12 @@ impl<'a> AbstractObject<'a> {
         self.alloc_header().identity_hash_code()
     }
 
+    /// The unique id of the object. Since arrays start with the same header as objects,
+    /// this works for both kinds.
+    pub fn id(&self) -> ObjectId {
+        self.object_header().id
+    }
+
     pub fn kind(&self) -> ObjectKind {
         self.alloc_header().kind()
     }
@@ -235,7 +267,8 @@ impl<'a> Debug for AbstractObject<'a> {
             ObjectKind::Object => write!(f, " class_id {}", self.class_id()),
             ObjectKind::Array => write!(
                 f,
-                " elements type {:?} len {}",
+                " class_id {} elements type {:?} len {}",
+                self.class_id(),
                 self.elements_type(),
                 self.len()
             ),
@@ -254,7 +287,7 @@ fn identity_hash_code(ptr: *mut u8) -> i32 {
     // since we'll store this in AllocHeader!
     let hash = (hash & ((1 << 30) - 1)) as u32;
 
-    unsafe { std::mem::transmute(hash) }
+    hash as i32
 }
 
 unsafe fn write_value(ptr: *mut u8, value: Value) {
@@ -265,6 +298,7 @@ unsafe fn write_value(ptr: *mut u8, value: Value) {
         Value::Double(double) => std::ptr::write(ptr as *mut f64, double),
         Value::Uninitialized | Value::Null => std::ptr::write(ptr as *mut u64, 0),
         Value::Object(obj) => std::ptr::write(ptr as *mut AbstractObject, obj),
+        Value::ReturnAddress(pc) => std::ptr::write(ptr as *mut u64, pc.0 as u64),
     }
 }
 
@@ -296,7 +330,7 @@ unsafe fn read_value2<'a>(ptr: *const u8, field_type: &ArrayEntryType) -> Value<
         ArrayEntryType::Base(BaseType::Long) => Value::Long(std::ptr::read(ptr as *const i64)),
         ArrayEntryType::Base(BaseType::Float) => Value::Float(std::ptr::read(ptr as *const f32)),
         ArrayEntryType::Base(BaseType::Double) => Value::Double(std::ptr::read(ptr as *const f64)),
-        ArrayEntryType::Object(_) | ArrayEntryType::Array => {
+        ArrayEntryType::Object(_) | ArrayEntryType::Array(..) => {
             match std::ptr::read(ptr as *const i64) {
                 0 => Value::Null,
                 _ => Value::Object(std::ptr::read(ptr as *const AbstractObject)),
@@ -321,6 +355,18 @@ impl<'a> AbstractObject<'a> {
         let offset = ALLOC_HEADER_SIZE + OBJECT_HEADER_SIZE + preceding_fields_size;
         self.data.add(offset)
     }
+
+    /// Copies the values of all the fields of the given object, which must be an
+    /// instance of the same class
+    pub(crate) fn copy_fields_from(&self, source: &AbstractObject<'a>, class: &Class) {
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                source.ptr_to_field_value(0),
+                self.ptr_to_field_value(0),
+                8 * class.num_total_fields,
+            );
+        }
+    }
 }
 
 impl<'a> Object<'a> for AbstractObject<'a> {
@@ -373,7 +419,7 @@ impl<'a> Array<'a> for AbstractObject<'a> {
 
     fn set_element(&self, index: usize, value: Value<'a>) -> Result<(), VmError> {
         if index >= self.len().into_usize_safe() {
-            Err(VmError::ArrayIndexOutOfBoundsException)
+            Err(VmError::array_index_out_of_bounds(index as i32, self.len()))
         } else {
             unsafe {
                 let ptr = self.ptr_to_array_element(index);
@@ -385,7 +431,7 @@ impl<'a> Array<'a> for AbstractObject<'a> {
 
     fn get_element(&self, index: usize) -> Result<Value<'a>, VmError> {
         if index >= self.len().into_usize_safe() {
-            Err(VmError::ArrayIndexOutOfBoundsException)
+            Err(VmError::array_index_out_of_bounds(index as i32, self.len()))
         } else {
             unsafe {
                 let ptr = self.ptr_to_array_element(index);
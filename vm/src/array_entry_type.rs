@@ -12,12 +12,56 @@ use crate::class::{resolver::ClassByIdResolver, ClassId};
 pub enum ArrayEntryType {
     Base(BaseType),
     Object(ClassId),
-    // Note: here we would have to keep the sub-element type. Not doing this means that we do not
-    // correctly support arrays of arrays!
-    Array,
+    /// The entries are arrays themselves. Since this type cannot be recursive, we store the
+    /// type of the innermost elements and the number of dimensions of the entries, i.e.
+    /// the entries of `int[][][]` have type `Array(ArrayLeafType::Base(BaseType::Int), 2)`.
+    Array(ArrayLeafType, u8),
+}
+
+/// The type of the innermost elements of a multi-dimensional array
+#[derive(PartialEq, Clone, Debug)]
+pub enum ArrayLeafType {
+    Base(BaseType),
+    Object(ClassId),
+}
+
+impl From<ArrayLeafType> for ArrayEntryType {
+    fn from(value: ArrayLeafType) -> Self {
+        match value {
+            ArrayLeafType::Base(base_type) => ArrayEntryType::Base(base_type),
+            ArrayLeafType::Object(class_id) => ArrayEntryType::Object(class_id),
+        }
+    }
 }
 
 impl ArrayEntryType {
+    /// Returns the type of an array whose entries are arrays of this type,
+    /// i.e. for `int` it returns `int[]`
+    pub fn array_of(self) -> ArrayEntryType {
+        match self {
+            ArrayEntryType::Base(base_type) => {
+                ArrayEntryType::Array(ArrayLeafType::Base(base_type), 1)
+            }
+            ArrayEntryType::Object(class_id) => {
+                ArrayEntryType::Array(ArrayLeafType::Object(class_id), 1)
+            }
+            ArrayEntryType::Array(leaf_type, dimensions) => {
+                ArrayEntryType::Array(leaf_type, dimensions + 1)
+            }
+        }
+    }
+
+    /// If the entries are arrays, returns the type of their entries
+    pub fn component_type(&self) -> Option<ArrayEntryType> {
+        match self {
+            ArrayEntryType::Array(leaf_type, 1) => Some(leaf_type.clone().into()),
+            ArrayEntryType::Array(leaf_type, dimensions) => {
+                Some(ArrayEntryType::Array(leaf_type.clone(), dimensions - 1))
+            }
+            _ => None,
+        }
+    }
+
     pub fn into_field_type<'a>(
         self,
         class_resolver: &impl ClassByIdResolver<'a>,
@@ -27,8 +71,11 @@ impl ArrayEntryType {
             ArrayEntryType::Object(class_id) => class_resolver
                 .find_class_by_id(class_id)
                 .map(|class| FieldType::Object(class.name.clone())),
-            ArrayEntryType::Array => {
-                todo!("Arrays of arrays are not supported at the moment")
+            ArrayEntryType::Array(leaf_type, dimensions) => {
+                let leaf_type = ArrayEntryType::from(leaf_type).into_field_type(class_resolver)?;
+                Some((0..dimensions).fold(leaf_type, |field_type, _| {
+                    FieldType::Array(Box::new(field_type))
+                }))
             }
         }
     }
@@ -1,13 +1,12 @@
-use log::warn;
-
 use rjvm_reader::{
     constant_pool::ConstantPoolEntry,
     field_type::{
         BaseType,
         FieldType::{self, Base},
     },
-    instruction::{Instruction, NewArrayType},
+    instruction::{Instruction, NewArrayType, WideInstruction},
     line_number::LineNumber,
+    method_descriptor::MethodDescriptor,
     program_counter::ProgramCounter,
     type_conversion::ToUsizeSafe,
     ClassFileField, ClassFileMethod,
@@ -18,14 +17,25 @@ use crate::{
     array::Array,
     array_entry_type::ArrayEntryType,
     call_frame::InstructionCompleted::{ContinueMethodExecution, ReturnFromMethod},
-    call_stack::{self, CallStack},
-    class::{resolver::ClassByIdResolver, Class},
+    call_stack::CallStack,
+    class::{resolver::ClassByIdResolver, Class, ClassRef},
     class_and_method::ClassAndMethod,
+    class_mirror::MirroredType,
+    direct_method_handle::{
+        convert_value, default_value, describe_method_type, DirectMethodHandle,
+    },
     exceptions::{JavaException, MethodCallFailed},
-    handle::MethodHandle,
-    java_objects_creation::{new_java_lang_class_object, new_java_lang_string_object},
+    handle::MethodHandleKind,
+    invoke_dynamic::{
+        get_name_and_type, link_call_site, parse_method_descriptor, CallSite, CallSiteId,
+    },
+    java_objects_creation::{
+        get_java_lang_class_object_of_type, new_java_lang_invoke_method_handle_object,
+        new_java_lang_invoke_method_type_object, new_java_lang_string_object,
+    },
     object::Object,
     stack_trace_element::StackTraceElement,
+    string_concat::render_argument,
     value::Value::{self, Double, Float, Int, Long, Null},
     value_stack::ValueStack,
     vm::Vm,
@@ -37,7 +47,7 @@ use crate::{
 /// - for failures: a MethodCallFailed error
 pub type MethodCallResult<'a> = Result<Option<Value<'a>>, MethodCallFailed<'a>>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 struct MethodReference<'a> {
     class_name: &'a str,
     method_name: &'a str,
@@ -205,7 +215,10 @@ macro_rules! generate_execute_store {
             let value = self.pop()?;
             match value {
                 $variant(..) => {
-                    self.locals[index] = value;
+                    *self
+                        .locals
+                        .get_mut(index)
+                        .ok_or(VmError::ValidationException)? = value;
                     Ok(())
                 }
                 _ => Err(MethodCallFailed::InternalError(
@@ -218,10 +231,11 @@ macro_rules! generate_execute_store {
 
 /// Pops the index and the array and pushes the element at the index
 macro_rules! generate_execute_array_load {
-    ($name:ident, $($variant:pat),+) => {
+    ($name:ident, $type_name:literal, $($variant:pat),+) => {
         fn $name(&mut self) -> Result<(), MethodCallFailed<'a>> {
-            let index = self.pop_int()?.into_usize_safe();
-            let array = self.pop_array()?;
+            let index = self.pop_int()?;
+            let array = self.pop_array(concat!("Cannot load from ", $type_name, " array"))?;
+            let index = Self::array_index(&array, index)?;
             let value = match array.elements_type() {
                 $($variant => {
                     array.get_element(index)
@@ -235,11 +249,12 @@ macro_rules! generate_execute_array_load {
 
 /// Pops the value, the index, and the array, and sets the element at the index
 macro_rules! generate_execute_array_store {
-    ($name:ident, $pop_fn:ident, $map_fn:ident, $($variant:pat),+) => {
+    ($name:ident, $type_name:literal, $pop_fn:ident, $map_fn:ident, $($variant:pat),+) => {
         fn $name(&mut self) -> Result<(), MethodCallFailed<'a>> {
             let value = Self::$map_fn(self.$pop_fn()?);
-            let index = self.pop_int()?.into_usize_safe();
-            let array = self.pop_array()?;
+            let index = self.pop_int()?;
+            let array = self.pop_array(concat!("Cannot store to ", $type_name, " array"))?;
+            let index = Self::array_index(&array, index)?;
             match array.elements_type() {
                 $($variant => {
                      array.set_element(index, value)?
@@ -312,38 +327,112 @@ impl<'a> CallFrame<'a> {
             self.pc = ProgramCounter(new_address as u16);
 
             let instruction_result = self.execute_instruction(vm, call_stack, instruction);
-            match instruction_result {
+            let exception = match instruction_result {
                 Ok(ReturnFromMethod(return_value)) => return Ok(return_value),
-                Ok(ContinueMethodExecution) => { /* continue the loop */ }
-
-                Err(MethodCallFailed::InternalError(err)) => {
-                    return Err(MethodCallFailed::InternalError(err))
+                Ok(ContinueMethodExecution) => continue,
+
+                Err(MethodCallFailed::InternalError(VmError::OutOfMemoryError)) => {
+                    self.pc = executed_instruction_pc;
+                    match vm.out_of_memory_error(call_stack) {
+                        Some(exception) => exception,
+                        None => {
+                            return Err(MethodCallFailed::InternalError(VmError::OutOfMemoryError))
+                        }
+                    }
                 }
 
-                Err(MethodCallFailed::ExceptionThrown(exception)) => {
-                    let exception_handler = self.find_exception_handler(
-                        vm,
-                        call_stack,
-                        executed_instruction_pc,
-                        &exception,
-                    );
-                    match exception_handler {
-                        Err(err) => return Err(err),
-                        Ok(None) => {
-                            // Bubble exception up to the caller
-                            return Err(MethodCallFailed::ExceptionThrown(exception));
-                        }
-                        Ok(Some(catch_handler_pc)) => {
-                            // Re-push exception on the stack and continue execution of this method from the catch handler
-                            self.stack.push(Value::Object(exception.0))?;
-                            self.pc = catch_handler_pc
+                Err(MethodCallFailed::InternalError(err)) => match err.to_java_exception() {
+                    None => return Err(MethodCallFailed::InternalError(err)),
+                    Some((class_name, message)) => {
+                        // Runtime faults are reported from the instruction that caused them
+                        self.pc = executed_instruction_pc;
+                        let exception = call_stack.with_reserved_frames(|call_stack| {
+                            self.new_java_exception(vm, call_stack, class_name, message)
+                        });
+                        match exception {
+                            Ok(exception) => exception,
+                            // An exception thrown while creating the exception replaces it
+                            Err(MethodCallFailed::ExceptionThrown(exception)) => exception,
+                            Err(err) => return Err(err),
                         }
                     }
+                },
+
+                Err(MethodCallFailed::ExceptionThrown(exception)) => exception,
+            };
+
+            let exception_handler =
+                self.find_exception_handler(vm, call_stack, executed_instruction_pc, &exception);
+            match exception_handler {
+                Err(err) => return Err(err),
+                Ok(None) => {
+                    // Bubble exception up to the caller
+                    return Err(MethodCallFailed::ExceptionThrown(exception));
+                }
+                Ok(Some(catch_handler_pc)) => {
+                    // Clear the stack, push the exception on it, and continue execution
+                    // of this method from the catch handler
+                    self.stack.truncate(0)?;
+                    self.stack.push(Value::Object(exception.0))?;
+                    self.pc = catch_handler_pc
                 }
             }
         }
     }
 
+    /// Creates an instance of the given exception class, invoking its constructor with
+    /// the message, if there is one.
+    fn new_java_exception(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        class_name: &str,
+        message: Option<String>,
+    ) -> Result<JavaException<'a>, MethodCallFailed<'a>> {
+        let exception = vm.new_object(call_stack, class_name)?;
+
+        // Allocating the message might trigger a garbage collection, which would move the
+        // exception. Thus, we keep it in our locals (which are a gc root) until we are done.
+        // We do not use the stack, because its maximum size might be too small.
+        self.locals.push(Value::Object(exception));
+        let result = self.invoke_exception_constructor(vm, call_stack, class_name, message);
+        match (result, self.locals.pop()) {
+            (Ok(()), Some(Value::Object(exception))) => Ok(JavaException(exception)),
+            (Err(err), _) => Err(err),
+            _ => Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            )),
+        }
+    }
+
+    fn invoke_exception_constructor(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        class_name: &str,
+        message: Option<String>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        let (constructor_descriptor, args) = match message {
+            None => ("()V", vec![]),
+            Some(message) => {
+                let message = new_java_lang_string_object(vm, call_stack, &message)?;
+                ("(Ljava/lang/String;)V", vec![Value::Object(message)])
+            }
+        };
+        let exception = match self.locals.last() {
+            Some(Value::Object(exception)) => exception.clone(),
+            _ => {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::ValidationException,
+                ))
+            }
+        };
+        let constructor =
+            vm.resolve_class_method(call_stack, class_name, "<init>", constructor_descriptor)?;
+        vm.invoke(call_stack, constructor, Some(exception), args)?;
+        Ok(())
+    }
+
     // Reference: https://docs.oracle.com/javase/specs/jvms/se7/html/jvms-6.html
     fn execute_instruction(
         &mut self,
@@ -409,7 +498,7 @@ impl<'a> CallFrame<'a> {
 
             Instruction::Ldc(index) => self.execute_ldc(vm, call_stack, index as u16)?,
             Instruction::Ldc_w(index) => self.execute_ldc(vm, call_stack, index)?,
-            Instruction::Ldc2_w(index) => self.execute_ldc_long_double(index)?,
+            Instruction::Ldc2_w(index) => self.execute_ldc_long_double(vm, call_stack, index)?,
 
             Instruction::Fload(index) => self.execute_fload(index.into_usize_safe())?,
             Instruction::Fload_0 => self.execute_fload(0)?,
@@ -470,7 +559,7 @@ impl<'a> CallFrame<'a> {
             Instruction::Pop2 => self.stack.pop2().map(|_| ())?,
             Instruction::Swap => self.stack.swap()?,
 
-            Instruction::Bipush(byte_value) => self.push(Int(byte_value as i32))?,
+            Instruction::Bipush(byte_value) => self.push(Int(byte_value as i8 as i32))?,
             Instruction::Sipush(short_value) => self.push(Int(short_value as i32))?,
 
             Instruction::Invokespecial(constant_index) => {
@@ -547,9 +636,7 @@ impl<'a> CallFrame<'a> {
             })?,
 
             Instruction::Iinc(index, constant) => {
-                let index = index.into_usize_safe();
-                let local = self.get_local_int_as_int(vm, index)?;
-                self.locals[index] = Int(local + constant as i32);
+                self.execute_iinc(vm, index.into_usize_safe(), constant as i32)?
             }
 
             Instruction::Ladd => self.execute_long_math(|a, b| Ok(a + b))?,
@@ -623,7 +710,57 @@ impl<'a> CallFrame<'a> {
             Instruction::Fneg => self.execute_fneg()?,
             Instruction::Dneg => self.execute_dneg()?,
 
-            Instruction::Goto(jump_address) => self.goto(jump_address),
+            Instruction::Goto(jump_address) => self.goto(jump_address)?,
+            Instruction::Goto_w(jump_address) => self.goto(jump_address)?,
+            Instruction::Wide(WideInstruction::Aload(index)) => {
+                self.execute_aload(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Astore(index)) => {
+                self.execute_astore(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Iload(index)) => {
+                self.execute_iload(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Istore(index)) => {
+                self.execute_istore(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Lload(index)) => {
+                self.execute_lload(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Lstore(index)) => {
+                self.execute_lstore(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Fload(index)) => {
+                self.execute_fload(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Fstore(index)) => {
+                self.execute_fstore(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Dload(index)) => {
+                self.execute_dload(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Dstore(index)) => {
+                self.execute_dstore(index.into_usize_safe())?
+            }
+            Instruction::Wide(WideInstruction::Iinc(index, constant)) => {
+                self.execute_iinc(vm, index.into_usize_safe(), constant as i32)?
+            }
+            Instruction::Wide(WideInstruction::Ret(index)) => {
+                self.execute_ret(index.into_usize_safe())?
+            }
+
+            Instruction::Jsr(jump_address) => self.execute_jsr(jump_address)?,
+            Instruction::Jsr_w(jump_address) => self.execute_jsr(jump_address)?,
+            Instruction::Ret(index) => self.execute_ret(index.into_usize_safe())?,
+
+            Instruction::Tableswitch(table_switch) => {
+                let key = self.pop_int()?;
+                self.goto(table_switch.jump_address(key))?;
+            }
+            Instruction::Lookupswitch(lookup_switch) => {
+                let key = self.pop_int()?;
+                self.goto(lookup_switch.jump_address(key))?;
+            }
 
             Instruction::Ifeq(jump_address) => self.execute_if(jump_address, |v| v == 0)?,
             Instruction::Ifne(jump_address) => self.execute_if(jump_address, |v| v != 0)?,
@@ -662,12 +799,16 @@ impl<'a> CallFrame<'a> {
             Instruction::Dcmpl => self.execute_double_compare(1)?,
 
             Instruction::Newarray(array_type) => {
-                self.execute_newarray(vm, array_type)?;
+                self.execute_newarray(vm, call_stack, array_type)?;
             }
             Instruction::Anewarray(constant_index) => {
                 self.execute_anewarray(vm, call_stack, constant_index)?;
             }
 
+            Instruction::Multianewarray(constant_index, dimensions) => {
+                self.execute_multianewarray(vm, call_stack, constant_index, dimensions)?;
+            }
+
             Instruction::Arraylength => self.execute_array_length()?,
 
             Instruction::Baload => self.execute_baload()?,
@@ -688,28 +829,12 @@ impl<'a> CallFrame<'a> {
             Instruction::Dastore => self.execute_dastore()?,
             Instruction::Aastore => self.execute_aastore(vm)?,
 
-            Instruction::Monitorenter => self.execute_monitorenter()?,
-            Instruction::Monitorexit => self.execute_monitorexit()?,
+            Instruction::Monitorenter => self.execute_monitorenter(vm)?,
+            Instruction::Monitorexit => self.execute_monitorexit(vm)?,
 
             Instruction::Athrow => self.execute_athrow()?,
 
-            /* Unimplemented instructions:
-            Instruction::Goto_w => {}
-            Instruction::Invokedynamic(_) => {}
-            Instruction::Jsr(_) => {}
-            Instruction::Jsr_w => {}
-            Instruction::Lookupswitch => {}
-            Instruction::Multianewarray(_, _) => {}
-            Instruction::Ret(_) => {}
-            Instruction::Tableswitch => {}
-            Instruction::Wide => {}
-            */
             Instruction::Nop => {}
-
-            _ => {
-                warn!("Unsupported instruction: {:?}", instruction);
-                return Err(MethodCallFailed::InternalError(VmError::NotImplemented));
-            }
         };
         Ok(ContinueMethodExecution)
     }
@@ -791,20 +916,40 @@ impl<'a> CallFrame<'a> {
         kind: InvokeKind,
     ) -> Result<(), MethodCallFailed<'a>> {
         let method_reference = self.get_constant_method_reference(constant_index)?;
-        if method_reference.class_name.starts_with('[') && method_reference.method_name == "clone" {
-            // TODO:
-            //  Since we have NOT modelled arrays properly (i.e. we do not have a real class
-            //  to model them), we cannot lookup methods naturally. Thus we have a special case for
-            //  invoking "clone" on an array.
-            let array = self.pop()?;
-            let clone = vm.clone_array(array)?;
-            return self.push(clone);
+        if let (InvokeKind::Virtual, "java/lang/invoke/MethodHandle", "invokeExact" | "invoke") = (
+            kind,
+            method_reference.class_name,
+            method_reference.method_name,
+        ) {
+            let exact = method_reference.method_name == "invokeExact";
+            let type_descriptor = method_reference.type_descriptor.to_string();
+            let method_description = Self::describe_method(method_reference);
+            return self.invoke_method_handle(
+                vm,
+                call_stack,
+                &type_descriptor,
+                exact,
+                method_description,
+            );
         }
 
         let static_method_reference =
             self.get_method_to_invoke_statically(vm, call_stack, method_reference, kind)?;
-        let (receiver, params, new_stack_len) =
-            self.get_method_receiver_and_params(&static_method_reference)?;
+        if matches!(kind, InvokeKind::Static) {
+            // The arguments are still on the stack, thus reachable by the garbage collector
+            vm.initialize_class(call_stack, static_method_reference.class)?;
+        }
+        let (receiver, params, new_stack_len) = self
+            .get_method_receiver_and_params(&static_method_reference)
+            .map_err(|err| match err {
+                VmError::NullPointerException(None) => {
+                    VmError::NullPointerException(Some(format!(
+                        "Cannot invoke \"{}\"",
+                        Self::describe_method(method_reference)
+                    )))
+                }
+                _ => err,
+            })?;
         let class_and_method = match kind {
             InvokeKind::Virtual | InvokeKind::Interface => {
                 Self::resolve_virtual_method(vm, receiver.clone(), static_method_reference)?
@@ -823,6 +968,218 @@ impl<'a> CallFrame<'a> {
         Ok(())
     }
 
+    /// Implements the signature polymorphic methods `MethodHandle.invokeExact` and
+    /// `MethodHandle.invoke`. The stack contains the handle, followed by the arguments
+    /// described by the symbolic type descriptor of the call site.
+    fn invoke_method_handle(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        type_descriptor: &str,
+        exact: bool,
+        method_description: String,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        let call_site_type = parse_method_descriptor(type_descriptor)?;
+        let handle_index = self
+            .stack
+            .len()
+            .checked_sub(call_site_type.parameters.len() + 1)
+            .ok_or(VmError::ValidationException)?;
+        let method_handle = match self.stack.get(handle_index) {
+            Some(Value::Object(method_handle_object)) => vm
+                .get_method_handle(method_handle_object)
+                .cloned()
+                .ok_or(VmError::NotImplemented)?,
+            Some(Null) => {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::NullPointerException(Some(format!(
+                        "Cannot invoke \"{method_description}\""
+                    ))),
+                ))
+            }
+            _ => {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::ValidationException,
+                ))
+            }
+        };
+
+        if call_site_type != method_handle.method_type {
+            if exact
+                || call_site_type.parameters.len() != method_handle.method_type.parameters.len()
+            {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::WrongMethodTypeException(format!(
+                        "expected {} but found {}",
+                        describe_method_type(&method_handle.method_type),
+                        describe_method_type(&call_site_type)
+                    )),
+                ));
+            }
+
+            // The arguments are converted while they are on the stack, where they are
+            // reachable by the garbage collector
+            for (index, (from, to)) in call_site_type
+                .parameters
+                .iter()
+                .zip(method_handle.method_type.parameters.iter())
+                .enumerate()
+            {
+                let stack_index = handle_index + 1 + index;
+                let value = self
+                    .stack
+                    .get(stack_index)
+                    .cloned()
+                    .ok_or(VmError::ValidationException)?;
+                let converted = convert_value(vm, call_stack, value, from, to)?;
+                self.stack.set(stack_index, converted)?;
+            }
+        }
+
+        let result =
+            self.invoke_direct_method_handle(vm, call_stack, &method_handle, handle_index)?;
+        let result = match (
+            &method_handle.method_type.return_type,
+            &call_site_type.return_type,
+        ) {
+            (_, None) => None,
+            (None, Some(return_type)) => Some(default_value(return_type)),
+            (Some(from), Some(to)) => {
+                let value = result.ok_or(VmError::ValidationException)?;
+                Some(convert_value(vm, call_stack, value, from, to)?)
+            }
+        };
+        if let Some(value) = result {
+            self.push(value)?;
+        }
+        Ok(())
+    }
+
+    /// Executes the target of a direct method handle, whose arguments are on the stack,
+    /// after the handle itself
+    fn invoke_direct_method_handle(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        method_handle: &DirectMethodHandle<'a>,
+        handle_index: usize,
+    ) -> MethodCallResult<'a> {
+        let class = method_handle.class;
+        let member_reference = MethodReference {
+            class_name: &class.name,
+            method_name: &method_handle.member_name,
+            type_descriptor: &method_handle.member_descriptor,
+        };
+        let field_reference = FieldReference {
+            class_name: &class.name,
+            field_name: &method_handle.member_name,
+            type_descriptor: &method_handle.member_descriptor,
+        };
+
+        let class_and_method = match method_handle.kind {
+            MethodHandleKind::GetField | MethodHandleKind::PutField => {
+                let object = self.get_object_from_stack(handle_index + 1, class)?;
+                let object_class = vm.get_class_by_id(object.class_id())?;
+                let (index, _) = Self::get_field(object_class, field_reference)?;
+                let result = if method_handle.kind == MethodHandleKind::GetField {
+                    Some(object.get_field(object_class, index))
+                } else {
+                    let value = self.pop()?;
+                    object.set_field(index, value);
+                    None
+                };
+                self.stack.truncate(handle_index)?;
+                return Ok(result);
+            }
+            MethodHandleKind::GetStatic | MethodHandleKind::PutStatic => {
+                let class =
+                    Self::get_static_field_declaring_class(class, field_reference.field_name)
+                        .unwrap_or(class);
+                let (index, _) = Self::get_field(class, field_reference)?;
+                vm.initialize_class(call_stack, class)?;
+                let static_instance = vm
+                    .get_static_instance(class.id)
+                    .ok_or(VmError::ValidationException)?;
+                let result = if method_handle.kind == MethodHandleKind::GetStatic {
+                    Some(static_instance.get_field(class, index))
+                } else {
+                    let value = self.pop()?;
+                    static_instance.set_field(index, value);
+                    None
+                };
+                self.stack.truncate(handle_index)?;
+                return Ok(result);
+            }
+            MethodHandleKind::InvokeStatic | MethodHandleKind::InvokeSpecial => {
+                if method_handle.kind == MethodHandleKind::InvokeStatic {
+                    vm.initialize_class(call_stack, class)?;
+                }
+                let method = Self::get_method_of_class(class, member_reference)?;
+                ClassAndMethod { class, method }
+            }
+            MethodHandleKind::NewInvokeSpecial => {
+                vm.initialize_class(call_stack, class)?;
+                // The new object takes the place of the handle on the stack, so that it
+                // is both the receiver of the constructor and reachable by the garbage collector
+                let new_object = vm.new_object_of_class(class)?;
+                self.stack.set(handle_index, Value::Object(new_object))?;
+                let method = Self::get_method_of_class(class, member_reference)?;
+                ClassAndMethod { class, method }
+            }
+            MethodHandleKind::InvokeVirtual | MethodHandleKind::InvokeInterface => {
+                let receiver = self.get_object_from_stack(handle_index + 1, class)?;
+                let static_method_reference =
+                    Self::get_method_checking_superclasses(class, member_reference)?;
+                Self::resolve_virtual_method(vm, Some(receiver), static_method_reference)?
+            }
+        };
+
+        let (receiver, params, _) = self.get_method_receiver_and_params(&class_and_method)?;
+        if method_handle.kind == MethodHandleKind::NewInvokeSpecial {
+            self.stack.truncate(handle_index + 1)?;
+            vm.invoke(call_stack, class_and_method, receiver, params)?;
+            self.pop().map(Some)
+        } else {
+            self.stack.truncate(handle_index)?;
+            vm.invoke(call_stack, class_and_method, receiver, params)
+        }
+    }
+
+    /// Describes a method like HotSpot does in the message of a `NullPointerException`,
+    /// i.e. `String.indexOf(String, int)`
+    fn describe_method(method_reference: MethodReference) -> String {
+        let parameters = MethodDescriptor::parse(method_reference.type_descriptor)
+            .map(|descriptor| {
+                descriptor
+                    .parameters
+                    .iter()
+                    .map(Self::describe_type)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        format!(
+            "{}.{}({})",
+            Self::describe_type(&FieldType::Object(method_reference.class_name.to_string())),
+            method_reference.method_name,
+            parameters
+        )
+    }
+
+    fn describe_type(field_type: &FieldType) -> String {
+        match field_type {
+            FieldType::Base(base_type) => base_type.to_string().to_lowercase(),
+            FieldType::Object(class_name) => match class_name.as_str() {
+                "java/lang/Object" => "Object".to_string(),
+                "java/lang/String" => "String".to_string(),
+                _ => class_name.replace('/', "."),
+            },
+            FieldType::Array(component_type) => {
+                format!("{}[]", Self::describe_type(component_type))
+            }
+        }
+    }
+
     fn get_field(
         class: &'a Class,
         field_reference: FieldReference,
@@ -851,10 +1208,15 @@ impl<'a> CallFrame<'a> {
         }
     }
 
-    fn pop_array(&mut self) -> Result<impl Array<'a>, MethodCallFailed<'a>> {
+    /// Pops an array from the stack; if it is null, fails with a `NullPointerException`
+    /// with the given message
+    fn pop_array(&mut self, null_message: &str) -> Result<impl Array<'a>, MethodCallFailed<'a>> {
         let receiver = self.pop()?;
         match receiver {
             Value::Object(object) if object.kind() == ObjectKind::Array => Ok(object),
+            Null => Err(MethodCallFailed::InternalError(
+                VmError::NullPointerException(Some(null_message.to_string())),
+            )),
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
@@ -881,7 +1243,7 @@ impl<'a> CallFrame<'a> {
     fn get_constant_method_reference(
         &self,
         constant_index: u16,
-    ) -> Result<MethodReference, VmError> {
+    ) -> Result<MethodReference<'_>, VmError> {
         let constant = self.get_constant(constant_index)?;
 
         let (class_name_index, name_and_type_descriptor_index) = match *constant {
@@ -907,23 +1269,10 @@ impl<'a> CallFrame<'a> {
         }
     }
 
-    fn get_method_handle(&self, handle_index: u16) -> Result<MethodHandle, VmError> {
-        let constant = self.get_constant(handle_index)?;
-
-        let (kind, index) = match *constant {
-            ConstantPoolEntry::MethodHandle(k, i) => (k, i),
-            _ => return Err(VmError::ValidationException),
-        };
-
-        MethodHandle::resolve(
-            &self.class_and_method.class.version,
-            &self.class_and_method.class.constants,
-            kind,
-            index,
-        )
-    }
-
-    fn get_constant_field_reference(&self, constant_index: u16) -> Result<FieldReference, VmError> {
+    fn get_constant_field_reference(
+        &self,
+        constant_index: u16,
+    ) -> Result<FieldReference<'_>, VmError> {
         let constant = self.get_constant(constant_index)?;
         if let &ConstantPoolEntry::FieldReference(
             class_name_index,
@@ -957,16 +1306,339 @@ impl<'a> CallFrame<'a> {
     }
 
     fn invoke_dynamic(
-        &self,
+        &mut self,
         vm: &mut Vm<'a>,
         call_stack: &mut CallStack<'a>,
         dynamic_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
-        error!("InvokeDynamic: {:?}", dynamic_index);
-        let method = self.get_method_handle(dynamic_index)?;
+        let call_site_id = CallSiteId::new(&self.class_and_method, self.pc);
+        let call_site = match vm.find_call_site(&call_site_id) {
+            Some(call_site) => call_site,
+            None => {
+                let call_site =
+                    link_call_site(vm, call_stack, self.class_and_method.class, dynamic_index)?;
+                vm.register_call_site(call_site_id, call_site.clone());
+                call_site
+            }
+        };
+
+        match call_site {
+            CallSite::Lambda(lambda_class) => {
+                // The captured arguments stay on the stack, and thus are reachable by the
+                // garbage collector, until the lambda object has been allocated
+                let lambda = vm.new_object_of_class(lambda_class)?;
+                for index in (0..lambda_class.fields.len()).rev() {
+                    let value = self.pop()?;
+                    lambda.set_field(lambda_class.first_field_index + index, value);
+                }
+                self.push(Value::Object(lambda))
+            }
+            CallSite::StringConcat(recipe) => {
+                // We convert the arguments to strings while they are still on the stack,
+                // since invoking `toString` could trigger a garbage collection
+                let arguments_count = recipe.argument_types.len();
+                let first_argument_index = self
+                    .stack
+                    .len()
+                    .checked_sub(arguments_count)
+                    .ok_or(VmError::ValidationException)?;
+                let mut arguments = Vec::with_capacity(arguments_count);
+                for (index, argument_type) in recipe.argument_types.iter().enumerate() {
+                    let value = self
+                        .stack
+                        .get(first_argument_index + index)
+                        .cloned()
+                        .ok_or(VmError::ValidationException)?;
+                    arguments.push(render_argument(vm, call_stack, value, argument_type)?);
+                }
+                self.stack.truncate(first_argument_index)?;
+
+                let string =
+                    new_java_lang_string_object(vm, call_stack, &recipe.concatenate(&arguments))?;
+                self.push(Value::Object(string))
+            }
+        }
+    }
+
+    /// Loads a dynamically-computed constant. Its bootstrap method is run only the first time,
+    /// and its failures are reported as a `BootstrapMethodError`. Like a successful resolution,
+    /// a failed one is remembered: later attempts throw the same error, following
+    /// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-5.html#jvms-5.4.3
+    fn load_dynamic_constant(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        constant_index: u16,
+    ) -> Result<Value<'a>, MethodCallFailed<'a>> {
+        let class_id = self.class_and_method.class.id;
+        if let Some(value) = vm.find_dynamic_constant(class_id, constant_index) {
+            return Ok(value);
+        }
+        if let Some(error) = vm.find_failed_dynamic_constant(class_id, constant_index) {
+            return Err(MethodCallFailed::ExceptionThrown(error));
+        }
+
+        let value = match self.resolve_dynamic_constant(vm, call_stack, constant_index) {
+            Ok(value) => value,
+            Err(err) => {
+                let error = self.wrap_in_bootstrap_method_error(vm, call_stack, err)?;
+                vm.register_failed_dynamic_constant(class_id, constant_index, &error);
+                return Err(MethodCallFailed::ExceptionThrown(error));
+            }
+        };
+        vm.register_dynamic_constant(class_id, constant_index, value.clone());
+        Ok(value)
+    }
+
+    /// Resolves a `CONSTANT_Dynamic` by invoking its bootstrap method with a lookup, the name
+    /// and the type of the constant, followed by the static arguments. See
+    /// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-5.html#jvms-5.4.3.6
+    fn resolve_dynamic_constant(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        constant_index: u16,
+    ) -> Result<Value<'a>, MethodCallFailed<'a>> {
+        // The arguments of the bootstrap method are kept in our locals, which are a gc root,
+        // until it is invoked
+        let first_argument_index = self.locals.len();
+        let result = self.invoke_bootstrap_method(vm, call_stack, constant_index);
+        self.locals.truncate(first_argument_index);
+        result
+    }
+
+    fn invoke_bootstrap_method(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        constant_index: u16,
+    ) -> Result<Value<'a>, MethodCallFailed<'a>> {
+        let class = self.class_and_method.class;
+        let &ConstantPoolEntry::DynamicInfo(bootstrap_method_index, name_and_type_index) =
+            self.get_constant(constant_index)?
+        else {
+            return Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            ));
+        };
+        let (name, descriptor) = get_name_and_type(&class.constants, name_and_type_index)?;
+        let constant_type =
+            FieldType::parse(descriptor).map_err(|_| VmError::ValidationException)?;
+        let bootstrap_method = class
+            .bootstrap_methods
+            .get(bootstrap_method_index as usize)
+            .ok_or(VmError::ValidationException)?;
+        let bootstrap_method_handle = DirectMethodHandle::resolve(
+            vm,
+            call_stack,
+            class,
+            bootstrap_method.method_handle_index,
+        )?;
+        if bootstrap_method_handle.kind != MethodHandleKind::InvokeStatic {
+            return Err(MethodCallFailed::InternalError(
+                VmError::BootstrapMethodError(format!(
+                    "unsupported bootstrap method kind {:?}",
+                    bootstrap_method_handle.kind
+                )),
+            ));
+        }
+        let method = Self::get_method_of_class(
+            bootstrap_method_handle.class,
+            MethodReference {
+                class_name: &bootstrap_method_handle.class.name,
+                method_name: &bootstrap_method_handle.member_name,
+                type_descriptor: &bootstrap_method_handle.member_descriptor,
+            },
+        )?;
+        debug!(
+            "resolving dynamic constant {name}:{descriptor} with bootstrap method {}.{}",
+            bootstrap_method_handle.class.name, bootstrap_method_handle.member_name
+        );
+
+        // We do not model `MethodHandles.Lookup`, since the vm does not check accesses
+        let first_argument_index = self.locals.len();
+        let mut argument_types = vec![
+            FieldType::Object("java/lang/invoke/MethodHandles$Lookup".to_string()),
+            FieldType::Object("java/lang/String".to_string()),
+            FieldType::Object("java/lang/Class".to_string()),
+        ];
+        self.locals.push(Null);
+        let name_object = new_java_lang_string_object(vm, call_stack, name)?;
+        self.locals.push(Value::Object(name_object));
+        let type_object = get_java_lang_class_object_of_type(vm, call_stack, &constant_type)?;
+        self.locals.push(Value::Object(type_object));
+        for &argument_index in bootstrap_method.arguments.iter() {
+            let argument = self.load_constant(vm, call_stack, argument_index)?;
+            self.locals.push(argument);
+            argument_types.push(self.get_constant_type(argument_index)?);
+        }
+
+        self.adapt_bootstrap_method_arguments(
+            vm,
+            call_stack,
+            &bootstrap_method_handle.method_type.parameters,
+            method.is_varargs(),
+            first_argument_index,
+            &argument_types,
+        )?;
+        let mut arguments = self.locals.split_off(first_argument_index);
+        Self::fix_long_and_double_params(&mut arguments)?;
+        let class_and_method = ClassAndMethod {
+            class: bootstrap_method_handle.class,
+            method,
+        };
+        let result = vm.invoke(call_stack, class_and_method, None, arguments)?;
+
+        match (result, &bootstrap_method_handle.method_type.return_type) {
+            (Some(value), Some(return_type)) => {
+                convert_value(vm, call_stack, value, return_type, &constant_type)
+            }
+            _ => Err(MethodCallFailed::InternalError(
+                VmError::BootstrapMethodError(format!(
+                    "bootstrap method {} returned void",
+                    bootstrap_method_handle.member_name
+                )),
+            )),
+        }
+    }
+
+    /// Converts the arguments of a bootstrap method, stored in our locals, to the types of
+    /// its parameters. Like `MethodHandle.invokeWithArguments`, if the method has variable
+    /// arity, the trailing arguments are collected into an array.
+    fn adapt_bootstrap_method_arguments(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        parameter_types: &[FieldType],
+        is_varargs: bool,
+        first_argument_index: usize,
+        argument_types: &[FieldType],
+    ) -> Result<(), MethodCallFailed<'a>> {
+        let variable_arity_type = match parameter_types.last() {
+            Some(FieldType::Array(component_type))
+                if is_varargs
+                    && !(argument_types.len() == parameter_types.len()
+                        && matches!(argument_types.last(), Some(FieldType::Array(_)))) =>
+            {
+                Some(component_type.as_ref())
+            }
+            _ => None,
+        };
+        let fixed_arguments_count = match variable_arity_type {
+            Some(_) => parameter_types.len() - 1,
+            None => parameter_types.len(),
+        };
+        if argument_types.len() < fixed_arguments_count
+            || (variable_arity_type.is_none() && argument_types.len() != fixed_arguments_count)
+        {
+            return Err(MethodCallFailed::InternalError(
+                VmError::WrongMethodTypeException(format!(
+                    "cannot invoke bootstrap method with {} arguments",
+                    argument_types.len()
+                )),
+            ));
+        }
+
+        for (index, from) in argument_types.iter().enumerate() {
+            let to = parameter_types
+                .get(index)
+                .filter(|_| index < fixed_arguments_count)
+                .or(variable_arity_type)
+                .ok_or(VmError::ValidationException)?;
+            let local_index = first_argument_index + index;
+            let value = self.locals[local_index].clone();
+            self.locals[local_index] = convert_value(vm, call_stack, value, from, to)?;
+        }
+
+        if let Some(component_type) = variable_arity_type {
+            let elements_type = Self::resolve_array_entry_type(vm, call_stack, component_type)?;
+            let array = vm.new_array(
+                call_stack,
+                elements_type,
+                argument_types.len() - fixed_arguments_count,
+            )?;
+            let first_variable_index = first_argument_index + fixed_arguments_count;
+            for (index, value) in self.locals.drain(first_variable_index..).enumerate() {
+                array.set_element(index, value)?;
+            }
+            self.locals.push(Value::Object(array));
+        }
         Ok(())
     }
 
+    /// Like the JVM, wraps an exception thrown while resolving a dynamic constant
+    /// into a `BootstrapMethodError`, unless it is already an `Error`
+    fn wrap_in_bootstrap_method_error(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        err: MethodCallFailed<'a>,
+    ) -> Result<JavaException<'a>, MethodCallFailed<'a>> {
+        let cause = match err {
+            MethodCallFailed::ExceptionThrown(exception) => exception,
+            MethodCallFailed::InternalError(err) => match err.to_java_exception() {
+                Some((class_name, message)) => {
+                    self.new_java_exception(vm, call_stack, class_name, message)?
+                }
+                None => return Err(MethodCallFailed::InternalError(err)),
+            },
+        };
+
+        // Like in `new_java_exception`, we keep the objects in our locals until we are done
+        let first_local_index = self.locals.len();
+        self.locals.push(Value::Object(cause.0));
+        let result = self.new_bootstrap_method_error(vm, call_stack, first_local_index);
+        self.locals.truncate(first_local_index);
+        result
+    }
+
+    fn new_bootstrap_method_error(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        cause_index: usize,
+    ) -> Result<JavaException<'a>, MethodCallFailed<'a>> {
+        let error_class = vm.get_or_resolve_class(call_stack, "java/lang/Error")?;
+        let cause = self.get_object_from_locals(cause_index)?;
+        if vm
+            .get_class_by_id(cause.class_id())?
+            .is_subclass_of(error_class)
+        {
+            return Ok(JavaException(cause));
+        }
+
+        let class_name = "java/lang/BootstrapMethodError";
+        let error = vm.new_object(call_stack, class_name)?;
+        self.locals.push(Value::Object(error));
+        let message = new_java_lang_string_object(
+            vm,
+            call_stack,
+            "bootstrap method initialization exception",
+        )?;
+        let cause = self.get_object_from_locals(cause_index)?;
+        let error = self.get_object_from_locals(cause_index + 1)?;
+        let constructor = vm.resolve_class_method(
+            call_stack,
+            class_name,
+            "<init>",
+            "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+        )?;
+        vm.invoke(
+            call_stack,
+            constructor,
+            Some(error),
+            vec![Value::Object(message), Value::Object(cause)],
+        )?;
+        Ok(JavaException(self.get_object_from_locals(cause_index + 1)?))
+    }
+
+    fn get_object_from_locals(&self, index: usize) -> Result<AbstractObject<'a>, VmError> {
+        match self.locals.get(index) {
+            Some(Value::Object(object)) => Ok(object.clone()),
+            _ => Err(VmError::ValidationException),
+        }
+    }
+
     fn get_method_to_invoke_statically(
         &self,
         vm: &mut Vm<'a>,
@@ -1034,13 +1706,13 @@ impl<'a> CallFrame<'a> {
         }
     }
 
-    fn resolve_virtual_method(
+    pub(crate) fn resolve_virtual_method(
         vm: &Vm<'a>,
         receiver: Option<AbstractObject>,
         class_and_method: ClassAndMethod,
     ) -> Result<ClassAndMethod<'a>, MethodCallFailed<'a>> {
         match receiver {
-            Some(receiver) if receiver.kind() == ObjectKind::Object => {
+            Some(receiver) => {
                 let receiver_class = vm.find_class_by_id(receiver.class_id()).ok_or(
                     VmError::ClassNotFoundException(receiver.class_id().to_string()),
                 )?;
@@ -1132,6 +1804,7 @@ impl<'a> CallFrame<'a> {
                 // TODO: here we should check "instanceof" the expected class of a subclass
                 Ok(object.clone())
             }
+            Null => Err(VmError::NullPointerException(None)),
             _ => Err(VmError::ValidationException),
         }
     }
@@ -1220,13 +1893,56 @@ impl<'a> CallFrame<'a> {
     generate_execute_coerce!(coerce_float, pop_float, f32);
     generate_execute_coerce!(coerce_double, pop_double, f64);
 
-    fn goto(&mut self, jump_address: u16) {
-        self.pc = ProgramCounter(jump_address);
+    fn execute_iinc(
+        &mut self,
+        vm: &Vm<'a>,
+        index: usize,
+        constant: i32,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        let local = self.get_local_int_as_int(vm, index)?;
+        *self
+            .locals
+            .get_mut(index)
+            .ok_or(VmError::ValidationException)? = Int(local.wrapping_add(constant));
+        Ok(())
+    }
+
+    fn goto(&mut self, jump_address: u32) -> Result<(), MethodCallFailed<'a>> {
+        // The reader accepts any target reachable with a 32 bits offset, but the
+        // code of a method is at most 65535 bytes long
+        match u16::try_from(jump_address) {
+            Ok(jump_address) if (jump_address as usize) < self.code.len() => {
+                self.pc = ProgramCounter(jump_address);
+                Ok(())
+            }
+            _ => Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            )),
+        }
+    }
+
+    /// Jumps to a subroutine, pushing the address of the next instruction on the stack
+    fn execute_jsr(&mut self, jump_address: u32) -> Result<(), MethodCallFailed<'a>> {
+        self.push(Value::ReturnAddress(self.pc))?;
+        self.goto(jump_address)
+    }
+
+    /// Returns from a subroutine, to the address stored in the given local variable
+    fn execute_ret(&mut self, index: usize) -> Result<(), MethodCallFailed<'a>> {
+        match self.locals.get(index) {
+            Some(Value::ReturnAddress(return_address)) => {
+                self.pc = *return_address;
+                Ok(())
+            }
+            _ => Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            )),
+        }
     }
 
     fn execute_if<T>(
         &mut self,
-        jump_address: u16,
+        jump_address: u32,
         comparator: T,
     ) -> Result<(), MethodCallFailed<'a>>
     where
@@ -1234,14 +1950,14 @@ impl<'a> CallFrame<'a> {
     {
         let value = self.pop_int()?;
         if comparator(value) {
-            self.goto(jump_address);
+            self.goto(jump_address)?;
         }
         Ok(())
     }
 
     fn execute_if_icmp<T>(
         &mut self,
-        jump_address: u16,
+        jump_address: u32,
         comparator: T,
     ) -> Result<(), MethodCallFailed<'a>>
     where
@@ -1250,26 +1966,26 @@ impl<'a> CallFrame<'a> {
         let val2 = self.pop_int()?;
         let val1 = self.pop_int()?;
         if comparator(val1, val2) {
-            self.goto(jump_address);
+            self.goto(jump_address)?;
         }
         Ok(())
     }
 
     fn execute_if_null(
         &mut self,
-        jump_address: u16,
+        jump_address: u32,
         jump_on_null: bool,
     ) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop()?;
         match value {
             Value::Object(_) => {
                 if !jump_on_null {
-                    self.goto(jump_address);
+                    self.goto(jump_address)?;
                 }
             }
             Null => {
                 if jump_on_null {
-                    self.goto(jump_address);
+                    self.goto(jump_address)?;
                 }
             }
             _ => {
@@ -1283,7 +1999,7 @@ impl<'a> CallFrame<'a> {
 
     fn execute_if_acmp(
         &mut self,
-        jump_address: u16,
+        jump_address: u32,
         jump_on_equal: bool,
     ) -> Result<(), MethodCallFailed<'a>> {
         let value2 = self.pop()?;
@@ -1314,7 +2030,7 @@ impl<'a> CallFrame<'a> {
             }
         };
         if (jump_on_equal && equal) || (!jump_on_equal && !equal) {
-            self.goto(jump_address);
+            self.goto(jump_address)?;
         }
         Ok(())
     }
@@ -1341,12 +2057,11 @@ impl<'a> CallFrame<'a> {
     fn execute_astore(&mut self, index: usize) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop()?;
         match value {
-            Value::Object(..) => {
-                self.locals[index] = value;
-                Ok(())
-            }
-            Null => {
-                self.locals[index] = value;
+            Value::Object(..) | Null | Value::ReturnAddress(..) => {
+                *self
+                    .locals
+                    .get_mut(index)
+                    .ok_or(VmError::ValidationException)? = value;
                 Ok(())
             }
             _ => Err(MethodCallFailed::InternalError(
@@ -1366,58 +2081,121 @@ impl<'a> CallFrame<'a> {
         call_stack: &mut CallStack<'a>,
         index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
-        let constant_value = self.get_constant(index)?;
-        match constant_value {
-            ConstantPoolEntry::Integer(value) => self.push(Int(*value)),
-            ConstantPoolEntry::Float(value) => self.push(Float(*value)),
-            ConstantPoolEntry::StringReference(string_index) => {
-                let constant = self.get_constant(*string_index)?;
-                match constant {
-                    ConstantPoolEntry::Utf8(string) => {
-                        let string_object = new_java_lang_string_object(vm, call_stack, string)?;
-                        self.push(Value::Object(string_object))
-                    }
-                    _ => Err(MethodCallFailed::InternalError(
-                        VmError::ValidationException,
-                    )),
-                }
+        match self.get_constant(index)? {
+            ConstantPoolEntry::Long(_) | ConstantPoolEntry::Double(_) => Err(
+                MethodCallFailed::InternalError(VmError::ValidationException),
+            ),
+            _ => {
+                let value = self.load_constant(vm, call_stack, index)?;
+                self.push(value)
             }
-            ConstantPoolEntry::ClassReference(class_index) => {
-                let constant = self.get_constant(*class_index)?;
-                match constant {
-                    ConstantPoolEntry::Utf8(class_name) => {
-                        let class_object = new_java_lang_class_object(vm, call_stack, class_name)?;
-                        self.push(Value::Object(class_object))
-                    }
-                    _ => Err(MethodCallFailed::InternalError(
-                        VmError::ValidationException,
-                    )),
-                }
+        }
+    }
+
+    fn execute_ldc_long_double(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        index: u16,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        match self.get_constant(index)? {
+            ConstantPoolEntry::Long(_)
+            | ConstantPoolEntry::Double(_)
+            | ConstantPoolEntry::DynamicInfo(_, _) => {
+                let value = self.load_constant(vm, call_stack, index)?;
+                self.push(value)
             }
-            // TODO: method type or method handle
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
         }
     }
 
-    fn execute_ldc_long_double(&mut self, index: u16) -> Result<(), MethodCallFailed<'a>> {
+    /// Loads the value of a loadable constant, i.e. one that can be pushed by `ldc`
+    /// or passed as a static argument to a bootstrap method
+    fn load_constant(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        index: u16,
+    ) -> Result<Value<'a>, MethodCallFailed<'a>> {
         let constant_value = self.get_constant(index)?;
         match constant_value {
-            ConstantPoolEntry::Long(value) => self.push(Long(*value)),
-            ConstantPoolEntry::Double(value) => self.push(Double(*value)),
+            ConstantPoolEntry::Integer(value) => Ok(Int(*value)),
+            ConstantPoolEntry::Float(value) => Ok(Float(*value)),
+            ConstantPoolEntry::Long(value) => Ok(Long(*value)),
+            ConstantPoolEntry::Double(value) => Ok(Double(*value)),
+            ConstantPoolEntry::StringReference(string_index) => {
+                let class_id = self.class_and_method.class.id;
+                if let Some(string_object) = vm.find_string_constant(class_id, index) {
+                    return Ok(Value::Object(string_object));
+                }
+                let string = self.get_constant_utf8(*string_index)?;
+                let string_object = vm.intern_string(call_stack, string)?;
+                vm.register_string_constant(class_id, index, string_object.clone());
+                Ok(Value::Object(string_object))
+            }
+            ConstantPoolEntry::ClassReference(class_index) => {
+                let class_name = self.get_constant_utf8(*class_index)?;
+                let mirrored_type = MirroredType::of_class_name(vm, call_stack, class_name)?;
+                let class_object = vm.get_class_mirror(call_stack, mirrored_type)?;
+                Ok(Value::Object(class_object))
+            }
+            ConstantPoolEntry::MethodType(descriptor_index) => {
+                let descriptor = self.get_constant_utf8(*descriptor_index)?;
+                let method_type = parse_method_descriptor(descriptor)?;
+                let method_type_object =
+                    new_java_lang_invoke_method_type_object(vm, call_stack, &method_type)?;
+                Ok(Value::Object(method_type_object))
+            }
+            ConstantPoolEntry::MethodHandle(_, _) => {
+                let method_handle = DirectMethodHandle::resolve(
+                    vm,
+                    call_stack,
+                    self.class_and_method.class,
+                    index,
+                )?;
+                let method_handle_object =
+                    new_java_lang_invoke_method_handle_object(vm, call_stack, method_handle)?;
+                Ok(Value::Object(method_handle_object))
+            }
+            ConstantPoolEntry::DynamicInfo(_, _) => {
+                self.load_dynamic_constant(vm, call_stack, index)
+            }
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
         }
     }
 
+    /// Returns the type of the value of a loadable constant
+    fn get_constant_type(&self, index: u16) -> Result<FieldType, VmError> {
+        let class_name = match self.get_constant(index)? {
+            ConstantPoolEntry::Integer(_) => return Ok(FieldType::Base(BaseType::Int)),
+            ConstantPoolEntry::Float(_) => return Ok(FieldType::Base(BaseType::Float)),
+            ConstantPoolEntry::Long(_) => return Ok(FieldType::Base(BaseType::Long)),
+            ConstantPoolEntry::Double(_) => return Ok(FieldType::Base(BaseType::Double)),
+            &ConstantPoolEntry::DynamicInfo(_, name_and_type_index) => {
+                let (_, descriptor) =
+                    get_name_and_type(&self.class_and_method.class.constants, name_and_type_index)?;
+                return FieldType::parse(descriptor).map_err(|_| VmError::ValidationException);
+            }
+            ConstantPoolEntry::StringReference(_) => "java/lang/String",
+            ConstantPoolEntry::ClassReference(_) => "java/lang/Class",
+            ConstantPoolEntry::MethodType(_) => "java/lang/invoke/MethodType",
+            ConstantPoolEntry::MethodHandle(_, _) => "java/lang/invoke/MethodHandle",
+            _ => return Err(VmError::ValidationException),
+        };
+        Ok(FieldType::Object(class_name.to_string()))
+    }
+
     fn execute_newarray(
         &mut self,
         vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
         array_type: NewArrayType,
     ) -> Result<(), MethodCallFailed<'a>> {
-        let length = self.pop_int()?.into_usize_safe();
+        let length = Self::array_length(self.pop_int()?)?;
         let elements_type = match array_type {
             NewArrayType::Boolean => ArrayEntryType::Base(BaseType::Boolean),
             NewArrayType::Char => ArrayEntryType::Base(BaseType::Char),
@@ -1429,7 +2207,7 @@ impl<'a> CallFrame<'a> {
             NewArrayType::Long => ArrayEntryType::Base(BaseType::Long),
         };
 
-        let array = vm.new_array(elements_type, length);
+        let array = vm.new_array(call_stack, elements_type, length)?;
         self.push(Value::Object(array))
     }
 
@@ -1439,17 +2217,127 @@ impl<'a> CallFrame<'a> {
         call_stack: &mut CallStack<'a>,
         constant_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
-        let length = self.pop_int()?.into_usize_safe();
+        let length = Self::array_length(self.pop_int()?)?;
         let class_name = self.get_constant_class_reference(constant_index)?;
-        let class = vm.get_or_resolve_class(call_stack, class_name)?;
-        let elements_type = ArrayEntryType::Object(class.id);
+        let elements_type = Self::class_name_to_field_type(class_name)?;
+        let elements_type = Self::resolve_array_entry_type(vm, call_stack, &elements_type)?;
 
-        let array = vm.new_array(elements_type, length);
+        let array = vm.new_array(call_stack, elements_type, length)?;
+        self.push(Value::Object(array))
+    }
+
+    fn execute_multianewarray(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        constant_index: u16,
+        dimensions: u8,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        let mut lengths = (0..dimensions)
+            .map(|_| self.pop_int())
+            .collect::<Result<Vec<i32>, MethodCallFailed<'a>>>()?
+            .into_iter()
+            .map(Self::array_length)
+            .collect::<Result<Vec<usize>, VmError>>()?;
+        lengths.reverse();
+
+        let class_name = self.get_constant_class_reference(constant_index)?;
+        let array_type = Self::class_name_to_field_type(class_name)?;
+        let array = self.new_multi_dimensional_array(vm, call_stack, &array_type, &lengths)?;
         self.push(Value::Object(array))
     }
 
+    /// Allocates an array of the given type, and recursively its sub-arrays, one for each of
+    /// the given lengths. If there are less lengths than dimensions, the innermost entries
+    /// are left null (i.e. `new int[3][]`).
+    fn new_multi_dimensional_array(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        array_type: &FieldType,
+        lengths: &[usize],
+    ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+        let (FieldType::Array(elements_type), Some((&length, sub_arrays_lengths))) =
+            (array_type, lengths.split_first())
+        else {
+            return Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            ));
+        };
+        let array_entry_type = Self::resolve_array_entry_type(vm, call_stack, elements_type)?;
+        let array = vm.new_array(call_stack, array_entry_type, length)?;
+        if sub_arrays_lengths.is_empty() {
+            return Ok(array);
+        }
+
+        // Allocating the sub-arrays might trigger a garbage collection, which would move the
+        // array. Thus, we keep it on our stack (which is a gc root) and re-read it each time.
+        self.push(Value::Object(array))?;
+        for index in 0..length {
+            let sub_array = self.new_multi_dimensional_array(
+                vm,
+                call_stack,
+                elements_type,
+                sub_arrays_lengths,
+            )?;
+            match self.stack.iter().last() {
+                Some(Value::Object(array)) => array.set_element(index, Value::Object(sub_array))?,
+                _ => {
+                    return Err(MethodCallFailed::InternalError(
+                        VmError::ValidationException,
+                    ))
+                }
+            }
+        }
+        match self.pop()? {
+            Value::Object(array) => Ok(array),
+            _ => Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            )),
+        }
+    }
+
+    /// Converts the name of a class reference to a type; arrays are referred to via their
+    /// type descriptor, while classes via their name.
+    fn class_name_to_field_type(class_name: &str) -> Result<FieldType, VmError> {
+        if class_name.starts_with('[') {
+            FieldType::parse(class_name).map_err(|_| VmError::ValidationException)
+        } else {
+            Ok(FieldType::Object(class_name.to_string()))
+        }
+    }
+
+    /// Returns the [ArrayEntryType] for entries of the given type, resolving classes if needed
+    fn resolve_array_entry_type(
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        field_type: &FieldType,
+    ) -> Result<ArrayEntryType, MethodCallFailed<'a>> {
+        match field_type {
+            FieldType::Base(base_type) => Ok(ArrayEntryType::Base(base_type.clone())),
+            FieldType::Object(class_name) => {
+                let class = vm.get_or_resolve_class(call_stack, class_name)?;
+                Ok(ArrayEntryType::Object(class.id))
+            }
+            FieldType::Array(component_type) => {
+                Ok(Self::resolve_array_entry_type(vm, call_stack, component_type)?.array_of())
+            }
+        }
+    }
+
+    /// Checks that the index is not negative, and converts it to a usize. Too big indexes
+    /// will be detected when accessing the array.
+    fn array_index(array: &impl Array<'a>, index: i32) -> Result<usize, VmError> {
+        usize::try_from(index).map_err(|_| VmError::array_index_out_of_bounds(index, array.len()))
+    }
+
+    /// Checks that the length of a new array is not negative, and converts it to a usize
+    fn array_length(length: i32) -> Result<usize, VmError> {
+        usize::try_from(length).map_err(|_| VmError::NegativeArraySizeException(length))
+    }
+
     fn execute_array_length(&mut self) -> Result<(), MethodCallFailed<'a>> {
-        let array = self.pop_array()?;
+        let array = self.pop_array("Cannot read the array length")?;
         let len = array.len() as i32;
         self.push(Int(len))?;
         Ok(())
@@ -1457,19 +2345,38 @@ impl<'a> CallFrame<'a> {
 
     generate_execute_array_load!(
         execute_baload,
+        "byte/boolean",
         ArrayEntryType::Base(BaseType::Byte),
         ArrayEntryType::Base(BaseType::Boolean)
     );
-    generate_execute_array_load!(execute_caload, ArrayEntryType::Base(BaseType::Char));
-    generate_execute_array_load!(execute_saload, ArrayEntryType::Base(BaseType::Short));
-    generate_execute_array_load!(execute_iaload, ArrayEntryType::Base(BaseType::Int));
-    generate_execute_array_load!(execute_laload, ArrayEntryType::Base(BaseType::Long));
-    generate_execute_array_load!(execute_faload, ArrayEntryType::Base(BaseType::Float));
-    generate_execute_array_load!(execute_daload, ArrayEntryType::Base(BaseType::Double));
-    generate_execute_array_load!(execute_aaload, ArrayEntryType::Object(..));
+    generate_execute_array_load!(execute_caload, "char", ArrayEntryType::Base(BaseType::Char));
+    generate_execute_array_load!(
+        execute_saload,
+        "short",
+        ArrayEntryType::Base(BaseType::Short)
+    );
+    generate_execute_array_load!(execute_iaload, "int", ArrayEntryType::Base(BaseType::Int));
+    generate_execute_array_load!(execute_laload, "long", ArrayEntryType::Base(BaseType::Long));
+    generate_execute_array_load!(
+        execute_faload,
+        "float",
+        ArrayEntryType::Base(BaseType::Float)
+    );
+    generate_execute_array_load!(
+        execute_daload,
+        "double",
+        ArrayEntryType::Base(BaseType::Double)
+    );
+    generate_execute_array_load!(
+        execute_aaload,
+        "object",
+        ArrayEntryType::Object(..),
+        ArrayEntryType::Array(..)
+    );
 
     generate_execute_array_store!(
         execute_bastore,
+        "byte/boolean",
         pop_int,
         i2b,
         ArrayEntryType::Base(BaseType::Byte),
@@ -1477,36 +2384,42 @@ impl<'a> CallFrame<'a> {
     );
     generate_execute_array_store!(
         execute_castore,
+        "char",
         pop_int,
         i2c,
         ArrayEntryType::Base(BaseType::Char)
     );
     generate_execute_array_store!(
         execute_sastore,
+        "short",
         pop_int,
         i2s,
         ArrayEntryType::Base(BaseType::Short)
     );
     generate_execute_array_store!(
         execute_iastore,
+        "int",
         pop_int,
         i2i,
         ArrayEntryType::Base(BaseType::Int)
     );
     generate_execute_array_store!(
         execute_lastore,
+        "long",
         pop_long,
         l2l,
         ArrayEntryType::Base(BaseType::Long)
     );
     generate_execute_array_store!(
         execute_fastore,
+        "float",
         pop_float,
         f2f,
         ArrayEntryType::Base(BaseType::Float)
     );
     generate_execute_array_store!(
         execute_dastore,
+        "double",
         pop_double,
         d2d,
         ArrayEntryType::Base(BaseType::Double)
@@ -1514,18 +2427,26 @@ impl<'a> CallFrame<'a> {
 
     fn execute_aastore(&mut self, vm: &Vm) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop_object_or_null()?;
-        let index = self.pop_int()?.into_usize_safe();
-        let array = self.pop_array()?;
+        let index = self.pop_int()?;
+        let array = self.pop_array("Cannot store to object array")?;
+        let index = Self::array_index(&array, index)?;
         match array.elements_type() {
             ArrayEntryType::Object(elements_class_id) => {
                 let elements_class_name = vm.get_class_by_id(elements_class_id)?;
-                Self::validate_type(
+                Self::validate_array_store(
                     vm,
                     FieldType::Object(elements_class_name.name.clone()),
                     &value,
                 )?;
                 array.set_element(index, value)?
             }
+            elements_type @ ArrayEntryType::Array(..) => {
+                let elements_type = elements_type
+                    .into_field_type(vm)
+                    .ok_or(VmError::ValidationException)?;
+                Self::validate_array_store(vm, elements_type, &value)?;
+                array.set_element(index, value)?
+            }
             _ => {
                 return Err(MethodCallFailed::InternalError(
                     VmError::ValidationException,
@@ -1535,6 +2456,34 @@ impl<'a> CallFrame<'a> {
         Ok(())
     }
 
+    /// Checks that the value can be stored in an array with the given type of entries
+    fn validate_array_store(
+        vm: &Vm,
+        elements_type: FieldType,
+        value: &Value<'a>,
+    ) -> Result<(), VmError> {
+        match value {
+            Value::Object(object) if Self::validate_type(vm, elements_type, value).is_err() => Err(
+                VmError::ArrayStoreException(Self::external_class_name(vm, object)?),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the name of the class of the given object, as printed by `Class::getName`,
+    /// i.e. `java.lang.String` or `[Ljava.lang.String;`
+    fn external_class_name(vm: &Vm, object: &AbstractObject) -> Result<String, VmError> {
+        let class_name = match object.kind() {
+            ObjectKind::Object => vm.get_class_by_id(object.class_id())?.name.clone(),
+            ObjectKind::Array => object
+                .elements_type()
+                .into_field_type(vm)
+                .map(|elements_type| FieldType::Array(Box::new(elements_type)).to_descriptor())
+                .ok_or(VmError::ValidationException)?,
+        };
+        Ok(class_name.replace('/', "."))
+    }
+
     fn execute_instanceof(
         &mut self,
         vm: &mut Vm<'a>,
@@ -1553,11 +2502,26 @@ impl<'a> CallFrame<'a> {
         constant_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop()?;
-        let is_instance_of = self.is_instanceof(vm, call_stack, constant_index, &value)?;
-        if is_instance_of {
-            self.push(value)
-        } else {
-            Err(MethodCallFailed::InternalError(VmError::ClassCastException))
+        match &value {
+            // Null can be cast to any type
+            Null => self.push(value),
+            Value::Object(object) => {
+                if self.is_instanceof(vm, call_stack, constant_index, &value)? {
+                    self.push(value)
+                } else {
+                    let class_name = self.get_constant_class_reference(constant_index)?;
+                    Err(MethodCallFailed::InternalError(
+                        VmError::ClassCastException(format!(
+                            "class {} cannot be cast to class {}",
+                            Self::external_class_name(vm, object)?,
+                            class_name.replace('/', ".")
+                        )),
+                    ))
+                }
+            }
+            _ => Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            )),
         }
     }
 
@@ -1571,39 +2535,24 @@ impl<'a> CallFrame<'a> {
     ) -> Result<bool, MethodCallFailed<'a>> {
         let class_name = self.get_constant_class_reference(constant_index)?;
 
-        // TODO: we should model classes of arrays
-        // TODO: multidimensional arrays are not supported!
-        let (is_array, expected_class) = {
-            if class_name.starts_with("[L") && class_name.ends_with(';') {
-                (
-                    true,
-                    vm.get_or_resolve_class(call_stack, &class_name[2..class_name.len() - 1])?,
-                )
-            } else {
-                (false, vm.get_or_resolve_class(call_stack, class_name)?)
-            }
-        };
-
         let is_instance_of = match &value {
             Null => false,
 
             Value::Object(object) => match object.kind() {
                 ObjectKind::Object => {
-                    if is_array {
+                    if class_name.starts_with('[') {
                         false
                     } else {
+                        let expected_class = vm.get_or_resolve_class(call_stack, class_name)?;
                         let object_class = vm.get_class_by_id(object.class_id())?;
                         object_class.is_subclass_of(expected_class)
                     }
                 }
-                ObjectKind::Array => match object.elements_type() {
-                    ArrayEntryType::Base(_) => false,
-                    ArrayEntryType::Object(elements_class_id) => {
-                        let components_class = vm.get_class_by_id(elements_class_id)?;
-                        components_class.is_subclass_of(expected_class)
-                    }
-                    ArrayEntryType::Array => false,
-                },
+                ObjectKind::Array => {
+                    let expected_type = Self::class_name_to_field_type(class_name)?;
+                    let array_class = vm.get_class_by_id(object.class_id())?;
+                    Self::is_array_assignable_to(vm, call_stack, array_class, &expected_type)?
+                }
             },
 
             _ => {
@@ -1615,15 +2564,71 @@ impl<'a> CallFrame<'a> {
         Ok(is_instance_of)
     }
 
+    /// Returns whether an array of the given class can be assigned to a variable of the
+    /// expected type, following the rules of `checkcast`
+    fn is_array_assignable_to(
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        array_class: ClassRef<'a>,
+        expected_type: &FieldType,
+    ) -> Result<bool, MethodCallFailed<'a>> {
+        match expected_type {
+            FieldType::Base(_) => Ok(false),
+            FieldType::Object(expected_class_name) => {
+                let expected_class = vm.get_or_resolve_class(call_stack, expected_class_name)?;
+                Ok(array_class.is_subclass_of(expected_class))
+            }
+            FieldType::Array(expected_component_type) => {
+                let Ok(FieldType::Array(component_type)) = FieldType::parse(&array_class.name)
+                else {
+                    return Err(MethodCallFailed::InternalError(
+                        VmError::ValidationException,
+                    ));
+                };
+                match (*component_type, expected_component_type.as_ref()) {
+                    (FieldType::Base(base_type), FieldType::Base(expected_base_type)) => {
+                        Ok(base_type == *expected_base_type)
+                    }
+                    (FieldType::Object(class_name), FieldType::Object(expected_class_name)) => {
+                        let expected_class =
+                            vm.get_or_resolve_class(call_stack, expected_class_name)?;
+                        let class = vm.get_or_resolve_class(call_stack, &class_name)?;
+                        Ok(class.is_subclass_of(expected_class))
+                    }
+                    (component_type @ FieldType::Array(_), expected_component_type) => {
+                        // The entries are arrays themselves
+                        let component_class =
+                            vm.get_or_resolve_class(call_stack, &component_type.to_descriptor())?;
+                        Self::is_array_assignable_to(
+                            vm,
+                            call_stack,
+                            component_class,
+                            expected_component_type,
+                        )
+                    }
+                    _ => Ok(false),
+                }
+            }
+        }
+    }
+
     fn execute_getfield(
         &mut self,
         vm: &mut Vm<'a>,
         field_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
         let object = self.pop()?;
+        let field_reference = self.get_constant_field_reference(field_index)?;
+        if object == Null {
+            return Err(MethodCallFailed::InternalError(
+                VmError::NullPointerException(Some(format!(
+                    "Cannot read field \"{}\"",
+                    field_reference.field_name
+                ))),
+            ));
+        }
         if let Value::Object(object_ref) = object {
             if object_ref.kind() == ObjectKind::Object {
-                let field_reference = self.get_constant_field_reference(field_index)?;
                 let object_class = vm.get_class_by_id(object_ref.class_id())?;
                 let (index, field) = Self::get_field(object_class, field_reference)?;
                 let field_value = object_ref.get_field(object_class, index);
@@ -1644,9 +2649,17 @@ impl<'a> CallFrame<'a> {
     ) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop()?;
         let object = self.pop()?;
+        let field_reference = self.get_constant_field_reference(field_index)?;
+        if object == Null {
+            return Err(MethodCallFailed::InternalError(
+                VmError::NullPointerException(Some(format!(
+                    "Cannot assign field \"{}\"",
+                    field_reference.field_name
+                ))),
+            ));
+        }
         if let Value::Object(object_ref) = object {
             if object_ref.kind() == ObjectKind::Object {
-                let field_reference = self.get_constant_field_reference(field_index)?;
                 let object_class = vm.get_class_by_id(object_ref.class_id())?;
                 let (index, field) = Self::get_field(object_class, field_reference)?;
                 Self::validate_type(vm, field.type_descriptor.clone(), &value)?;
@@ -1666,12 +2679,13 @@ impl<'a> CallFrame<'a> {
         field_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
         let field_reference = self.get_constant_field_reference(field_index)?;
-        let object_class = vm.get_or_resolve_class(call_stack, field_reference.class_name)?;
-        let (index, field) = Self::get_field(object_class, field_reference)?;
-        let object = vm.get_static_instance(self.class_and_method.class.id);
+        let class = Self::resolve_static_field_class(vm, call_stack, &field_reference)?;
+        let (index, field) = Self::get_field(class, field_reference)?;
+        vm.initialize_class(call_stack, class)?;
+        let object = vm.get_static_instance(class.id);
         if let Some(object_ref) = object {
             if object_ref.kind() == ObjectKind::Object {
-                let field_value = object_ref.get_field(object_class, index);
+                let field_value = object_ref.get_field(class, index);
                 Self::validate_type(vm, field.type_descriptor.clone(), &field_value)?;
                 self.push(field_value)?;
                 return Ok(());
@@ -1689,11 +2703,12 @@ impl<'a> CallFrame<'a> {
         field_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
         let field_reference = self.get_constant_field_reference(field_index)?;
-        let object_class = vm.get_or_resolve_class(call_stack, field_reference.class_name)?;
-        let (index, field) = Self::get_field(object_class, field_reference)?;
+        let class = Self::resolve_static_field_class(vm, call_stack, &field_reference)?;
+        let (index, field) = Self::get_field(class, field_reference)?;
+        vm.initialize_class(call_stack, class)?;
         let value = self.pop()?;
         Self::validate_type(vm, field.type_descriptor.clone(), &value)?;
-        let object = vm.get_static_instance(self.class_and_method.class.id);
+        let object = vm.get_static_instance(class.id);
         if let Some(object_ref) = object {
             if object_ref.kind() == ObjectKind::Object {
                 object_ref.set_field(index, value);
@@ -1705,28 +2720,65 @@ impl<'a> CallFrame<'a> {
         ))
     }
 
-    fn execute_monitorenter(&mut self) -> Result<(), MethodCallFailed<'a>> {
+    /// Resolves the class that declares the given static field, which is the one
+    /// storing its value. It might be a superclass or superinterface of the referenced one.
+    fn resolve_static_field_class(
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        field_reference: &FieldReference,
+    ) -> Result<ClassRef<'a>, MethodCallFailed<'a>> {
+        let referenced_class = vm.get_or_resolve_class(call_stack, field_reference.class_name)?;
+        Ok(
+            Self::get_static_field_declaring_class(referenced_class, field_reference.field_name)
+                .unwrap_or(referenced_class),
+        )
+    }
+
+    /// Looks up the class declaring the given field, as specified in
+    /// https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.3.2
+    fn get_static_field_declaring_class(
+        class: ClassRef<'a>,
+        field_name: &str,
+    ) -> Option<ClassRef<'a>> {
+        if class.fields.iter().any(|field| field.name == field_name) {
+            return Some(class);
+        }
+        class
+            .interfaces
+            .iter()
+            .find_map(|interface| Self::get_static_field_declaring_class(interface, field_name))
+            .or_else(|| {
+                class.superclass.and_then(|superclass| {
+                    Self::get_static_field_declaring_class(superclass, field_name)
+                })
+            })
+    }
+
+    fn execute_monitorenter(&mut self, vm: &mut Vm<'a>) -> Result<(), MethodCallFailed<'a>> {
         let obj = self.pop()?;
         match obj {
-            Value::Object(_) => {
-                // We don't really have monitors or lock, since we are single-threaded,
-                // so any monitor access will succeed!
+            Value::Object(object) => {
+                // We don't really have locks, since we are single-threaded, so entering
+                // a monitor will always succeed. We only count how many times it was entered.
+                vm.enter_monitor(&object);
                 Ok(())
             }
+            Null => Err(MethodCallFailed::InternalError(
+                VmError::NullPointerException(Some("Cannot enter synchronized block".to_string())),
+            )),
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
         }
     }
 
-    fn execute_monitorexit(&mut self) -> Result<(), MethodCallFailed<'a>> {
+    fn execute_monitorexit(&mut self, vm: &mut Vm<'a>) -> Result<(), MethodCallFailed<'a>> {
         let obj = self.pop()?;
         match obj {
-            Value::Object(_) => {
-                // We don't really have monitors or lock, since we are single-threaded,
-                // so any monitor access will succeed!
-                Ok(())
-            }
+            Value::Object(object) => Ok(vm.exit_monitor(&object)?),
+            Null => Err(MethodCallFailed::InternalError(
+                VmError::NullPointerException(Some("Cannot exit synchronized block".to_string())),
+            )),
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
@@ -1739,6 +2791,9 @@ impl<'a> CallFrame<'a> {
             Value::Object(exception) => {
                 Err(MethodCallFailed::ExceptionThrown(JavaException(exception)))
             }
+            Null => Err(MethodCallFailed::InternalError(
+                VmError::NullPointerException(Some("Cannot throw exception".to_string())),
+            )),
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
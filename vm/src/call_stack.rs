@@ -1,6 +1,5 @@
 use std::{fmt, fmt::Formatter};
 
-use log::debug;
 use typed_arena::Arena;
 
 use rjvm_reader::{method_flags::MethodFlags, type_conversion::ToUsizeSafe, ClassFileMethodCode};
@@ -10,16 +9,26 @@ use crate::{
     stack_trace_element::StackTraceElement, value::Value, vm_error::VmError,
 };
 
+/// Maximum number of elements of the stack traces of exceptions; deeper frames are omitted.
+/// Same default as HotSpot's `MaxJavaStackTraceDepth`.
+pub const MAX_STACK_TRACE_DEPTH: usize = 1024;
+
+/// Number of frames that can be added beyond the maximum depth while constructing the
+/// exceptions thrown by the vm, i.e. the `StackOverflowError` itself.
+const RESERVED_FRAMES: usize = 32;
+
 /// A call stack, which will include multiple frames, one for each method call.
 // The allocator will allocate and ensure that our call frames are alive while the call stack is.
 // Thus, we can do some unsafe magic to avoid Rc<RefCell<>>, which would mess up our code when
 // we try to get a stack trace _while_ executing a method, which we need for exceptions.
 // This also means that we _never_ deallocate the call frames, even after we have finished
 // executing them!
-#[derive(Default)]
 pub struct CallStack<'a> {
     frames: Vec<CallFrameReference<'a>>,
     allocator: Arena<CallFrame<'a>>,
+
+    /// Maximum number of frames; adding more fails with a [VmError::StackOverflowError]
+    max_depth: usize,
 }
 
 // SAFETY: The pointer will be valid until the generating call stack is,
@@ -41,8 +50,12 @@ impl<'a> AsMut<CallFrame<'a>> for CallFrameReference<'a> {
 }
 
 impl<'a> CallStack<'a> {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            frames: Vec::new(),
+            allocator: Arena::new(),
+            max_depth,
+        }
     }
 
     /// Adds a new frame to the call stack.
@@ -53,6 +66,9 @@ impl<'a> CallStack<'a> {
         receiver: Option<AbstractObject<'a>>,
         args: Vec<Value<'a>>,
     ) -> Result<CallFrameReference<'a>, VmError> {
+        if self.frames.len() >= self.max_depth {
+            return Err(VmError::StackOverflowError);
+        }
         Self::check_receiver(&class_and_method, receiver.clone())?;
         let code = Self::get_code(&class_and_method)?;
         let locals = Self::prepare_locals(code, receiver, args);
@@ -74,7 +90,7 @@ impl<'a> CallStack<'a> {
                 return Err(VmError::ValidationException);
             }
         } else if receiver.is_none() {
-            return Err(VmError::NullPointerException);
+            return Err(VmError::NullPointerException(None));
         }
         Ok(())
     }
@@ -99,7 +115,7 @@ impl<'a> CallStack<'a> {
         let mut locals: Vec<Value<'a>> = receiver
             .map(Value::Object)
             .into_iter()
-            .chain(args.into_iter())
+            .chain(args)
             .collect();
         while locals.len() < code.max_locals.into_usize_safe() {
             locals.push(Value::Uninitialized);
@@ -114,14 +130,27 @@ impl<'a> CallStack<'a> {
             .ok_or(VmError::ValidationException)
     }
 
+    /// Returns the stack trace, with the innermost frame first, truncated to
+    /// [MAX_STACK_TRACE_DEPTH] elements
     pub fn get_stack_trace_elements(&self) -> Vec<StackTraceElement<'a>> {
         self.frames
             .iter()
             .rev()
+            .take(MAX_STACK_TRACE_DEPTH)
             .map(|frame| frame.as_ref().to_stack_trace_element())
             .collect()
     }
 
+    /// Executes the given function allowing a few more frames than the maximum depth.
+    /// Used to construct exceptions, which would otherwise overflow the stack again
+    /// when thrown because of a stack overflow.
+    pub fn with_reserved_frames<T>(&mut self, function: impl FnOnce(&mut Self) -> T) -> T {
+        self.max_depth += RESERVED_FRAMES;
+        let result = function(self);
+        self.max_depth -= RESERVED_FRAMES;
+        result
+    }
+
     pub fn gc_roots(&mut self) -> impl Iterator<Item = *mut AbstractObject<'a>> {
         let mut roots = vec![];
         roots.extend(
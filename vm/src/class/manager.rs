@@ -11,7 +11,14 @@ use super::{
     Class, ClassId, ClassRef,
 };
 use crate::vm_error::VmError;
-use rjvm_reader::{read_buffer, ClassFile};
+use rjvm_reader::{field_type::FieldType, read_buffer, ClassAccessFlags, ClassFile};
+
+/// The superclass of all array classes, followed by the interfaces they implement
+const ARRAY_SUPERCLASS_AND_INTERFACES: [&str; 3] = [
+    "java/lang/Object",
+    "java/lang/Cloneable",
+    "java/io/Serializable",
+];
 
 /// An object that will allocate and manage Class objects
 pub(crate) struct ClassManager<'a> {
@@ -28,6 +35,9 @@ pub(crate) struct ClassManager<'a> {
     /// In a real implementation, we would have a current class loader for each thread,
     /// in a hierarchy. Currently, we only have exactly ONE global class loader.
     current_class_loader: ClassLoader<'a>,
+
+    /// The initialization state of the loaded classes; missing entries are not initialized
+    initialization_states: HashMap<ClassId, ClassInitializationState>,
 }
 
 impl<'a> Default for ClassManager<'a> {
@@ -39,6 +49,7 @@ impl<'a> Default for ClassManager<'a> {
             arena: Arena::with_capacity(100),
             next_id: 1,
             current_class_loader: Default::default(),
+            initialization_states: Default::default(),
         }
     }
 }
@@ -50,29 +61,45 @@ impl<'a> fmt::Debug for ClassManager<'a> {
 }
 
 /// When a class instance is requested, returns whether the class was already loaded,
-/// or whether the requeste loaded a new class (which will need to be initialized).
+/// or whether the requeste loaded a new class (which will need to be prepared).
 #[derive(Debug, Clone)]
 pub(crate) enum ResolvedClass<'a> {
     AlreadyLoaded(ClassRef<'a>),
-    NewClass(ClassesToInitialize<'a>),
+    NewClass(ClassesToPrepare<'a>),
 }
 
 impl<'a> ResolvedClass<'a> {
     pub fn get_class(&self) -> ClassRef<'a> {
         match self {
             ResolvedClass::AlreadyLoaded(class) => class,
-            ResolvedClass::NewClass(classes_to_initialize) => classes_to_initialize.resolved_class,
+            ResolvedClass::NewClass(classes_to_prepare) => classes_to_prepare.resolved_class,
         }
     }
 }
 
-/// In case a new class was loaded, maps the whole list of the classes that require
-/// initialization, in order so that a base class is initialized _before_ the derived classes.
-/// Includes the newly resolved class in the list [to_initialize].
+/// In case a new class was loaded, maps the whole list of the newly loaded classes, that
+/// require preparation (i.e. the allocation of their static fields), in order so that a
+/// base class comes _before_ the derived classes.
+/// Includes the newly resolved class in the list [to_prepare].
 #[derive(Debug, Clone)]
-pub(crate) struct ClassesToInitialize<'a> {
+pub(crate) struct ClassesToPrepare<'a> {
     resolved_class: ClassRef<'a>,
-    pub(crate) to_initialize: Vec<ClassRef<'a>>,
+    pub(crate) to_prepare: Vec<ClassRef<'a>>,
+}
+
+/// The initialization state of a class, as described in
+/// https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.5
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ClassInitializationState {
+    /// The class has been loaded and linked, but its static initializer has not run yet
+    #[default]
+    NotInitialized,
+    /// The static initializer is running. Since we have only one thread, requesting
+    /// the initialization of a class in this state is a recursive request.
+    InProgress,
+    Initialized,
+    /// The static initializer has failed, thus the class cannot be used
+    Erroneous,
 }
 
 impl<'a> ClassByIdResolver<'a> for ClassManager<'a> {
@@ -93,16 +120,41 @@ impl<'a> ClassManager<'a> {
     pub fn get_or_resolve_class(&mut self, class_name: &str) -> Result<ResolvedClass<'a>, VmError> {
         if let Some(already_loaded_class) = self.find_class_by_name(class_name) {
             Ok(ResolvedClass::AlreadyLoaded(already_loaded_class))
+        } else if class_name.starts_with('[') {
+            self.create_array_class(class_name)
+                .map(ResolvedClass::NewClass)
         } else {
             self.resolve_and_load_class(class_name)
                 .map(ResolvedClass::NewClass)
         }
     }
 
+    pub fn initialization_state(&self, class_id: ClassId) -> ClassInitializationState {
+        self.initialization_states
+            .get(&class_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_initialization_state(&mut self, class_id: ClassId, state: ClassInitializationState) {
+        self.initialization_states.insert(class_id, state);
+    }
+
+    /// Defines a class generated at runtime by the vm, rather than read from the class path
+    pub fn define_class(&mut self, class_file: ClassFile) -> Result<ResolvedClass<'a>, VmError> {
+        if self.find_class_by_name(&class_file.name).is_some() {
+            return Err(VmError::ClassLoadingError(format!(
+                "duplicate class definition: {}",
+                class_file.name
+            )));
+        }
+        self.load_class(class_file).map(ResolvedClass::NewClass)
+    }
+
     fn resolve_and_load_class(
         &mut self,
         class_name: &str,
-    ) -> Result<ClassesToInitialize<'a>, VmError> {
+    ) -> Result<ClassesToPrepare<'a>, VmError> {
         let class_file_bytes = self
             .class_path
             .resolve(class_name)
@@ -113,7 +165,57 @@ impl<'a> ClassManager<'a> {
         self.load_class(class_file)
     }
 
-    fn load_class(&mut self, class_file: ClassFile) -> Result<ClassesToInitialize<'a>, VmError> {
+    /// Array classes do not have a class file; the vm creates them when they are first
+    /// needed. They extend `java.lang.Object`, implement `java.lang.Cloneable` and
+    /// `java.io.Serializable`, and do not declare any field or method.
+    fn create_array_class(&mut self, class_name: &str) -> Result<ClassesToPrepare<'a>, VmError> {
+        let Ok(FieldType::Array(component_type)) = FieldType::parse(class_name) else {
+            return Err(VmError::ClassNotFoundException(class_name.to_string()));
+        };
+
+        let mut referenced_classes: IndexMap<String, ResolvedClass<'a>> = Default::default();
+        match *component_type {
+            FieldType::Base(_) => {}
+            FieldType::Object(component_class_name) => {
+                self.resolve_and_collect_class(&component_class_name, &mut referenced_classes)?
+            }
+            component_type @ FieldType::Array(_) => self.resolve_and_collect_class(
+                &component_type.to_descriptor(),
+                &mut referenced_classes,
+            )?,
+        }
+        for class_name in ARRAY_SUPERCLASS_AND_INTERFACES {
+            self.resolve_and_collect_class(class_name, &mut referenced_classes)?;
+        }
+
+        let superclass = referenced_classes[ARRAY_SUPERCLASS_AND_INTERFACES[0]].get_class();
+        let class = Class {
+            id: self.next_class_id(),
+            name: class_name.to_string(),
+            version: Default::default(),
+            source_file: None,
+            constants: Default::default(),
+            flags: ClassAccessFlags::PUBLIC | ClassAccessFlags::FINAL | ClassAccessFlags::ABSTRACT,
+            superclass: Some(superclass),
+            interfaces: ARRAY_SUPERCLASS_AND_INTERFACES[1..]
+                .iter()
+                .map(|interface_name| referenced_classes[*interface_name].get_class())
+                .collect(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            bootstrap_methods: Vec::new(),
+            num_total_fields: superclass.num_total_fields,
+            first_field_index: superclass.num_total_fields,
+        };
+        debug!("creating array class {} with id {}", class.name, class.id);
+
+        // Array classes have no static fields, so they never need to be prepared
+        let loaded_class = self.store_class(class, &referenced_classes, false);
+        self.register_loaded_class(loaded_class.resolved_class);
+        Ok(loaded_class)
+    }
+
+    fn load_class(&mut self, class_file: ClassFile) -> Result<ClassesToPrepare<'a>, VmError> {
         let referenced_classes = self.resolve_super_and_interfaces(&class_file)?;
         let loaded_class = self.allocate(class_file, referenced_classes)?;
         self.register_loaded_class(loaded_class.resolved_class);
@@ -148,13 +250,27 @@ impl<'a> ClassManager<'a> {
         &mut self,
         class_file: ClassFile,
         referenced_classes: IndexMap<String, ResolvedClass<'a>>,
-    ) -> Result<ClassesToInitialize<'a>, VmError> {
+    ) -> Result<ClassesToPrepare<'a>, VmError> {
+        let id = self.next_class_id();
+        debug!("loading class {} from file {}", id, class_file.name);
+        let class = Self::new_class(class_file, id, &referenced_classes)?;
+        Ok(self.store_class(class, &referenced_classes, true))
+    }
+
+    fn next_class_id(&mut self) -> ClassId {
         let next_id = self.next_id;
         self.next_id += 1;
+        ClassId::new(next_id)
+    }
 
-        let id = ClassId::new(next_id);
-        debug!("loading class {} from file {}", id, class_file.name);
-        let class = Self::new_class(class_file, id, &referenced_classes)?;
+    /// Moves the class in the arena, and returns the list of classes that need to be
+    /// prepared, i.e. the newly loaded referenced classes and, optionally, the class itself.
+    fn store_class(
+        &mut self,
+        class: Class<'a>,
+        referenced_classes: &IndexMap<String, ResolvedClass<'a>>,
+        requires_preparation: bool,
+    ) -> ClassesToPrepare<'a> {
         let class_ref = self.arena.alloc(class);
 
         // SAFETY: our reference class_ref is alive only for 'b.
@@ -167,29 +283,31 @@ impl<'a> ClassManager<'a> {
             &*class_ptr
         };
 
-        let mut classes_to_init: Vec<ClassRef<'a>> = Vec::new();
+        let mut classes_to_prepare: Vec<ClassRef<'a>> = Vec::new();
         for resolved_class in referenced_classes.values() {
             if let ResolvedClass::NewClass(new_class) = resolved_class {
-                for to_initialize in new_class.to_initialize.iter() {
-                    classes_to_init.push(to_initialize)
+                for to_prepare in new_class.to_prepare.iter() {
+                    classes_to_prepare.push(to_prepare)
                 }
             }
         }
-        classes_to_init.push(class_ref);
+        if requires_preparation {
+            classes_to_prepare.push(class_ref);
+        }
 
         debug!(
-            "initializing class {}, classes to init {:?}",
+            "loaded class {}, classes to prepare {:?}",
             class_ref.name,
-            classes_to_init
+            classes_to_prepare
                 .iter()
                 .map(|c| &c.name)
                 .collect::<Vec<&String>>()
         );
 
-        Ok(ClassesToInitialize {
+        ClassesToPrepare {
             resolved_class: class_ref,
-            to_initialize: classes_to_init,
-        })
+            to_prepare: classes_to_prepare,
+        }
     }
 
     fn new_class(
@@ -224,6 +342,7 @@ impl<'a> ClassManager<'a> {
             interfaces,
             fields: class_file.fields,
             methods: class_file.methods,
+            bootstrap_methods: class_file.bootstrap_methods,
             num_total_fields: num_superclass_fields + num_this_class_fields,
             first_field_index: num_superclass_fields,
         })
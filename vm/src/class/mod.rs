@@ -5,7 +5,8 @@ pub mod path_entry;
 pub mod resolver;
 
 use rjvm_reader::{
-    ClassAccessFlags, ClassFileField, ClassFileMethod, ClassFileVersion, ConstantPool,
+    BootstrapMethod, ClassAccessFlags, ClassFileField, ClassFileMethod, ClassFileVersion,
+    ConstantPool, MethodFlags,
 };
 use std::{fmt, fmt::Formatter};
 
@@ -48,6 +49,8 @@ pub struct Class<'a> {
     pub interfaces: Vec<ClassRef<'a>>,
     pub fields: Vec<ClassFileField>,
     pub methods: Vec<ClassFileMethod>,
+    /// The entries of the `BootstrapMethods` attribute, used to link `invokedynamic`
+    pub bootstrap_methods: Vec<BootstrapMethod>,
     // Base classes field have the same index they have in the base class, and our own
     // field come after. This is the index of the first "owned" field.
     // Note that this will include the static fields, as required by the bytecode specs.
@@ -65,10 +68,22 @@ impl<'a> Class<'a> {
         self.name == base.name
             || self
                 .superclass
-                .map_or(false, |superclass| superclass.is_subclass_of(base))
+                .is_some_and(|superclass| superclass.is_subclass_of(base))
             || self.interfaces.iter().any(|intf| intf.is_subclass_of(base))
     }
 
+    pub fn is_interface(&self) -> bool {
+        self.flags.contains(ClassAccessFlags::INTERFACE)
+    }
+
+    /// Returns whether the class declares a non-abstract, non-static method, i.e. for
+    /// interfaces, whether it declares a default method
+    pub fn declares_default_methods(&self) -> bool {
+        self.methods
+            .iter()
+            .any(|method| !method.is_static() && !method.flags.contains(MethodFlags::ABSTRACT))
+    }
+
     pub fn find_method(
         &self,
         method_name: &str,
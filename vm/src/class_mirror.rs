@@ -0,0 +1,167 @@
+use rjvm_reader::field_type::{BaseType, FieldType};
+
+use crate::{
+    abstract_object::{AbstractObject, ObjectKind},
+    array::Array,
+    array_entry_type::ArrayEntryType,
+    call_stack::CallStack,
+    class::ClassRef,
+    exceptions::MethodCallFailed,
+    object::Object,
+    vm::Vm,
+    vm_error::VmError,
+};
+
+/// The type represented by an instance of `java.lang.Class`, i.e. by its "mirror".
+/// Every type has exactly one mirror, including primitive types, `void` and arrays.
+#[derive(Debug, Clone)]
+pub enum MirroredType<'a> {
+    Void,
+    Primitive(BaseType),
+    Class(ClassRef<'a>),
+    /// An array, with the given component type
+    Array(Box<MirroredType<'a>>),
+}
+
+impl<'a> MirroredType<'a> {
+    /// Returns the type with the given descriptor, resolving the classes that it references
+    pub fn of_field_type(
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        field_type: &FieldType,
+    ) -> Result<Self, MethodCallFailed<'a>> {
+        Ok(match field_type {
+            FieldType::Base(base_type) => MirroredType::Primitive(base_type.clone()),
+            FieldType::Object(class_name) => {
+                MirroredType::Class(vm.get_or_resolve_class(call_stack, class_name)?)
+            }
+            FieldType::Array(component_type) => {
+                // Array classes are created when first needed; we make sure that the class
+                // exists, since it defines the supertypes of the array
+                vm.get_or_resolve_class(call_stack, &field_type.to_descriptor())?;
+                MirroredType::Array(Box::new(Self::of_field_type(
+                    vm,
+                    call_stack,
+                    component_type,
+                )?))
+            }
+        })
+    }
+
+    /// Returns the type referenced by a `CONSTANT_Class`, whose name is either the binary name
+    /// of a class, i.e. `java/lang/String`, or the descriptor of an array, i.e. `[I`
+    pub fn of_class_name(
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        class_name: &str,
+    ) -> Result<Self, MethodCallFailed<'a>> {
+        if class_name.starts_with('[') {
+            let field_type =
+                FieldType::parse(class_name).map_err(|_| VmError::ValidationException)?;
+            Self::of_field_type(vm, call_stack, &field_type)
+        } else {
+            Ok(MirroredType::Class(
+                vm.get_or_resolve_class(call_stack, class_name)?,
+            ))
+        }
+    }
+
+    /// Returns the primitive type with the given name, i.e. `int` or `void`
+    pub fn of_primitive_name(name: &str) -> Option<Self> {
+        let base_type = match name {
+            "void" => return Some(MirroredType::Void),
+            "byte" => BaseType::Byte,
+            "char" => BaseType::Char,
+            "double" => BaseType::Double,
+            "float" => BaseType::Float,
+            "int" => BaseType::Int,
+            "long" => BaseType::Long,
+            "short" => BaseType::Short,
+            "boolean" => BaseType::Boolean,
+            _ => return None,
+        };
+        Some(MirroredType::Primitive(base_type))
+    }
+
+    /// Returns the type of the given object
+    pub fn of_object(vm: &Vm<'a>, object: &AbstractObject<'a>) -> Result<Self, VmError> {
+        match object.kind() {
+            ObjectKind::Object => Ok(MirroredType::Class(vm.get_class_by_id(object.class_id())?)),
+            ObjectKind::Array => Ok(MirroredType::Array(Box::new(Self::of_array_entry_type(
+                vm,
+                object.elements_type(),
+            )?))),
+        }
+    }
+
+    fn of_array_entry_type(vm: &Vm<'a>, entry_type: ArrayEntryType) -> Result<Self, VmError> {
+        match entry_type {
+            ArrayEntryType::Base(base_type) => Ok(MirroredType::Primitive(base_type)),
+            ArrayEntryType::Object(class_id) => {
+                Ok(MirroredType::Class(vm.get_class_by_id(class_id)?))
+            }
+            entry_type @ ArrayEntryType::Array(..) => {
+                let component_type = entry_type
+                    .component_type()
+                    .ok_or(VmError::ValidationException)?;
+                Ok(MirroredType::Array(Box::new(Self::of_array_entry_type(
+                    vm,
+                    component_type,
+                )?)))
+            }
+        }
+    }
+
+    /// The descriptor of the type, i.e. `I` or `[Ljava/lang/String;`, which identifies it
+    pub fn descriptor(&self) -> String {
+        match self {
+            MirroredType::Void => "V".to_string(),
+            MirroredType::Primitive(base_type) => base_type.to_descriptor().to_string(),
+            MirroredType::Class(class) => format!("L{};", class.name),
+            MirroredType::Array(component_type) => format!("[{}", component_type.descriptor()),
+        }
+    }
+
+    /// The name returned by `Class.getName()`, i.e. `int`, `java.lang.String`
+    /// or `[Ljava.lang.String;`
+    pub fn java_name(&self) -> String {
+        match self {
+            MirroredType::Void => "void".to_string(),
+            MirroredType::Primitive(base_type) => base_type.to_string().to_lowercase(),
+            MirroredType::Class(class) => class.name.replace('/', "."),
+            MirroredType::Array(_) => self.descriptor().replace('/', "."),
+        }
+    }
+
+    /// Whether a value of the given type can be assigned to a variable of this type,
+    /// as specified by `Class.isAssignableFrom`
+    pub fn is_assignable_from(
+        &self,
+        vm: &Vm<'a>,
+        other: &MirroredType<'a>,
+    ) -> Result<bool, VmError> {
+        Ok(match (self, other) {
+            (MirroredType::Void, MirroredType::Void) => true,
+            (MirroredType::Primitive(base_type), MirroredType::Primitive(other_base_type)) => {
+                base_type == other_base_type
+            }
+            (MirroredType::Class(class), MirroredType::Class(other_class)) => {
+                other_class.is_subclass_of(class)
+            }
+            (MirroredType::Class(class), MirroredType::Array(_)) => {
+                other.array_class(vm)?.is_subclass_of(class)
+            }
+            (MirroredType::Array(component_type), MirroredType::Array(other_component_type)) => {
+                component_type.is_assignable_from(vm, other_component_type)?
+            }
+            _ => false,
+        })
+    }
+
+    /// Returns the class of an array type, which exists since the array, or its type,
+    /// have been resolved
+    fn array_class(&self, vm: &Vm<'a>) -> Result<ClassRef<'a>, VmError> {
+        vm.find_class_by_name(&self.descriptor())
+            .ok_or(VmError::ValidationException)
+    }
+}
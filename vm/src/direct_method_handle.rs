@@ -0,0 +1,279 @@
+use rjvm_reader::{
+    field_type::{BaseType, FieldType},
+    method_descriptor::MethodDescriptor,
+};
+
+use crate::{
+    abstract_object::ObjectKind,
+    call_stack::CallStack,
+    class::ClassRef,
+    exceptions::MethodCallFailed,
+    handle::MethodHandleKind,
+    invoke_dynamic::{get_method_handle_reference, parse_method_descriptor},
+    lambda_metafactory::{unwrapped_type_of, wrapper_of},
+    object::Object,
+    value::Value,
+    vm::Vm,
+    vm_error::VmError,
+};
+
+/// A method handle created by resolving a `CONSTANT_MethodHandle`, which directly
+/// reads or writes a field, or invokes a method or a constructor.
+#[derive(Debug, Clone)]
+pub(crate) struct DirectMethodHandle<'a> {
+    pub kind: MethodHandleKind,
+    pub class: ClassRef<'a>,
+    pub member_name: String,
+    pub member_descriptor: String,
+    /// The type of the handle, i.e. the arguments expected by `invokeExact` and its return value
+    pub method_type: MethodDescriptor,
+}
+
+impl<'a> DirectMethodHandle<'a> {
+    /// Resolves the given method handle constant of the class
+    pub fn resolve(
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        class: ClassRef<'a>,
+        constant_index: u16,
+    ) -> Result<Self, MethodCallFailed<'a>> {
+        let reference = get_method_handle_reference(class, constant_index)?;
+        let member_class = vm.get_or_resolve_class(call_stack, reference.class_name)?;
+        let method_type =
+            Self::method_type_of(reference.kind, reference.class_name, reference.descriptor)?;
+        Ok(Self {
+            kind: reference.kind,
+            class: member_class,
+            member_name: reference.member_name.to_string(),
+            member_descriptor: reference.descriptor.to_string(),
+            method_type,
+        })
+    }
+
+    /// Computes the type of a method handle, as specified in
+    /// https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.3.5
+    fn method_type_of(
+        kind: MethodHandleKind,
+        class_name: &str,
+        descriptor: &str,
+    ) -> Result<MethodDescriptor, VmError> {
+        let class_type = FieldType::Object(class_name.to_string());
+        let method_type = match kind {
+            MethodHandleKind::GetField
+            | MethodHandleKind::GetStatic
+            | MethodHandleKind::PutField
+            | MethodHandleKind::PutStatic => {
+                let field_type =
+                    FieldType::parse(descriptor).map_err(|_| VmError::ValidationException)?;
+                match kind {
+                    MethodHandleKind::GetField => MethodDescriptor {
+                        parameters: vec![class_type],
+                        return_type: Some(field_type),
+                    },
+                    MethodHandleKind::GetStatic => MethodDescriptor {
+                        parameters: vec![],
+                        return_type: Some(field_type),
+                    },
+                    MethodHandleKind::PutField => MethodDescriptor {
+                        parameters: vec![class_type, field_type],
+                        return_type: None,
+                    },
+                    _ => MethodDescriptor {
+                        parameters: vec![field_type],
+                        return_type: None,
+                    },
+                }
+            }
+            MethodHandleKind::InvokeStatic => parse_method_descriptor(descriptor)?,
+            MethodHandleKind::NewInvokeSpecial => MethodDescriptor {
+                parameters: parse_method_descriptor(descriptor)?.parameters,
+                return_type: Some(class_type),
+            },
+            MethodHandleKind::InvokeVirtual
+            | MethodHandleKind::InvokeSpecial
+            | MethodHandleKind::InvokeInterface => {
+                let method_descriptor = parse_method_descriptor(descriptor)?;
+                let mut parameters = vec![class_type];
+                parameters.extend(method_descriptor.parameters);
+                MethodDescriptor {
+                    parameters,
+                    return_type: method_descriptor.return_type,
+                }
+            }
+        };
+        Ok(method_type)
+    }
+}
+
+/// Formats a method type like `MethodType.toString`, i.e. `(int,String)Object`
+pub(crate) fn describe_method_type(method_type: &MethodDescriptor) -> String {
+    let parameters: Vec<String> = method_type.parameters.iter().map(simple_name).collect();
+    let return_type = method_type
+        .return_type
+        .as_ref()
+        .map(simple_name)
+        .unwrap_or_else(|| "void".to_string());
+    format!("({}){}", parameters.join(","), return_type)
+}
+
+fn simple_name(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Base(base_type) => base_type.to_string().to_lowercase(),
+        FieldType::Object(class_name) => class_name
+            .rsplit(['/', '$'])
+            .next()
+            .unwrap_or(class_name)
+            .to_string(),
+        FieldType::Array(component_type) => format!("{}[]", simple_name(component_type)),
+    }
+}
+
+/// Adapts a value passed to `MethodHandle.invoke`, or returned by it, like `MethodHandle.asType`:
+/// primitives can be widened, boxed and unboxed, and references are cast.
+pub(crate) fn convert_value<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    value: Value<'a>,
+    from: &FieldType,
+    to: &FieldType,
+) -> Result<Value<'a>, MethodCallFailed<'a>> {
+    if from == to {
+        return Ok(value);
+    }
+    match (from, to) {
+        (FieldType::Base(_), FieldType::Base(to)) => widen(value, to),
+        (FieldType::Base(from), _) => {
+            let (wrapper, _) = wrapper_of(from);
+            let value_of = vm.resolve_class_method(
+                call_stack,
+                wrapper,
+                "valueOf",
+                &format!("({})L{wrapper};", from.to_descriptor()),
+            )?;
+            let mut arguments = vec![value];
+            if matches!(from, BaseType::Long | BaseType::Double) {
+                arguments.push(Value::Uninitialized);
+            }
+            let boxed = vm
+                .invoke(call_stack, value_of, None, arguments)?
+                .ok_or(VmError::ValidationException)?;
+            check_cast(vm, call_stack, boxed, to)
+        }
+        (_, FieldType::Base(to)) => {
+            let object = match value {
+                Value::Object(object) => object,
+                Value::Null => {
+                    return Err(MethodCallFailed::InternalError(
+                        VmError::NullPointerException(None),
+                    ))
+                }
+                _ => {
+                    return Err(MethodCallFailed::InternalError(
+                        VmError::ValidationException,
+                    ))
+                }
+            };
+            let class = vm.get_class_by_id(object.class_id())?;
+            let unwrapped_type = unwrapped_type_of(&class.name).ok_or_else(|| {
+                VmError::ClassCastException(format!(
+                    "Cannot unbox {} to {}",
+                    class.name.replace('/', "."),
+                    to.to_string().to_lowercase()
+                ))
+            })?;
+            let (_, unboxing_method_name) = wrapper_of(&unwrapped_type);
+            let unboxing_method = vm.resolve_class_method(
+                call_stack,
+                &class.name,
+                unboxing_method_name,
+                &format!("(){}", unwrapped_type.to_descriptor()),
+            )?;
+            let unboxed = vm
+                .invoke(call_stack, unboxing_method, Some(object), Vec::new())?
+                .ok_or(VmError::ValidationException)?;
+            widen(unboxed, to)
+        }
+        _ => check_cast(vm, call_stack, value, to),
+    }
+}
+
+/// Applies a widening primitive conversion
+fn widen<'a>(value: Value<'a>, to: &BaseType) -> Result<Value<'a>, MethodCallFailed<'a>> {
+    let widened = match (value, to) {
+        (Value::Int(value), BaseType::Long) => Value::Long(value as i64),
+        (Value::Int(value), BaseType::Float) => Value::Float(value as f32),
+        (Value::Int(value), BaseType::Double) => Value::Double(value as f64),
+        (Value::Long(value), BaseType::Float) => Value::Float(value as f32),
+        (Value::Long(value), BaseType::Double) => Value::Double(value as f64),
+        (Value::Float(value), BaseType::Double) => Value::Double(value as f64),
+        (value @ Value::Int(_), _) => value,
+        (value @ Value::Long(_), BaseType::Long)
+        | (value @ Value::Float(_), BaseType::Float)
+        | (value @ Value::Double(_), BaseType::Double) => value,
+        _ => {
+            return Err(MethodCallFailed::InternalError(
+                VmError::WrongMethodTypeException(format!(
+                    "cannot convert to {}",
+                    to.to_string().to_lowercase()
+                )),
+            ))
+        }
+    };
+    Ok(widened)
+}
+
+fn check_cast<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    value: Value<'a>,
+    to: &FieldType,
+) -> Result<Value<'a>, MethodCallFailed<'a>> {
+    if let (Value::Object(object), FieldType::Object(class_name)) = (&value, to) {
+        if object.kind() == ObjectKind::Object {
+            let expected_class = vm.get_or_resolve_class(call_stack, class_name)?;
+            let object_class = vm.get_class_by_id(object.class_id())?;
+            if !object_class.is_subclass_of(expected_class) {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::ClassCastException(format!(
+                        "Cannot cast {} to {}",
+                        object_class.name.replace('/', "."),
+                        class_name.replace('/', ".")
+                    )),
+                ));
+            }
+        }
+    }
+    Ok(value)
+}
+
+/// Returns the default value of a type, used when `invoke` adapts a void method
+/// to a call site that expects a value
+pub(crate) fn default_value<'a>(field_type: &FieldType) -> Value<'a> {
+    match field_type {
+        FieldType::Base(BaseType::Long) => Value::Long(0),
+        FieldType::Base(BaseType::Float) => Value::Float(0.0),
+        FieldType::Base(BaseType::Double) => Value::Double(0.0),
+        FieldType::Base(_) => Value::Int(0),
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rjvm_reader::method_descriptor::MethodDescriptor;
+
+    use crate::direct_method_handle::describe_method_type;
+
+    #[test]
+    fn method_types_are_described_like_java() {
+        let method_type =
+            MethodDescriptor::parse("(ILjava/lang/String;[J)Lrjvm/Outer$Inner;").unwrap();
+        assert_eq!(
+            "(int,String,long[])Inner",
+            describe_method_type(&method_type)
+        );
+
+        let method_type = MethodDescriptor::parse("()V").unwrap();
+        assert_eq!("()void", describe_method_type(&method_type));
+    }
+}
@@ -1,11 +1,15 @@
-use std::{alloc::Layout, fmt, fmt::Formatter, marker::PhantomData, ptr::null};
+use std::{
+    alloc::Layout, collections::HashSet, fmt, fmt::Formatter, marker::PhantomData, ptr::null,
+};
 
 use log::{debug, info};
 
 use rjvm_reader::{field_type::FieldType, type_conversion::ToUsizeSafe};
 
 use crate::{
-    abstract_object::{AbstractObject, AllocHeader, GcState, ObjectKind, ALLOC_HEADER_SIZE},
+    abstract_object::{
+        AbstractObject, AllocHeader, GcState, ObjectId, ObjectKind, ALLOC_HEADER_SIZE,
+    },
     alloc_entry::AllocEntry,
     array::Array,
     array_entry_type::ArrayEntryType,
@@ -93,6 +97,7 @@ impl MemoryChunk {
 pub struct ObjectAllocator<'a> {
     current: MemoryChunk,
     other: MemoryChunk,
+    next_object_id: u64,
     marker: PhantomData<&'a AbstractObject<'a>>,
 }
 
@@ -102,6 +107,7 @@ impl<'a> ObjectAllocator<'a> {
         Self {
             current: MemoryChunk::new(semi_space_capacity),
             other: MemoryChunk::new(semi_space_capacity),
+            next_object_id: 0,
             marker: Default::default(),
         }
     }
@@ -109,29 +115,45 @@ impl<'a> ObjectAllocator<'a> {
     /// Allocates a new object, or returns None if the memory is full
     pub fn allocate_object(&mut self, class: &Class<'a>) -> Option<AbstractObject<'a>> {
         let size = AbstractObject::size_of_object(class);
-        self.current
-            .alloc(size)
-            .map(|alloc_entry| AbstractObject::new_object(class, alloc_entry))
+        let alloc_entry = self.current.alloc(size)?;
+        Some(AbstractObject::new_object(
+            class,
+            self.next_object_id(),
+            alloc_entry,
+        ))
     }
 
     /// Allocates a new array, or returns None if the memory is full
     pub fn allocate_array(
         &mut self,
+        array_class: &Class<'a>,
         elements_type: ArrayEntryType,
         length: usize,
     ) -> Option<AbstractObject<'a>> {
         let size = AbstractObject::size_of_array(length);
-        self.current
-            .alloc(size)
-            .map(|alloc_entry| AbstractObject::new_array(elements_type, length, &alloc_entry))
+        let alloc_entry = self.current.alloc(size)?;
+        Some(AbstractObject::new_array(
+            array_class,
+            self.next_object_id(),
+            elements_type,
+            length,
+            &alloc_entry,
+        ))
+    }
+
+    fn next_object_id(&mut self) -> ObjectId {
+        self.next_object_id += 1;
+        ObjectId::new(self.next_object_id)
     }
 
     /// Runs the garbage collection! Will update the roots with the new addresses of the objects.
+    /// Returns the ids of the objects that survived, so that the caller can drop whatever
+    /// it stores about the other ones.
     pub unsafe fn do_garbage_collection(
         &mut self,
         roots: Vec<*mut AbstractObject<'a>>,
         class_resolver: &impl ClassByIdResolver<'a>,
-    ) -> Result<(), VmError> {
+    ) -> Result<HashSet<ObjectId>, VmError> {
         info!(
             "running gc; currently allocated memory = {}, gc roots count: {}",
             self.current.used,
@@ -142,7 +164,7 @@ impl<'a> ObjectAllocator<'a> {
         for root in roots.iter() {
             self.visit(*root, class_resolver)?;
         }
-        self.fix_references_in_new_region(class_resolver)?;
+        let survivors = self.fix_references_in_new_region(class_resolver)?;
         for root in roots {
             self.fix_gc_root(root);
         }
@@ -155,7 +177,7 @@ impl<'a> ObjectAllocator<'a> {
         );
         self.other.reset();
 
-        Ok(())
+        Ok(survivors)
     }
 
     /// Visits a given object, unless it was already processed.
@@ -256,7 +278,7 @@ impl<'a> ObjectAllocator<'a> {
                 // No objects are kept alive by this GC-reachable array!
                 Ok(())
             }
-            ArrayEntryType::Object(_) => {
+            ArrayEntryType::Object(_) | ArrayEntryType::Array(..) => {
                 for i in 0..array.len().into_usize_safe() {
                     let value = array.get_element(i);
                     match value {
@@ -272,23 +294,23 @@ impl<'a> ObjectAllocator<'a> {
                 }
                 Ok(())
             }
-            ArrayEntryType::Array => {
-                todo!("arrays of arrays are not supported yet")
-            }
         }
     }
 
     /// Iterates over the copied objects in the new region, which still have pointers to the
     /// objects in the original semispace, by updating the references with the copies' addresses.
+    /// Returns the ids of all the copied objects.
     unsafe fn fix_references_in_new_region(
         &mut self,
         class_resolver: &impl ClassByIdResolver<'a>,
-    ) -> Result<(), VmError> {
+    ) -> Result<HashSet<ObjectId>, VmError> {
+        let mut survivors = HashSet::new();
         let end_ptr = self.other.memory.add(self.other.used);
         let mut ptr = self.other.memory;
         while ptr < end_ptr {
             let header = &mut *(ptr as *mut AllocHeader);
             let object = AbstractObject::from_raw_ptr(ptr);
+            survivors.insert(object.id());
 
             if header.kind() == ObjectKind::Object {
                 self.fix_references_in_object(object, class_resolver)?;
@@ -299,7 +321,7 @@ impl<'a> ObjectAllocator<'a> {
             header.set_state(GcState::Unmarked);
             ptr = ptr.add(header.size());
         }
-        Ok(())
+        Ok(survivors)
     }
 
     /// Fixes all the references for each field in the given object
@@ -342,8 +364,8 @@ impl<'a> ObjectAllocator<'a> {
                 // No objects are kept alive by this GC-reachable array!
                 Ok(())
             }
-            ArrayEntryType::Object(class_id) => {
-                debug!("fixing entries of array {array:?} of type {class_id}");
+            elements_type @ (ArrayEntryType::Object(_) | ArrayEntryType::Array(..)) => {
+                debug!("fixing entries of array {array:?} of type {elements_type:?}");
                 for i in 0..array.len().into_usize_safe() {
                     let element_ptr = array.ptr_to_array_element(i);
                     debug!(
@@ -359,9 +381,6 @@ impl<'a> ObjectAllocator<'a> {
                 }
                 Ok(())
             }
-            ArrayEntryType::Array => {
-                todo!("arrays of arrays are not supported yet")
-            }
         }
     }
 
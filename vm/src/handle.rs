@@ -22,7 +22,7 @@ impl MethodHandle {
         // TODO: For kind == 8 check if method name != "<init>"
 
         match kind {
-            1 | 2 | 3 | 4 => {
+            1..=4 => {
                 if let ConstantPoolEntry::FieldReference(i, j) = entry {
                     Ok(Self::FieldRef(*i, *j))
                 } else {
@@ -64,3 +64,36 @@ impl MethodHandle {
         }
     }
 }
+
+/// The kind of a method handle constant, i.e. the bytecode behavior that it models:
+/// https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.3.5
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MethodHandleKind {
+    GetField,
+    GetStatic,
+    PutField,
+    PutStatic,
+    InvokeVirtual,
+    InvokeStatic,
+    InvokeSpecial,
+    NewInvokeSpecial,
+    InvokeInterface,
+}
+
+impl MethodHandleKind {
+    /// Converts the `reference_kind` stored in the constant pool
+    pub fn from_reference_kind(kind: u8) -> Result<MethodHandleKind, VmError> {
+        match kind {
+            1 => Ok(Self::GetField),
+            2 => Ok(Self::GetStatic),
+            3 => Ok(Self::PutField),
+            4 => Ok(Self::PutStatic),
+            5 => Ok(Self::InvokeVirtual),
+            6 => Ok(Self::InvokeStatic),
+            7 => Ok(Self::InvokeSpecial),
+            8 => Ok(Self::NewInvokeSpecial),
+            9 => Ok(Self::InvokeInterface),
+            _ => Err(VmError::ValidationException),
+        }
+    }
+}
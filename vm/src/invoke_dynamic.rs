@@ -0,0 +1,217 @@
+use rjvm_reader::{
+    method_descriptor::MethodDescriptor, program_counter::ProgramCounter, ClassFileMethod,
+    ConstantPool, ConstantPoolEntry,
+};
+
+use crate::{
+    call_stack::CallStack,
+    class::{ClassId, ClassRef},
+    class_and_method::ClassAndMethod,
+    exceptions::MethodCallFailed,
+    handle::{MethodHandle, MethodHandleKind},
+    lambda_metafactory::spin_lambda_class,
+    string_concat::StringConcatRecipe,
+    vm::Vm,
+    vm_error::VmError,
+};
+
+/// Identifies an `invokedynamic` instruction. Every instruction is a separate call site,
+/// which is linked once, even if it refers to the same constant as another instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct CallSiteId {
+    class_id: ClassId,
+    method: *const ClassFileMethod,
+    pc: ProgramCounter,
+}
+
+impl CallSiteId {
+    pub fn new(class_and_method: &ClassAndMethod, pc: ProgramCounter) -> Self {
+        Self {
+            class_id: class_and_method.class.id,
+            method: class_and_method.method,
+            pc,
+        }
+    }
+}
+
+/// The behavior of a linked call site
+#[derive(Debug, Clone)]
+pub(crate) enum CallSite<'a> {
+    /// Creates a new instance of the given class, generated by the `LambdaMetafactory`,
+    /// storing the arguments of the call site in its fields.
+    Lambda(ClassRef<'a>),
+    /// Concatenates the arguments of the call site into a new string
+    StringConcat(StringConcatRecipe),
+}
+
+/// The symbolic reference of a method handle constant
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MethodHandleReference<'b> {
+    pub kind: MethodHandleKind,
+    pub class_name: &'b str,
+    pub member_name: &'b str,
+    pub descriptor: &'b str,
+    /// Whether the reference is an `InterfaceMethodref`
+    pub is_interface: bool,
+}
+
+/// Links the call site of an `invokedynamic` instruction, by running its bootstrap method.
+/// We do not execute the bootstrap methods in java; rather, we support a few well-known
+/// bootstrap methods, that we implement natively.
+pub(crate) fn link_call_site<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    class: ClassRef<'a>,
+    constant_index: u16,
+) -> Result<CallSite<'a>, MethodCallFailed<'a>> {
+    let constants = &class.constants;
+    let (bootstrap_method_index, name_and_type_index) =
+        match get_constant(constants, constant_index)? {
+            &ConstantPoolEntry::InvokeDynamicInfo(bootstrap_method_index, name_and_type_index) => {
+                (bootstrap_method_index, name_and_type_index)
+            }
+            _ => {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::ValidationException,
+                ))
+            }
+        };
+    let (name, descriptor) = get_name_and_type(constants, name_and_type_index)?;
+    let bootstrap_method = class
+        .bootstrap_methods
+        .get(bootstrap_method_index as usize)
+        .ok_or(VmError::ValidationException)?;
+    let bootstrap_method_handle =
+        get_method_handle_reference(class, bootstrap_method.method_handle_index)?;
+    debug!(
+        "linking call site {name}:{descriptor} with bootstrap method {:?}",
+        bootstrap_method_handle
+    );
+
+    match (
+        bootstrap_method_handle.class_name,
+        bootstrap_method_handle.member_name,
+    ) {
+        ("java/lang/invoke/LambdaMetafactory", "metafactory" | "altMetafactory") => {
+            let alternate = bootstrap_method_handle.member_name == "altMetafactory";
+            let lambda_class = spin_lambda_class(
+                vm,
+                call_stack,
+                class,
+                name,
+                descriptor,
+                &bootstrap_method.arguments,
+                alternate,
+            )?;
+            Ok(CallSite::Lambda(lambda_class))
+        }
+        ("java/lang/invoke/StringConcatFactory", "makeConcatWithConstants") => {
+            let recipe =
+                StringConcatRecipe::with_constants(class, descriptor, &bootstrap_method.arguments)?;
+            Ok(CallSite::StringConcat(recipe))
+        }
+        ("java/lang/invoke/StringConcatFactory", "makeConcat") => {
+            let recipe = StringConcatRecipe::without_constants(descriptor)?;
+            Ok(CallSite::StringConcat(recipe))
+        }
+        _ => Err(MethodCallFailed::InternalError(
+            VmError::BootstrapMethodError(format!(
+                "unsupported bootstrap method {}.{}",
+                bootstrap_method_handle.class_name, bootstrap_method_handle.member_name
+            )),
+        )),
+    }
+}
+
+pub(crate) fn get_constant(
+    constants: &ConstantPool,
+    constant_index: u16,
+) -> Result<&ConstantPoolEntry, VmError> {
+    constants
+        .get(constant_index)
+        .map_err(|_| VmError::ValidationException)
+}
+
+pub(crate) fn get_utf8(constants: &ConstantPool, constant_index: u16) -> Result<&str, VmError> {
+    match get_constant(constants, constant_index)? {
+        ConstantPoolEntry::Utf8(string) => Ok(string),
+        _ => Err(VmError::ValidationException),
+    }
+}
+
+pub(crate) fn get_class_name(
+    constants: &ConstantPool,
+    constant_index: u16,
+) -> Result<&str, VmError> {
+    match get_constant(constants, constant_index)? {
+        &ConstantPoolEntry::ClassReference(name_index) => get_utf8(constants, name_index),
+        _ => Err(VmError::ValidationException),
+    }
+}
+
+pub(crate) fn get_name_and_type(
+    constants: &ConstantPool,
+    constant_index: u16,
+) -> Result<(&str, &str), VmError> {
+    match get_constant(constants, constant_index)? {
+        &ConstantPoolEntry::NameAndTypeDescriptor(name_index, type_descriptor_index) => Ok((
+            get_utf8(constants, name_index)?,
+            get_utf8(constants, type_descriptor_index)?,
+        )),
+        _ => Err(VmError::ValidationException),
+    }
+}
+
+pub(crate) fn get_integer(constants: &ConstantPool, constant_index: u16) -> Result<i32, VmError> {
+    match get_constant(constants, constant_index)? {
+        &ConstantPoolEntry::Integer(value) => Ok(value),
+        _ => Err(VmError::ValidationException),
+    }
+}
+
+pub(crate) fn parse_method_descriptor(descriptor: &str) -> Result<MethodDescriptor, VmError> {
+    MethodDescriptor::parse(descriptor).map_err(|_| VmError::ValidationException)
+}
+
+/// Returns the descriptor of a `MethodType` constant
+pub(crate) fn get_method_type(
+    constants: &ConstantPool,
+    constant_index: u16,
+) -> Result<&str, VmError> {
+    match get_constant(constants, constant_index)? {
+        &ConstantPoolEntry::MethodType(descriptor_index) => get_utf8(constants, descriptor_index),
+        _ => Err(VmError::ValidationException),
+    }
+}
+
+pub(crate) fn get_method_handle_reference(
+    class: ClassRef<'_>,
+    constant_index: u16,
+) -> Result<MethodHandleReference<'_>, VmError> {
+    let constants = &class.constants;
+    let &ConstantPoolEntry::MethodHandle(reference_kind, reference_index) =
+        get_constant(constants, constant_index)?
+    else {
+        return Err(VmError::ValidationException);
+    };
+
+    let kind = MethodHandleKind::from_reference_kind(reference_kind)?;
+    let (class_index, name_and_type_index, is_interface) =
+        match MethodHandle::resolve(&class.version, constants, reference_kind, reference_index)? {
+            MethodHandle::FieldRef(class_index, name_and_type_index)
+            | MethodHandle::MethodRef(class_index, name_and_type_index) => {
+                (class_index, name_and_type_index, false)
+            }
+            MethodHandle::InterfaceMethodRef(class_index, name_and_type_index) => {
+                (class_index, name_and_type_index, true)
+            }
+        };
+    let (member_name, descriptor) = get_name_and_type(constants, name_and_type_index)?;
+    Ok(MethodHandleReference {
+        kind,
+        class_name: get_class_name(constants, class_index)?,
+        member_name,
+        descriptor,
+        is_interface,
+    })
+}
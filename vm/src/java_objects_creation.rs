@@ -1,10 +1,16 @@
-use rjvm_reader::{field_type::BaseType, line_number::LineNumber};
+use rjvm_reader::{
+    field_type::{BaseType, FieldType},
+    line_number::LineNumber,
+    method_descriptor::MethodDescriptor,
+};
 
 use crate::{
     abstract_object::{string_from_char_array, AbstractObject},
     array::Array,
     array_entry_type::ArrayEntryType,
     call_stack::CallStack,
+    class_mirror::MirroredType,
+    direct_method_handle::DirectMethodHandle,
     exceptions::MethodCallFailed,
     object::Object,
     stack_trace_element::StackTraceElement,
@@ -24,7 +30,11 @@ pub fn new_java_lang_string_object<'a>(
         .map(|c| Value::Int(c as i32))
         .collect();
 
-    let java_array = vm.new_array(ArrayEntryType::Base(BaseType::Char), char_array.len());
+    let java_array = vm.new_array(
+        call_stack,
+        ArrayEntryType::Base(BaseType::Char),
+        char_array.len(),
+    )?;
     char_array
         .into_iter()
         .enumerate()
@@ -61,16 +71,15 @@ pub fn extract_str_from_java_lang_string<'a>(
     Err(VmError::ValidationException)
 }
 
-pub fn new_java_lang_class_object<'a>(
+/// Creates a new instance of `java.lang.Class`. Rather than calling this directly, use
+/// [Vm::get_class_mirror], which returns the unique instance of each type.
+/// The type that the instance represents is not stored in the java object, but rather
+/// associated with it by the vm, which implements the native methods of `Class`.
+pub(crate) fn new_java_lang_class_object<'a>(
     vm: &mut Vm<'a>,
     call_stack: &mut CallStack<'a>,
-    class_name: &str,
 ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
-    let class_object = vm.new_object(call_stack, "java/lang/Class")?;
-    // TODO: build a proper instance of Class object
-    let string_object = new_java_lang_string_object(vm, call_stack, class_name)?;
-    class_object.set_field(5, Value::Object(string_object));
-    Ok(class_object)
+    vm.new_object(call_stack, "java/lang/Class")
 }
 
 pub fn new_java_lang_stack_trace_element_object<'a>(
@@ -108,3 +117,69 @@ pub fn new_java_lang_stack_trace_element_object<'a>(
 
     Ok(stack_trace_element_java_object)
 }
+
+/// Creates a new instance of `java.lang.invoke.MethodType`, with the given descriptor
+pub fn new_java_lang_invoke_method_type_object<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    method_type: &MethodDescriptor,
+) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+    let return_type = match &method_type.return_type {
+        Some(return_type) => get_java_lang_class_object_of_type(vm, call_stack, return_type)?,
+        None => vm.get_class_mirror(call_stack, MirroredType::Void)?,
+    };
+    let class_class = vm.get_or_resolve_class(call_stack, "java/lang/Class")?;
+    let parameter_types = vm.new_array(
+        call_stack,
+        ArrayEntryType::Object(class_class.id),
+        method_type.parameters.len(),
+    )?;
+    for (index, parameter_type) in method_type.parameters.iter().enumerate() {
+        let parameter_type = get_java_lang_class_object_of_type(vm, call_stack, parameter_type)?;
+        parameter_types.set_element(index, Value::Object(parameter_type))?;
+    }
+
+    // Our JRE's MethodType stores the types in the fields:
+    //    private final Class<?> rtype;
+    //    private final Class<?>[] ptypes;
+    let method_type_object = vm.new_object(call_stack, "java/lang/invoke/MethodType")?;
+    let class = vm.get_class_by_id(method_type_object.class_id())?;
+    if let Some((index, _)) = class.find_field("rtype") {
+        method_type_object.set_field(index, Value::Object(return_type));
+    }
+    if let Some((index, _)) = class.find_field("ptypes") {
+        method_type_object.set_field(index, Value::Object(parameter_types));
+    }
+    Ok(method_type_object)
+}
+
+/// Creates a new instance of `java.lang.invoke.MethodHandle`. Since the vm implements
+/// the invocation of the handle natively, its target is not stored in the java object.
+pub(crate) fn new_java_lang_invoke_method_handle_object<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    method_handle: DirectMethodHandle<'a>,
+) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+    let method_type =
+        new_java_lang_invoke_method_type_object(vm, call_stack, &method_handle.method_type)?;
+
+    // Our JRE's MethodHandle stores its type in the field:
+    //    private final MethodType type;
+    let method_handle_object = vm.new_object(call_stack, "java/lang/invoke/MethodHandle")?;
+    let class = vm.get_class_by_id(method_handle_object.class_id())?;
+    if let Some((index, _)) = class.find_field("type") {
+        method_handle_object.set_field(index, Value::Object(method_type));
+    }
+    vm.associate_method_handle(method_handle_object.clone(), method_handle);
+    Ok(method_handle_object)
+}
+
+/// Returns the `java.lang.Class` instance for the given type
+pub fn get_java_lang_class_object_of_type<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    field_type: &FieldType,
+) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+    let mirrored_type = MirroredType::of_field_type(vm, call_stack, field_type)?;
+    vm.get_class_mirror(call_stack, mirrored_type)
+}
@@ -0,0 +1,578 @@
+use rjvm_reader::{
+    field_type::{BaseType, FieldType},
+    method_descriptor::MethodDescriptor,
+    ClassAccessFlags, ClassFile, ClassFileField, ClassFileMethod, ClassFileMethodCode,
+    ConstantPool, ConstantPoolEntry, FieldFlags, MethodFlags,
+};
+
+use crate::{
+    call_stack::CallStack,
+    class::ClassRef,
+    exceptions::MethodCallFailed,
+    handle::MethodHandleKind,
+    invoke_dynamic::{
+        get_class_name, get_integer, get_method_handle_reference, get_method_type,
+        parse_method_descriptor, MethodHandleReference,
+    },
+    vm::Vm,
+    vm_error::VmError,
+};
+
+// Flags of `LambdaMetafactory.altMetafactory`
+const FLAG_SERIALIZABLE: i32 = 1;
+const FLAG_MARKERS: i32 = 2;
+const FLAG_BRIDGES: i32 = 4;
+
+/// Implements the bootstrap methods `LambdaMetafactory.metafactory` and
+/// `LambdaMetafactory.altMetafactory`. Like HotSpot, we generate a class that implements
+/// the functional interface, whose fields are the captured arguments and whose method
+/// invokes the implementation method, adapting the arguments and the return value.
+pub(crate) fn spin_lambda_class<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    caller: ClassRef<'a>,
+    interface_method_name: &str,
+    invoked_type: &str,
+    arguments: &[u16],
+    alternate: bool,
+) -> Result<ClassRef<'a>, MethodCallFailed<'a>> {
+    let lambda = Lambda::new(
+        caller,
+        interface_method_name,
+        invoked_type,
+        arguments,
+        alternate,
+    )?;
+    let class_name = format!(
+        "{}$$Lambda${}",
+        caller.name,
+        vm.next_generated_class_number()
+    );
+    let class_file = lambda.generate_class(class_name)?;
+    vm.define_class(call_stack, class_file)
+}
+
+/// The arguments of the metafactory
+struct Lambda<'b> {
+    interface_name: String,
+    interface_method_name: &'b str,
+    /// The descriptors of the methods to implement: the functional method, and then
+    /// the bridges
+    interface_method_types: Vec<&'b str>,
+    /// The types of the arguments captured by the call site
+    captured_types: Vec<FieldType>,
+    /// The signature of the functional method, after the generic types are substituted
+    instantiated_type: MethodDescriptor,
+    implementation: MethodHandleReference<'b>,
+    implementation_type: MethodDescriptor,
+    extra_interfaces: Vec<&'b str>,
+}
+
+impl<'b> Lambda<'b> {
+    fn new(
+        caller: ClassRef<'b>,
+        interface_method_name: &'b str,
+        invoked_type: &'b str,
+        arguments: &[u16],
+        alternate: bool,
+    ) -> Result<Self, VmError> {
+        let constants = &caller.constants;
+        let invoked_type = parse_method_descriptor(invoked_type)?;
+        let Some(FieldType::Object(interface_name)) = &invoked_type.return_type else {
+            return Err(VmError::ValidationException);
+        };
+
+        let [interface_method_type, implementation, instantiated_type, other_arguments @ ..] =
+            arguments
+        else {
+            return Err(VmError::ValidationException);
+        };
+        let mut interface_method_types = vec![get_method_type(constants, *interface_method_type)?];
+        let implementation = get_method_handle_reference(caller, *implementation)?;
+        let implementation_type = parse_method_descriptor(implementation.descriptor)?;
+        let instantiated_type =
+            parse_method_descriptor(get_method_type(constants, *instantiated_type)?)?;
+
+        let mut extra_interfaces = Vec::new();
+        if alternate {
+            let mut other_arguments = other_arguments.iter();
+            let mut next_argument = || {
+                other_arguments
+                    .next()
+                    .copied()
+                    .ok_or(VmError::ValidationException)
+            };
+            let flags = get_integer(constants, next_argument()?)?;
+            if flags & FLAG_MARKERS != 0 {
+                let markers_count = get_integer(constants, next_argument()?)?;
+                for _ in 0..markers_count {
+                    extra_interfaces.push(get_class_name(constants, next_argument()?)?);
+                }
+            }
+            if flags & FLAG_SERIALIZABLE != 0 {
+                extra_interfaces.push("java/io/Serializable");
+            }
+            if flags & FLAG_BRIDGES != 0 {
+                let bridges_count = get_integer(constants, next_argument()?)?;
+                for _ in 0..bridges_count {
+                    interface_method_types.push(get_method_type(constants, next_argument()?)?);
+                }
+            }
+        }
+
+        Ok(Self {
+            interface_name: interface_name.clone(),
+            interface_method_name,
+            interface_method_types,
+            captured_types: invoked_type.parameters,
+            instantiated_type,
+            implementation,
+            implementation_type,
+            extra_interfaces,
+        })
+    }
+
+    fn generate_class(&self, class_name: String) -> Result<ClassFile, VmError> {
+        let mut constants = ConstantPoolBuilder::default();
+        let methods = self
+            .interface_method_types
+            .iter()
+            .enumerate()
+            .map(|(index, descriptor)| {
+                let flags = if index == 0 {
+                    MethodFlags::PUBLIC
+                } else {
+                    MethodFlags::PUBLIC | MethodFlags::BRIDGE | MethodFlags::SYNTHETIC
+                };
+                self.generate_method(&class_name, descriptor, flags, &mut constants)
+            })
+            .collect::<Result<Vec<ClassFileMethod>, VmError>>()?;
+        let fields = self
+            .captured_types
+            .iter()
+            .enumerate()
+            .map(|(index, captured_type)| ClassFileField {
+                flags: FieldFlags::PRIVATE | FieldFlags::FINAL,
+                name: captured_field_name(index),
+                type_descriptor: captured_type.clone(),
+                constant_value: None,
+                deprecated: false,
+                signature: None,
+                visible_annotations: Vec::new(),
+                invisible_annotations: Vec::new(),
+                visible_type_annotations: Vec::new(),
+                invisible_type_annotations: Vec::new(),
+            })
+            .collect();
+
+        let mut interfaces = vec![self.interface_name.clone()];
+        interfaces.extend(self.extra_interfaces.iter().map(|name| name.to_string()));
+
+        Ok(ClassFile {
+            constants: constants.pool,
+            flags: ClassAccessFlags::FINAL | ClassAccessFlags::SUPER | ClassAccessFlags::SYNTHETIC,
+            name: class_name,
+            superclass: Some("java/lang/Object".to_string()),
+            interfaces,
+            fields,
+            methods,
+            ..Default::default()
+        })
+    }
+
+    /// Generates a method with the given descriptor that loads the captured arguments from
+    /// the fields, followed by its own arguments, and invokes the implementation method.
+    fn generate_method(
+        &self,
+        class_name: &str,
+        descriptor: &str,
+        flags: MethodFlags,
+        constants: &mut ConstantPoolBuilder,
+    ) -> Result<ClassFileMethod, VmError> {
+        let method_type = parse_method_descriptor(descriptor)?;
+        let implementation_parameters = self.implementation_parameters();
+        let Some(expected_parameters) = implementation_parameters.get(self.captured_types.len()..)
+        else {
+            return Err(VmError::ValidationException);
+        };
+        if expected_parameters.len() != method_type.parameters.len()
+            || self.instantiated_type.parameters.len() != method_type.parameters.len()
+        {
+            return Err(VmError::ValidationException);
+        }
+
+        let mut code = BytecodeWriter::new(constants);
+        let implementation_class = self.implementation.class_name;
+        if self.implementation.kind == MethodHandleKind::NewInvokeSpecial {
+            code.class_instruction(NEW, implementation_class);
+            code.instruction(DUP);
+        }
+
+        for (index, captured_type) in self.captured_types.iter().enumerate() {
+            code.instruction(ALOAD_0);
+            let field = code.constants.field_reference(
+                class_name,
+                &captured_field_name(index),
+                &captured_type.to_descriptor(),
+            );
+            code.instruction_with_index(GETFIELD, field);
+        }
+
+        let mut local_index = 1;
+        for ((parameter, instantiated), expected) in method_type
+            .parameters
+            .iter()
+            .zip(self.instantiated_type.parameters.iter())
+            .zip(expected_parameters)
+        {
+            code.load(parameter, local_index);
+            local_index += slots_of(parameter);
+            code.convert(parameter, instantiated)?;
+            code.convert(instantiated, expected)?;
+        }
+
+        self.invoke_implementation(&mut code, &implementation_parameters);
+
+        let implementation_return_type = match self.implementation.kind {
+            MethodHandleKind::NewInvokeSpecial => {
+                Some(FieldType::Object(implementation_class.to_string()))
+            }
+            _ => self.implementation_type.return_type.clone(),
+        };
+        match (&implementation_return_type, &method_type.return_type) {
+            (None, None) => code.instruction(RETURN),
+            (Some(returned_type), None) => {
+                code.instruction(if slots_of(returned_type) == 2 {
+                    POP2
+                } else {
+                    POP
+                });
+                code.instruction(RETURN)
+            }
+            (Some(returned_type), Some(return_type)) => {
+                match &self.instantiated_type.return_type {
+                    Some(instantiated) => {
+                        code.convert(returned_type, instantiated)?;
+                        code.convert(instantiated, return_type)?;
+                    }
+                    None => code.convert(returned_type, return_type)?,
+                }
+                code.instruction(return_instruction(return_type))
+            }
+            (None, Some(_)) => return Err(VmError::ValidationException),
+        }
+
+        let max_locals = local_index;
+        let max_stack = 2 + 2 * (self.captured_types.len() + method_type.parameters.len()) as u16;
+        Ok(ClassFileMethod {
+            flags,
+            name: self.interface_method_name.to_string(),
+            type_descriptor: descriptor.to_string(),
+            parsed_type_descriptor: method_type,
+            attributes: Vec::new(),
+            code: Some(ClassFileMethodCode {
+                max_stack,
+                max_locals,
+                code: code.code,
+                ..Default::default()
+            }),
+            deprecated: false,
+            thrown_exceptions: Vec::new(),
+            signature: None,
+            visible_annotations: Vec::new(),
+            invisible_annotations: Vec::new(),
+            visible_parameter_annotations: Vec::new(),
+            invisible_parameter_annotations: Vec::new(),
+            visible_type_annotations: Vec::new(),
+            invisible_type_annotations: Vec::new(),
+            annotation_default: None,
+        })
+    }
+
+    /// The types of the values expected by the implementation method, including the receiver
+    fn implementation_parameters(&self) -> Vec<FieldType> {
+        let mut parameters = Vec::new();
+        if matches!(
+            self.implementation.kind,
+            MethodHandleKind::InvokeVirtual
+                | MethodHandleKind::InvokeInterface
+                | MethodHandleKind::InvokeSpecial
+        ) {
+            parameters.push(FieldType::Object(
+                self.implementation.class_name.to_string(),
+            ));
+        }
+        parameters.extend(self.implementation_type.parameters.iter().cloned());
+        parameters
+    }
+
+    fn invoke_implementation(&self, code: &mut BytecodeWriter, parameters: &[FieldType]) {
+        let implementation = &self.implementation;
+        let method = code.constants.method_reference(
+            implementation.class_name,
+            implementation.member_name,
+            implementation.descriptor,
+            implementation.is_interface,
+        );
+        match implementation.kind {
+            MethodHandleKind::InvokeStatic => code.instruction_with_index(INVOKESTATIC, method),
+            MethodHandleKind::InvokeSpecial | MethodHandleKind::NewInvokeSpecial => {
+                code.instruction_with_index(INVOKESPECIAL, method)
+            }
+            MethodHandleKind::InvokeInterface => {
+                code.instruction_with_index(INVOKEINTERFACE, method);
+                let count: u16 = parameters.iter().map(slots_of).sum();
+                code.code.extend_from_slice(&[count as u8, 0]);
+            }
+            _ => code.instruction_with_index(INVOKEVIRTUAL, method),
+        }
+    }
+}
+
+fn captured_field_name(index: usize) -> String {
+    format!("arg${}", index + 1)
+}
+
+fn slots_of(field_type: &FieldType) -> u16 {
+    match field_type {
+        FieldType::Base(BaseType::Long) | FieldType::Base(BaseType::Double) => 2,
+        _ => 1,
+    }
+}
+
+fn is_int_like(base_type: &BaseType) -> bool {
+    matches!(
+        base_type,
+        BaseType::Boolean | BaseType::Byte | BaseType::Char | BaseType::Short | BaseType::Int
+    )
+}
+
+fn return_instruction(field_type: &FieldType) -> u8 {
+    match field_type {
+        FieldType::Base(BaseType::Long) => LRETURN,
+        FieldType::Base(BaseType::Float) => FRETURN,
+        FieldType::Base(BaseType::Double) => DRETURN,
+        FieldType::Base(_) => IRETURN,
+        _ => ARETURN,
+    }
+}
+
+/// Returns the wrapper class of a primitive type, and the name of its unboxing method
+pub(crate) fn wrapper_of(base_type: &BaseType) -> (&'static str, &'static str) {
+    match base_type {
+        BaseType::Boolean => ("java/lang/Boolean", "booleanValue"),
+        BaseType::Byte => ("java/lang/Byte", "byteValue"),
+        BaseType::Char => ("java/lang/Character", "charValue"),
+        BaseType::Short => ("java/lang/Short", "shortValue"),
+        BaseType::Int => ("java/lang/Integer", "intValue"),
+        BaseType::Long => ("java/lang/Long", "longValue"),
+        BaseType::Float => ("java/lang/Float", "floatValue"),
+        BaseType::Double => ("java/lang/Double", "doubleValue"),
+    }
+}
+
+pub(crate) fn unwrapped_type_of(class_name: &str) -> Option<BaseType> {
+    match class_name {
+        "java/lang/Boolean" => Some(BaseType::Boolean),
+        "java/lang/Byte" => Some(BaseType::Byte),
+        "java/lang/Character" => Some(BaseType::Char),
+        "java/lang/Short" => Some(BaseType::Short),
+        "java/lang/Integer" => Some(BaseType::Int),
+        "java/lang/Long" => Some(BaseType::Long),
+        "java/lang/Float" => Some(BaseType::Float),
+        "java/lang/Double" => Some(BaseType::Double),
+        _ => None,
+    }
+}
+
+// Opcodes of the instructions that we generate
+const ALOAD_0: u8 = 0x2a;
+const ILOAD: u8 = 0x15;
+const LLOAD: u8 = 0x16;
+const FLOAD: u8 = 0x17;
+const DLOAD: u8 = 0x18;
+const ALOAD: u8 = 0x19;
+const POP: u8 = 0x57;
+const POP2: u8 = 0x58;
+const DUP: u8 = 0x59;
+const I2L: u8 = 0x85;
+const I2F: u8 = 0x86;
+const I2D: u8 = 0x87;
+const L2F: u8 = 0x89;
+const L2D: u8 = 0x8a;
+const F2D: u8 = 0x8d;
+const IRETURN: u8 = 0xac;
+const LRETURN: u8 = 0xad;
+const FRETURN: u8 = 0xae;
+const DRETURN: u8 = 0xaf;
+const ARETURN: u8 = 0xb0;
+const RETURN: u8 = 0xb1;
+const GETFIELD: u8 = 0xb4;
+const INVOKEVIRTUAL: u8 = 0xb6;
+const INVOKESPECIAL: u8 = 0xb7;
+const INVOKESTATIC: u8 = 0xb8;
+const INVOKEINTERFACE: u8 = 0xb9;
+const NEW: u8 = 0xbb;
+const CHECKCAST: u8 = 0xc0;
+const WIDE: u8 = 0xc4;
+
+/// Builds the constant pool of a generated class. Since the classes are small,
+/// we do not bother deduplicating the entries.
+#[derive(Default)]
+struct ConstantPoolBuilder {
+    pool: ConstantPool,
+    count: u16,
+}
+
+impl ConstantPoolBuilder {
+    fn add(&mut self, entry: ConstantPoolEntry) -> u16 {
+        self.pool.add(entry);
+        self.count += 1;
+        self.count
+    }
+
+    fn utf8(&mut self, string: &str) -> u16 {
+        self.add(ConstantPoolEntry::Utf8(string.to_string()))
+    }
+
+    fn class_reference(&mut self, class_name: &str) -> u16 {
+        let name = self.utf8(class_name);
+        self.add(ConstantPoolEntry::ClassReference(name))
+    }
+
+    fn name_and_type(&mut self, name: &str, descriptor: &str) -> u16 {
+        let name = self.utf8(name);
+        let descriptor = self.utf8(descriptor);
+        self.add(ConstantPoolEntry::NameAndTypeDescriptor(name, descriptor))
+    }
+
+    fn field_reference(&mut self, class_name: &str, name: &str, descriptor: &str) -> u16 {
+        let class = self.class_reference(class_name);
+        let name_and_type = self.name_and_type(name, descriptor);
+        self.add(ConstantPoolEntry::FieldReference(class, name_and_type))
+    }
+
+    fn method_reference(
+        &mut self,
+        class_name: &str,
+        name: &str,
+        descriptor: &str,
+        is_interface: bool,
+    ) -> u16 {
+        let class = self.class_reference(class_name);
+        let name_and_type = self.name_and_type(name, descriptor);
+        if is_interface {
+            self.add(ConstantPoolEntry::InterfaceMethodReference(
+                class,
+                name_and_type,
+            ))
+        } else {
+            self.add(ConstantPoolEntry::MethodReference(class, name_and_type))
+        }
+    }
+}
+
+/// Writes the bytecode of a generated method
+struct BytecodeWriter<'c> {
+    constants: &'c mut ConstantPoolBuilder,
+    code: Vec<u8>,
+}
+
+impl<'c> BytecodeWriter<'c> {
+    fn new(constants: &'c mut ConstantPoolBuilder) -> Self {
+        Self {
+            constants,
+            code: Vec::new(),
+        }
+    }
+
+    fn instruction(&mut self, opcode: u8) {
+        self.code.push(opcode);
+    }
+
+    fn instruction_with_index(&mut self, opcode: u8, constant_index: u16) {
+        self.code.push(opcode);
+        self.code.extend_from_slice(&constant_index.to_be_bytes());
+    }
+
+    fn class_instruction(&mut self, opcode: u8, class_name: &str) {
+        let class = self.constants.class_reference(class_name);
+        self.instruction_with_index(opcode, class);
+    }
+
+    fn load(&mut self, field_type: &FieldType, local_index: u16) {
+        let opcode = match field_type {
+            FieldType::Base(BaseType::Long) => LLOAD,
+            FieldType::Base(BaseType::Float) => FLOAD,
+            FieldType::Base(BaseType::Double) => DLOAD,
+            FieldType::Base(_) => ILOAD,
+            _ => ALOAD,
+        };
+        match u8::try_from(local_index) {
+            Ok(local_index) => self.code.extend_from_slice(&[opcode, local_index]),
+            Err(_) => {
+                self.code.extend_from_slice(&[WIDE, opcode]);
+                self.code.extend_from_slice(&local_index.to_be_bytes());
+            }
+        }
+    }
+
+    /// Converts the value on top of the stack, applying the same adaptations of the
+    /// `LambdaMetafactory`: widening of primitives, boxing, unboxing and casts.
+    fn convert(&mut self, from: &FieldType, to: &FieldType) -> Result<(), VmError> {
+        match (from, to) {
+            _ if from == to => {}
+            (FieldType::Base(from), FieldType::Base(to)) => self.widen(from, to)?,
+            (FieldType::Base(from), _) => {
+                let (wrapper, _) = wrapper_of(from);
+                let method = self.constants.method_reference(
+                    wrapper,
+                    "valueOf",
+                    &format!("({})L{wrapper};", from.to_descriptor()),
+                    false,
+                );
+                self.instruction_with_index(INVOKESTATIC, method);
+            }
+            (FieldType::Object(from), FieldType::Base(to)) => {
+                let unwrapped_type = match unwrapped_type_of(from) {
+                    Some(unwrapped_type) => unwrapped_type,
+                    None => {
+                        self.class_instruction(CHECKCAST, wrapper_of(to).0);
+                        to.clone()
+                    }
+                };
+                let (wrapper, unboxing_method) = wrapper_of(&unwrapped_type);
+                let method = self.constants.method_reference(
+                    wrapper,
+                    unboxing_method,
+                    &format!("(){}", unwrapped_type.to_descriptor()),
+                    false,
+                );
+                self.instruction_with_index(INVOKEVIRTUAL, method);
+                self.widen(&unwrapped_type, to)?;
+            }
+            (FieldType::Array(_), FieldType::Base(_)) => return Err(VmError::ValidationException),
+            (_, FieldType::Object(class_name)) if class_name == "java/lang/Object" => {}
+            (_, FieldType::Object(class_name)) => self.class_instruction(CHECKCAST, class_name),
+            (_, FieldType::Array(_)) => self.class_instruction(CHECKCAST, &to.to_descriptor()),
+        }
+        Ok(())
+    }
+
+    fn widen(&mut self, from: &BaseType, to: &BaseType) -> Result<(), VmError> {
+        if from == to || (is_int_like(from) && *to == BaseType::Int) {
+            return Ok(());
+        }
+        let opcode = match (from, to) {
+            (from, BaseType::Long) if is_int_like(from) => I2L,
+            (from, BaseType::Float) if is_int_like(from) => I2F,
+            (from, BaseType::Double) if is_int_like(from) => I2D,
+            (BaseType::Long, BaseType::Float) => L2F,
+            (BaseType::Long, BaseType::Double) => L2D,
+            (BaseType::Float, BaseType::Double) => F2D,
+            _ => return Err(VmError::ValidationException),
+        };
+        self.instruction(opcode);
+        Ok(())
+    }
+}
@@ -9,16 +9,22 @@ mod call_frame;
 pub mod call_stack;
 pub mod class;
 pub mod class_and_method;
+pub mod class_mirror;
+mod direct_method_handle;
 pub mod exceptions;
 mod file_system_class_path_entry;
 mod gc;
 pub mod handle;
+mod invoke_dynamic;
 mod jar_file_class_path_entry;
 pub mod java_objects_creation;
+mod lambda_metafactory;
 mod native_methods_impl;
 pub mod native_methods_registry;
 pub mod object;
+mod reflection;
 pub mod stack_trace_element;
+mod string_concat;
 mod time;
 pub mod value;
 mod value_stack;
@@ -1,23 +1,30 @@
 use log::{debug, info};
 
-use rjvm_reader::type_conversion::ToUsizeSafe;
+use rjvm_reader::{field_type::FieldType, type_conversion::ToUsizeSafe};
 
 use crate::{
     abstract_object::{AbstractObject, ObjectKind},
     array::Array,
+    array_entry_type::ArrayEntryType,
     call_frame::MethodCallResult,
     call_stack::CallStack,
+    class_mirror::MirroredType,
     exceptions::MethodCallFailed,
     java_objects_creation::{
-        extract_str_from_java_lang_string, new_java_lang_class_object,
-        new_java_lang_stack_trace_element_object,
+        extract_str_from_java_lang_string, new_java_lang_stack_trace_element_object,
+        new_java_lang_string_object,
     },
     native_methods_registry::NativeMethodsRegistry,
     object::Object,
+    reflection::{
+        class_for_name, class_modifiers, field_offset, get_caller_class, get_declared_constructors,
+        get_declared_fields, get_declared_methods, get_field_at_offset, invoke_method,
+        new_instance, put_field_at_offset, static_field_base,
+    },
     time::{get_current_time_millis, get_nano_time},
     value::{
         expect_abstract_object_at, expect_array_at, expect_concrete_object_at, expect_double_at,
-        expect_float_at, expect_int_at, Value,
+        expect_float_at, expect_int_at, expect_long_at, Value,
     },
     vm::Vm,
     vm_error::VmError,
@@ -27,13 +34,68 @@ use crate::{
 pub(crate) fn register_natives(registry: &mut NativeMethodsRegistry) {
     registry.register_temp_print(|vm, _, _, args| temp_print(vm, args));
     register_noops(registry);
+    register_object_methods(registry);
+    register_string_methods(registry);
     register_time_methods(registry);
+    register_system_methods(registry);
+    register_io_methods(registry);
     register_gc_methods(registry);
     register_native_repr_methods(registry);
     register_reflection_methods(registry);
+    register_unsafe_methods(registry);
     register_throwable_methods(registry);
 }
 
+/// Methods of java.lang.Object
+fn register_object_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register(
+        "java/lang/Object",
+        "getClass",
+        "()Ljava/lang/Class;",
+        |vm, stack, receiver, _| get_class(vm, stack, receiver),
+    );
+    registry.register(
+        "java/lang/Object",
+        "hashCode",
+        "()I",
+        |_, _, receiver, _| {
+            let receiver = expect_some_receiver(receiver)?;
+            Ok(Some(Value::Int(receiver.identity_hash_code())))
+        },
+    );
+    registry.register(
+        "java/lang/Object",
+        "clone",
+        "()Ljava/lang/Object;",
+        |vm, stack, receiver, _| clone(vm, stack, receiver),
+    );
+    registry.register(
+        "java/lang/Object",
+        "wait",
+        "(J)V",
+        |vm, stack, receiver, args| wait(vm, stack, receiver, &args),
+    );
+    registry.register("java/lang/Object", "notify", "()V", |vm, _, receiver, _| {
+        expect_owned_monitor(vm, receiver)
+    });
+    registry.register(
+        "java/lang/Object",
+        "notifyAll",
+        "()V",
+        |vm, _, receiver, _| expect_owned_monitor(vm, receiver),
+    );
+}
+
+/// Methods of java.lang.String
+fn register_string_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register(
+        "java/lang/String",
+        "intern",
+        "()Ljava/lang/String;",
+        |vm, _, receiver, _| intern(vm, receiver),
+    );
+}
+
 /// These various methods are noop, i.e. they do not do anything
 fn register_noops(registry: &mut NativeMethodsRegistry) {
     registry.register(
@@ -57,6 +119,63 @@ fn register_noops(registry: &mut NativeMethodsRegistry) {
         "()V",
         |_, _, _, _| Ok(None),
     );
+    registry.register("sun/misc/Unsafe", "registerNatives", "()V", |_, _, _, _| {
+        Ok(None)
+    });
+    for class_name in [
+        "java/io/FileDescriptor",
+        "java/io/FileInputStream",
+        "java/io/FileOutputStream",
+    ] {
+        registry.register(class_name, "initIDs", "()V", |_, _, _, _| Ok(None));
+    }
+}
+
+/// Methods of java.lang.System used by `System.initializeSystemClass`
+fn register_system_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register(
+        "java/lang/System",
+        "setIn0",
+        "(Ljava/io/InputStream;)V",
+        |vm, _, _, args| set_system_stream(vm, "in", &args),
+    );
+    registry.register(
+        "java/lang/System",
+        "setOut0",
+        "(Ljava/io/PrintStream;)V",
+        |vm, _, _, args| set_system_stream(vm, "out", &args),
+    );
+    registry.register(
+        "java/lang/System",
+        "setErr0",
+        "(Ljava/io/PrintStream;)V",
+        |vm, _, _, args| set_system_stream(vm, "err", &args),
+    );
+    registry.register(
+        "java/lang/System",
+        "initProperties",
+        "(Ljava/util/Properties;)Ljava/util/Properties;",
+        |vm, stack, _, args| init_properties(vm, stack, &args),
+    );
+}
+
+/// Methods of the java.io streams on the file descriptors
+fn register_io_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register(
+        "java/io/FileOutputStream",
+        "writeBytes",
+        "([BIIZ)V",
+        |vm, stack, receiver, args| write_bytes(vm, stack, receiver, &args),
+    );
+    registry.register(
+        "java/io/FileOutputStream",
+        "write",
+        "(IZ)V",
+        |vm, stack, receiver, args| {
+            let byte = expect_int_at(&args, 0)?;
+            write_to_file_output_stream(vm, stack, receiver, &[byte as u8])
+        },
+    );
 }
 
 /// Methods to access the system clock
@@ -92,7 +211,7 @@ fn register_native_repr_methods(registry: &mut NativeMethodsRegistry) {
         "java/lang/System",
         "arraycopy",
         "(Ljava/lang/Object;ILjava/lang/Object;II)V",
-        |_, _, _, args| native_array_copy(args),
+        |vm, _, _, args| native_array_copy(vm, args),
     );
     registry.register(
         "java/lang/Float",
@@ -128,6 +247,183 @@ fn register_reflection_methods(registry: &mut NativeMethodsRegistry) {
         "(Ljava/lang/String;)Ljava/lang/Class;",
         |vm, stack, _, args| get_primitive_class(vm, stack, &args),
     );
+    registry.register(
+        "java/lang/Class",
+        "getName0",
+        "()Ljava/lang/String;",
+        |vm, stack, receiver, _| get_class_name(vm, stack, receiver),
+    );
+    registry.register(
+        "java/lang/Class",
+        "getSuperclass",
+        "()Ljava/lang/Class;",
+        |vm, stack, receiver, _| get_superclass(vm, stack, receiver),
+    );
+    registry.register(
+        "java/lang/Class",
+        "isInterface",
+        "()Z",
+        |vm, _, receiver, _| {
+            let mirrored_type = expect_mirrored_type(vm, receiver)?;
+            let is_interface =
+                matches!(mirrored_type, MirroredType::Class(class) if class.is_interface());
+            Ok(Some(Value::Int(is_interface.into())))
+        },
+    );
+    registry.register("java/lang/Class", "isArray", "()Z", |vm, _, receiver, _| {
+        let mirrored_type = expect_mirrored_type(vm, receiver)?;
+        let is_array = matches!(mirrored_type, MirroredType::Array(_));
+        Ok(Some(Value::Int(is_array.into())))
+    });
+    registry.register(
+        "java/lang/Class",
+        "isPrimitive",
+        "()Z",
+        |vm, _, receiver, _| {
+            let mirrored_type = expect_mirrored_type(vm, receiver)?;
+            let is_primitive = matches!(
+                mirrored_type,
+                MirroredType::Primitive(_) | MirroredType::Void
+            );
+            Ok(Some(Value::Int(is_primitive.into())))
+        },
+    );
+    registry.register(
+        "java/lang/Class",
+        "getComponentType",
+        "()Ljava/lang/Class;",
+        |vm, stack, receiver, _| get_component_type(vm, stack, receiver),
+    );
+    registry.register(
+        "java/lang/Class",
+        "isInstance",
+        "(Ljava/lang/Object;)Z",
+        |vm, _, receiver, args| is_instance(vm, receiver, &args),
+    );
+    registry.register(
+        "java/lang/Class",
+        "isAssignableFrom",
+        "(Ljava/lang/Class;)Z",
+        |vm, _, receiver, args| is_assignable_from(vm, receiver, &args),
+    );
+    registry.register(
+        "java/lang/Class",
+        "getModifiers",
+        "()I",
+        |vm, _, receiver, _| {
+            let mirrored_type = expect_mirrored_type(vm, receiver)?;
+            Ok(Some(Value::Int(class_modifiers(&mirrored_type))))
+        },
+    );
+    registry.register(
+        "java/lang/Class",
+        "forName0",
+        "(Ljava/lang/String;ZLjava/lang/ClassLoader;Ljava/lang/Class;)Ljava/lang/Class;",
+        |vm, stack, _, args| class_for_name(vm, stack, &args),
+    );
+    registry.register(
+        "java/lang/Class",
+        "getDeclaredFields0",
+        "(Z)[Ljava/lang/reflect/Field;",
+        |vm, stack, receiver, args| {
+            let mirrored_type = expect_mirrored_type(vm, receiver)?;
+            get_declared_fields(vm, stack, mirrored_type, &args)
+        },
+    );
+    registry.register(
+        "java/lang/Class",
+        "getDeclaredMethods0",
+        "(Z)[Ljava/lang/reflect/Method;",
+        |vm, stack, receiver, args| {
+            let mirrored_type = expect_mirrored_type(vm, receiver)?;
+            get_declared_methods(vm, stack, mirrored_type, &args)
+        },
+    );
+    registry.register(
+        "java/lang/Class",
+        "getDeclaredConstructors0",
+        "(Z)[Ljava/lang/reflect/Constructor;",
+        |vm, stack, receiver, args| {
+            let mirrored_type = expect_mirrored_type(vm, receiver)?;
+            get_declared_constructors(vm, stack, mirrored_type, &args)
+        },
+    );
+    registry.register(
+        "sun/reflect/Reflection",
+        "getCallerClass",
+        "()Ljava/lang/Class;",
+        |vm, stack, _, _| get_caller_class(vm, stack),
+    );
+    registry.register(
+        "sun/reflect/NativeMethodAccessorImpl",
+        "invoke0",
+        "(Ljava/lang/reflect/Method;Ljava/lang/Object;[Ljava/lang/Object;)Ljava/lang/Object;",
+        |vm, stack, _, args| invoke_method(vm, stack, &args),
+    );
+    registry.register(
+        "sun/reflect/NativeConstructorAccessorImpl",
+        "newInstance0",
+        "(Ljava/lang/reflect/Constructor;[Ljava/lang/Object;)Ljava/lang/Object;",
+        |vm, stack, _, args| new_instance(vm, stack, &args),
+    );
+}
+
+/// Methods of sun.misc.Unsafe, used by the reflection classes to access fields
+fn register_unsafe_methods(registry: &mut NativeMethodsRegistry) {
+    for (type_name, descriptor) in [
+        ("Int", "I"),
+        ("Long", "J"),
+        ("Float", "F"),
+        ("Double", "D"),
+        ("Boolean", "Z"),
+        ("Byte", "B"),
+        ("Short", "S"),
+        ("Char", "C"),
+        ("Object", "Ljava/lang/Object;"),
+    ] {
+        registry.register(
+            "sun/misc/Unsafe",
+            &format!("get{type_name}"),
+            &format!("(Ljava/lang/Object;J){descriptor}"),
+            |vm, _, _, args| get_field_at_offset(vm, &args),
+        );
+        registry.register(
+            "sun/misc/Unsafe",
+            &format!("put{type_name}"),
+            &format!("(Ljava/lang/Object;J{descriptor})V"),
+            |vm, _, _, args| put_field_at_offset(vm, &args),
+        );
+    }
+    registry.register(
+        "sun/misc/Unsafe",
+        "objectFieldOffset",
+        "(Ljava/lang/reflect/Field;)J",
+        |vm, _, _, args| field_offset(vm, &args),
+    );
+    registry.register(
+        "sun/misc/Unsafe",
+        "staticFieldOffset",
+        "(Ljava/lang/reflect/Field;)J",
+        |vm, _, _, args| field_offset(vm, &args),
+    );
+    registry.register(
+        "sun/misc/Unsafe",
+        "staticFieldBase",
+        "(Ljava/lang/reflect/Field;)Ljava/lang/Object;",
+        |vm, _, _, args| static_field_base(vm, &args),
+    );
+    registry.register(
+        "sun/misc/Unsafe",
+        "ensureClassInitialized",
+        "(Ljava/lang/Class;)V",
+        |vm, stack, _, args| {
+            let class = expect_abstract_object_at(&args, 0)?;
+            if let MirroredType::Class(class) = expect_mirrored_type(vm, Some(class))? {
+                vm.initialize_class(stack, class)?;
+            }
+            Ok(None)
+        },
+    );
 }
 
 /// Methods of java.lang.Throwable
@@ -154,7 +450,7 @@ fn register_throwable_methods(registry: &mut NativeMethodsRegistry) {
 
 /// Debug method that does a "println", useful since we do not have real I/O
 fn temp_print<'a>(vm: &mut Vm<'a>, args: Vec<Value<'a>>) -> MethodCallResult<'a> {
-    let arg = args.get(0).ok_or(VmError::ValidationException)?;
+    let arg = args.first().ok_or(VmError::ValidationException)?;
 
     let formatted = match arg {
         Value::Object(object) if object.kind() == ObjectKind::Object => {
@@ -175,44 +471,362 @@ fn temp_print<'a>(vm: &mut Vm<'a>, args: Vec<Value<'a>>) -> MethodCallResult<'a>
     Ok(None)
 }
 
+fn clone<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+) -> MethodCallResult<'a> {
+    let receiver = expect_some_receiver(receiver)?;
+    match receiver.kind() {
+        ObjectKind::Array => Ok(Some(vm.clone_array(Value::Object(receiver))?)),
+        ObjectKind::Object => {
+            let class = vm.get_class_by_id(receiver.class_id())?;
+            let cloneable_class = vm.get_or_resolve_class(call_stack, "java/lang/Cloneable")?;
+            if !class.is_subclass_of(cloneable_class) {
+                let exception = vm.new_exception_with_message(
+                    call_stack,
+                    "java/lang/CloneNotSupportedException",
+                    &class.name.replace('/', "."),
+                )?;
+                return Err(MethodCallFailed::ExceptionThrown(exception));
+            }
+            Ok(Some(Value::Object(vm.clone_object(receiver)?)))
+        }
+    }
+}
+
+/// Since we are single-threaded, nobody can ever notify us. Rather than blocking the only
+/// thread until the timeout elapses, we return immediately, like a spurious wakeup.
+fn wait<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    expect_owned_monitor(vm, receiver)?;
+    let timeout = expect_long_at(args, 0)?;
+    if timeout < 0 {
+        let exception = vm.new_exception_with_message(
+            call_stack,
+            "java/lang/IllegalArgumentException",
+            "timeout value is negative",
+        )?;
+        return Err(MethodCallFailed::ExceptionThrown(exception));
+    }
+    Ok(None)
+}
+
+/// Waiting or notifying requires owning the monitor of the object
+fn expect_owned_monitor<'a>(
+    vm: &Vm<'a>,
+    receiver: Option<AbstractObject<'a>>,
+) -> MethodCallResult<'a> {
+    let receiver = expect_some_receiver(receiver)?;
+    if vm.owns_monitor(&receiver) {
+        Ok(None)
+    } else {
+        Err(MethodCallFailed::InternalError(
+            VmError::IllegalMonitorStateException,
+        ))
+    }
+}
+
+fn intern<'a>(vm: &mut Vm<'a>, receiver: Option<AbstractObject<'a>>) -> MethodCallResult<'a> {
+    let receiver = expect_some_receiver(receiver)?;
+    Ok(Some(Value::Object(vm.intern_string_object(receiver)?)))
+}
+
 fn identity_hash_code(args: Vec<Value<'_>>) -> MethodCallResult<'_> {
     let object = expect_abstract_object_at(&args, 0)?;
     Ok(Some(Value::Int(object.identity_hash_code())))
 }
 
-fn native_array_copy(args: Vec<Value>) -> MethodCallResult {
-    // TODO: handle NullPointerException with the correct error
+fn native_array_copy<'a>(vm: &Vm<'a>, args: Vec<Value<'a>>) -> MethodCallResult<'a> {
+    if args.first() == Some(&Value::Null) || args.get(2) == Some(&Value::Null) {
+        return Err(MethodCallFailed::InternalError(
+            VmError::NullPointerException(None),
+        ));
+    }
 
     let src = expect_array_at(&args, 0)?;
     let src_pos = expect_int_at(&args, 1)?;
     let dest = expect_array_at(&args, 2)?;
     let dest_pos = expect_int_at(&args, 3)?;
     let length = expect_int_at(&args, 4)?;
-    array_copy(&src, src_pos, &dest, dest_pos, length.into_usize_safe())?;
+    array_copy(vm, &src, src_pos, &dest, dest_pos, length)?;
     Ok(None)
 }
 
+/// Implements `System.arraycopy`, including its checks. The errors have the same messages
+/// that HotSpot uses for the exceptions.
 pub fn array_copy<'a>(
+    vm: &Vm<'a>,
+    src: &impl Array<'a>,
+    src_pos: i32,
+    dest: &impl Array<'a>,
+    dest_pos: i32,
+    length: i32,
+) -> Result<(), VmError> {
+    // Entries of object arrays are checked one by one, while copying them
+    let dest_entries_type = match (src.elements_type(), dest.elements_type()) {
+        (ArrayEntryType::Base(src_type), ArrayEntryType::Base(dest_type))
+            if src_type == dest_type =>
+        {
+            None
+        }
+        (ArrayEntryType::Base(_), _) | (_, ArrayEntryType::Base(_)) => {
+            return Err(VmError::ArrayStoreException(format!(
+                "arraycopy: type mismatch: can not copy {}[] into {}[]",
+                describe_array_copy_type(src),
+                describe_array_copy_type(dest)
+            )));
+        }
+        (_, dest_type) => Some(
+            dest_type
+                .into_field_type(vm)
+                .ok_or(VmError::ValidationException)?,
+        ),
+    };
+    check_array_copy_bounds(src, src_pos, dest, dest_pos, length)?;
+
+    // The arrays might be the same, thus we read all the entries before writing them
+    let entries = (src_pos..src_pos + length)
+        .map(|index| src.get_element(index.into_usize_safe()))
+        .collect::<Result<Vec<Value<'a>>, VmError>>()?;
+    for (i, entry) in entries.into_iter().enumerate() {
+        if let Some(dest_entries_type) = &dest_entries_type {
+            if !entry.matches_type(dest_entries_type.clone(), vm, |class_name| {
+                vm.find_class_by_name(class_name)
+            }) {
+                let src_entries_type = src
+                    .elements_type()
+                    .into_field_type(vm)
+                    .ok_or(VmError::ValidationException)?;
+                return Err(VmError::ArrayStoreException(format!(
+                    "arraycopy: element type mismatch: can not cast one of the elements of {}[] \
+                     to the type of the destination array, {}",
+                    describe_array_copy_entries_class(&src_entries_type),
+                    describe_array_copy_entries_class(dest_entries_type)
+                )));
+            }
+        }
+        dest.set_element(dest_pos.into_usize_safe() + i, entry)?;
+    }
+    Ok(())
+}
+
+fn check_array_copy_bounds<'a>(
     src: &impl Array<'a>,
     src_pos: i32,
     dest: &impl Array<'a>,
     dest_pos: i32,
-    length: usize,
+    length: i32,
 ) -> Result<(), VmError> {
-    if dest.elements_type() != src.elements_type() {
-        // TODO: we should throw an instance of ArrayStoreException
-        return Err(VmError::ValidationException);
+    let message = if src_pos < 0 {
+        format!(
+            "arraycopy: source index {src_pos} out of bounds for {}[{}]",
+            describe_array_copy_type(src),
+            src.len()
+        )
+    } else if dest_pos < 0 {
+        format!(
+            "arraycopy: destination index {dest_pos} out of bounds for {}[{}]",
+            describe_array_copy_type(dest),
+            dest.len()
+        )
+    } else if length < 0 {
+        format!("arraycopy: length {length} is negative")
+    } else if src_pos as i64 + length as i64 > src.len() as i64 {
+        format!(
+            "arraycopy: last source index {} out of bounds for {}[{}]",
+            src_pos as i64 + length as i64,
+            describe_array_copy_type(src),
+            src.len()
+        )
+    } else if dest_pos as i64 + length as i64 > dest.len() as i64 {
+        format!(
+            "arraycopy: last destination index {} out of bounds for {}[{}]",
+            dest_pos as i64 + length as i64,
+            describe_array_copy_type(dest),
+            dest.len()
+        )
+    } else {
+        return Ok(());
+    };
+    Err(VmError::ArrayIndexOutOfBoundsException(message))
+}
+
+/// Describes the entries of an array like HotSpot does in the messages of the exceptions
+/// thrown by `System.arraycopy`, i.e. `int` or `object array`
+fn describe_array_copy_type<'a>(array: &impl Array<'a>) -> String {
+    match array.elements_type() {
+        ArrayEntryType::Base(base_type) => base_type.to_string().to_lowercase(),
+        ArrayEntryType::Object(_) | ArrayEntryType::Array(..) => "object array".to_string(),
+    }
+}
+
+fn describe_array_copy_entries_class(entries_type: &FieldType) -> String {
+    match entries_type {
+        FieldType::Object(class_name) => class_name.replace('/', "."),
+        _ => entries_type.to_descriptor().replace('/', "."),
     }
+}
+
+/// `System.in`, `System.out` and `System.err` are final, thus they are set natively
+fn set_system_stream<'a>(
+    vm: &mut Vm<'a>,
+    field_name: &str,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let stream = args.first().cloned().ok_or(VmError::ValidationException)?;
+    let system_class = vm
+        .find_class_by_name("java/lang/System")
+        .ok_or(VmError::ValidationException)?;
+    let (index, _) = system_class
+        .find_field(field_name)
+        .ok_or(VmError::FieldNotFoundException(
+            system_class.name.clone(),
+            field_name.to_string(),
+        ))?;
+    let static_instance = vm
+        .get_static_instance(system_class.id)
+        .ok_or(VmError::ValidationException)?;
+    static_instance.set_field(index, stream);
+    Ok(None)
+}
+
+/// Sets the system properties that the vm knows about
+fn init_properties<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let properties = expect_abstract_object_at(args, 0)?;
+    let line_separator = if cfg!(windows) { "\r\n" } else { "\n" };
+    let path_separator = if cfg!(windows) { ";" } else { ":" };
+    let current_dir = std::env::current_dir()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let values = [
+        ("line.separator", line_separator),
+        ("file.separator", std::path::MAIN_SEPARATOR_STR),
+        ("path.separator", path_separator),
+        ("file.encoding", "UTF-8"),
+        ("os.name", std::env::consts::OS),
+        ("os.arch", std::env::consts::ARCH),
+        ("user.dir", &current_dir),
+        ("java.vm.name", "rjvm"),
+    ];
 
-    for i in 0..length {
-        let src_index = src_pos.into_usize_safe() + i;
-        let src_item = src.get_element(src_index)?;
+    let set_property = vm.resolve_class_method(
+        call_stack,
+        "java/util/Properties",
+        "setProperty",
+        "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/Object;",
+    )?;
+    let properties_index = vm.push_temporary_root(properties);
+    let result = values
+        .iter()
+        .try_for_each(|(key, value)| -> Result<(), MethodCallFailed<'a>> {
+            let key = new_java_lang_string_object(vm, call_stack, key)?;
+            let key_index = vm.push_temporary_root(key);
+            let value = new_java_lang_string_object(vm, call_stack, value)?;
+            let key = vm.get_temporary_root(key_index)?;
+            let properties = vm.get_temporary_root(properties_index)?;
+            vm.truncate_temporary_roots(key_index);
+            vm.invoke(
+                call_stack,
+                set_property.clone(),
+                Some(properties),
+                vec![Value::Object(key), Value::Object(value)],
+            )?;
+            Ok(())
+        });
+    let properties = vm.get_temporary_root(properties_index);
+    vm.truncate_temporary_roots(properties_index);
+    result?;
+    Ok(Some(Value::Object(properties?)))
+}
 
-        let dest_index = dest_pos.into_usize_safe() + i;
-        dest.set_element(dest_index, src_item)?;
+/// Implements `FileOutputStream.writeBytes(byte[] b, int off, int len, boolean append)`
+fn write_bytes<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let bytes = match args.first() {
+        Some(Value::Object(array)) => array.clone(),
+        _ => {
+            return Err(MethodCallFailed::InternalError(
+                VmError::NullPointerException(None),
+            ))
+        }
+    };
+    let offset = expect_int_at(args, 1)?;
+    let length = expect_int_at(args, 2)?;
+    if offset < 0 || length < 0 || offset as i64 + length as i64 > bytes.len() as i64 {
+        return Err(MethodCallFailed::InternalError(
+            VmError::ArrayIndexOutOfBoundsException(format!(
+                "Range [{offset}, {offset} + {length}) out of bounds for length {}",
+                bytes.len()
+            )),
+        ));
     }
 
-    Ok(())
+    let bytes = (offset..offset + length)
+        .map(|index| match bytes.get_element(index.into_usize_safe())? {
+            Value::Int(byte) => Ok(byte as u8),
+            _ => Err(VmError::ValidationException),
+        })
+        .collect::<Result<Vec<u8>, VmError>>()?;
+    write_to_file_output_stream(vm, call_stack, receiver, &bytes)
+}
+
+fn write_to_file_output_stream<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+    bytes: &[u8],
+) -> MethodCallResult<'a> {
+    let receiver = expect_some_receiver(receiver)?;
+    let result = match get_file_descriptor(vm, &receiver)? {
+        Some(fd) => vm
+            .write_to_file_descriptor(fd, bytes)
+            .map_err(|err| err.to_string()),
+        None => Err("Stream Closed".to_string()),
+    };
+    match result {
+        Ok(()) => Ok(None),
+        Err(message) => Err(MethodCallFailed::ExceptionThrown(
+            vm.new_exception_with_message(call_stack, "java/io/IOException", &message)?,
+        )),
+    }
+}
+
+/// Reads the field `fd` of a `FileOutputStream`, and the number of the
+/// file descriptor stored in it
+fn get_file_descriptor<'a>(
+    vm: &Vm<'a>,
+    stream: &AbstractObject<'a>,
+) -> Result<Option<i32>, VmError> {
+    let stream_class = vm.get_class_by_id(stream.class_id())?;
+    let (index, _) = stream_class
+        .find_field("fd")
+        .ok_or(VmError::ValidationException)?;
+    let file_descriptor = match stream.get_field(stream_class, index) {
+        Value::Object(file_descriptor) => file_descriptor,
+        _ => return Ok(None),
+    };
+
+    let file_descriptor_class = vm.get_class_by_id(file_descriptor.class_id())?;
+    let (index, _) = file_descriptor_class
+        .find_field("fd")
+        .ok_or(VmError::ValidationException)?;
+    match file_descriptor.get_field(file_descriptor_class, index) {
+        Value::Int(fd) if fd >= 0 => Ok(Some(fd)),
+        _ => Ok(None),
+    }
 }
 
 fn float_to_raw_int_bits<'a>(args: &[Value<'a>]) -> MethodCallResult<'a> {
@@ -241,10 +855,110 @@ fn get_primitive_class<'a>(
 ) -> MethodCallResult<'a> {
     let arg = expect_concrete_object_at(args, 0)?;
     let class_name = extract_str_from_java_lang_string(vm, &arg)?;
-    let java_lang_class_instance = new_java_lang_class_object(vm, stack, &class_name)?;
+    let mirrored_type =
+        MirroredType::of_primitive_name(&class_name).ok_or(VmError::ValidationException)?;
+    let java_lang_class_instance = vm.get_class_mirror(stack, mirrored_type)?;
     Ok(Some(Value::Object(java_lang_class_instance)))
 }
 
+fn get_class<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+) -> MethodCallResult<'a> {
+    let receiver = expect_some_receiver(receiver)?;
+    let mirrored_type = MirroredType::of_object(vm, &receiver)?;
+    Ok(Some(Value::Object(
+        vm.get_class_mirror(stack, mirrored_type)?,
+    )))
+}
+
+/// Returns the type represented by the given instance of `java.lang.Class`
+fn expect_mirrored_type<'a>(
+    vm: &Vm<'a>,
+    receiver: Option<AbstractObject<'a>>,
+) -> Result<MirroredType<'a>, VmError> {
+    let receiver = expect_some_receiver(receiver)?;
+    vm.get_mirrored_type(&receiver)
+        .cloned()
+        .ok_or(VmError::ValidationException)
+}
+
+fn get_class_name<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+) -> MethodCallResult<'a> {
+    let mirrored_type = expect_mirrored_type(vm, receiver)?;
+    let name = vm.intern_string(stack, &mirrored_type.java_name())?;
+    Ok(Some(Value::Object(name)))
+}
+
+fn get_superclass<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+) -> MethodCallResult<'a> {
+    let superclass = match expect_mirrored_type(vm, receiver)? {
+        MirroredType::Class(class) if !class.is_interface() => class.superclass,
+        MirroredType::Array(_) => Some(vm.get_or_resolve_class(stack, "java/lang/Object")?),
+        _ => None,
+    };
+    match superclass {
+        Some(superclass) => Ok(Some(Value::Object(
+            vm.get_class_mirror(stack, MirroredType::Class(superclass))?,
+        ))),
+        None => Ok(Some(Value::Null)),
+    }
+}
+
+fn get_component_type<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+) -> MethodCallResult<'a> {
+    match expect_mirrored_type(vm, receiver)? {
+        MirroredType::Array(component_type) => Ok(Some(Value::Object(
+            vm.get_class_mirror(stack, *component_type)?,
+        ))),
+        _ => Ok(Some(Value::Null)),
+    }
+}
+
+fn is_instance<'a>(
+    vm: &mut Vm<'a>,
+    receiver: Option<AbstractObject<'a>>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let mirrored_type = expect_mirrored_type(vm, receiver)?;
+    let is_instance = match args.first() {
+        Some(Value::Object(object)) => {
+            mirrored_type.is_assignable_from(vm, &MirroredType::of_object(vm, object)?)?
+        }
+        _ => false,
+    };
+    Ok(Some(Value::Int(is_instance.into())))
+}
+
+fn is_assignable_from<'a>(
+    vm: &mut Vm<'a>,
+    receiver: Option<AbstractObject<'a>>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let mirrored_type = expect_mirrored_type(vm, receiver)?;
+    let other_type = match args.first() {
+        Some(Value::Object(other)) => expect_mirrored_type(vm, Some(other.clone()))?,
+        _ => {
+            return Err(MethodCallFailed::InternalError(
+                VmError::NullPointerException(None),
+            ))
+        }
+    };
+    Ok(Some(Value::Int(
+        mirrored_type.is_assignable_from(vm, &other_type)?.into(),
+    )))
+}
+
 fn fill_in_stack_trace<'a>(
     vm: &mut Vm<'a>,
     call_stack: &mut CallStack<'a>,
@@ -0,0 +1,757 @@
+use rjvm_reader::{
+    field_flags::FieldFlags, field_type::FieldType, method_flags::MethodFlags,
+    type_conversion::ToUsizeSafe, ClassAccessFlags, ClassFileMethod,
+};
+
+use crate::{
+    abstract_object::AbstractObject,
+    array::Array,
+    array_entry_type::ArrayEntryType,
+    call_frame::{CallFrame, MethodCallResult},
+    call_stack::CallStack,
+    class::ClassRef,
+    class_and_method::ClassAndMethod,
+    class_mirror::MirroredType,
+    direct_method_handle::convert_value,
+    exceptions::{JavaException, MethodCallFailed},
+    java_objects_creation::extract_str_from_java_lang_string,
+    object::Object,
+    value::{expect_abstract_object_at, expect_int_at, expect_long_at, Value},
+    vm::Vm,
+    vm_error::VmError,
+};
+
+// Values of the constants of java.lang.reflect.Modifier that are not class access flags
+const MODIFIER_PRIVATE: i32 = 0x0002;
+const MODIFIER_PROTECTED: i32 = 0x0004;
+
+/// Implements `Class.forName0`. The class loader and the caller are ignored,
+/// since we only have the bootstrap class loader.
+pub(crate) fn class_for_name<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let name = match args.first() {
+        Some(Value::Object(name)) => extract_str_from_java_lang_string(vm, name)?,
+        _ => {
+            return Err(MethodCallFailed::InternalError(
+                VmError::NullPointerException(None),
+            ))
+        }
+    };
+    let initialize = expect_int_at(args, 1)? != 0;
+
+    let mirrored_type = match MirroredType::of_class_name(vm, call_stack, &name.replace('.', "/")) {
+        Ok(mirrored_type) => mirrored_type,
+        Err(MethodCallFailed::InternalError(VmError::ClassNotFoundException(_))) => {
+            let exception = vm.new_exception_with_message(
+                call_stack,
+                "java/lang/ClassNotFoundException",
+                &name,
+            )?;
+            return Err(MethodCallFailed::ExceptionThrown(exception));
+        }
+        Err(err) => return Err(err),
+    };
+    if let (true, MirroredType::Class(class)) = (initialize, &mirrored_type) {
+        vm.initialize_class(call_stack, class)?;
+    }
+    Ok(Some(Value::Object(
+        vm.get_class_mirror(call_stack, mirrored_type)?,
+    )))
+}
+
+/// Implements `Class.getModifiers`
+pub(crate) fn class_modifiers(mirrored_type: &MirroredType) -> i32 {
+    match mirrored_type {
+        MirroredType::Class(class) => (class.flags - ClassAccessFlags::SUPER).bits() as i32,
+        MirroredType::Array(component_type) => {
+            let visibility =
+                ClassAccessFlags::PUBLIC.bits() as i32 | MODIFIER_PRIVATE | MODIFIER_PROTECTED;
+            (class_modifiers(component_type) & visibility)
+                | (ClassAccessFlags::FINAL | ClassAccessFlags::ABSTRACT).bits() as i32
+        }
+        MirroredType::Primitive(_) | MirroredType::Void => {
+            (ClassAccessFlags::PUBLIC | ClassAccessFlags::FINAL | ClassAccessFlags::ABSTRACT).bits()
+                as i32
+        }
+    }
+}
+
+/// Implements `Class.getDeclaredFields0`
+pub(crate) fn get_declared_fields<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    mirrored_type: MirroredType<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let public_only = expect_int_at(args, 0)? != 0;
+    let slots = declared_class(&mirrored_type)
+        .map(|class| {
+            class
+                .fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| !public_only || field.flags.contains(FieldFlags::PUBLIC))
+                .map(|(slot, _)| slot)
+                .collect()
+        })
+        .unwrap_or_default();
+    new_members_array(
+        vm,
+        call_stack,
+        "java/lang/reflect/Field",
+        &mirrored_type,
+        slots,
+        new_field_object,
+    )
+}
+
+/// Implements `Class.getDeclaredMethods0`
+pub(crate) fn get_declared_methods<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    mirrored_type: MirroredType<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let public_only = expect_int_at(args, 0)? != 0;
+    let slots = declared_method_slots(&mirrored_type, public_only, |method| {
+        method.name != "<init>" && method.name != "<clinit>"
+    });
+    new_members_array(
+        vm,
+        call_stack,
+        "java/lang/reflect/Method",
+        &mirrored_type,
+        slots,
+        new_method_object,
+    )
+}
+
+/// Implements `Class.getDeclaredConstructors0`
+pub(crate) fn get_declared_constructors<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    mirrored_type: MirroredType<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let public_only = expect_int_at(args, 0)? != 0;
+    let slots = declared_method_slots(&mirrored_type, public_only, |method| {
+        method.name == "<init>"
+    });
+    new_members_array(
+        vm,
+        call_stack,
+        "java/lang/reflect/Constructor",
+        &mirrored_type,
+        slots,
+        new_method_object,
+    )
+}
+
+/// Arrays and primitive types do not declare any member
+fn declared_class<'a>(mirrored_type: &MirroredType<'a>) -> Option<ClassRef<'a>> {
+    match mirrored_type {
+        MirroredType::Class(class) => Some(class),
+        _ => None,
+    }
+}
+
+fn declared_method_slots(
+    mirrored_type: &MirroredType,
+    public_only: bool,
+    predicate: impl Fn(&ClassFileMethod) -> bool,
+) -> Vec<usize> {
+    declared_class(mirrored_type)
+        .map(|class| {
+            class
+                .methods
+                .iter()
+                .enumerate()
+                .filter(|(_, method)| {
+                    predicate(method)
+                        && (!public_only || method.flags.contains(MethodFlags::PUBLIC))
+                })
+                .map(|(slot, _)| slot)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+type NewMemberObject<'a> = fn(
+    &mut Vm<'a>,
+    &mut CallStack<'a>,
+    ClassRef<'a>,
+    usize,
+) -> Result<AbstractObject<'a>, MethodCallFailed<'a>>;
+
+/// Creates an array of the given reflection class, containing one object for each of
+/// the given slots, i.e. indexes of the field or method in the class
+fn new_members_array<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    member_class_name: &str,
+    mirrored_type: &MirroredType<'a>,
+    slots: Vec<usize>,
+    new_member_object: NewMemberObject<'a>,
+) -> MethodCallResult<'a> {
+    let member_class = vm.get_or_resolve_class(call_stack, member_class_name)?;
+    let array = vm.new_array(
+        call_stack,
+        ArrayEntryType::Object(member_class.id),
+        slots.len(),
+    )?;
+    let Some(class) = declared_class(mirrored_type) else {
+        return Ok(Some(Value::Object(array)));
+    };
+
+    // Creating the members will allocate, possibly moving the array
+    let array_index = vm.push_temporary_root(array);
+    let result = slots
+        .into_iter()
+        .enumerate()
+        .try_for_each(|(index, slot)| -> Result<(), MethodCallFailed<'a>> {
+            let member = new_member_object(vm, call_stack, class, slot)?;
+            vm.get_temporary_root(array_index)?
+                .set_element(index, Value::Object(member))?;
+            Ok(())
+        })
+        .and_then(|_| Ok(vm.get_temporary_root(array_index)?));
+    vm.truncate_temporary_roots(array_index);
+    Ok(Some(Value::Object(result?)))
+}
+
+/// Creates an instance of `java.lang.reflect.Field`
+fn new_field_object<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    class: ClassRef<'a>,
+    slot: usize,
+) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+    let field = &class.fields[slot];
+    let field_type = MirroredType::of_field_type(vm, call_stack, &field.type_descriptor)?;
+
+    // Mirrors and interned strings are created only once, so we create them before allocating
+    // the field object. Looking them up afterward will not allocate and move it.
+    vm.get_class_mirror(call_stack, MirroredType::Class(class))?;
+    vm.get_class_mirror(call_stack, field_type.clone())?;
+    vm.intern_string(call_stack, &field.name)?;
+
+    let field_object = vm.new_object(call_stack, "java/lang/reflect/Field")?;
+    let declaring_class = vm.get_class_mirror(call_stack, MirroredType::Class(class))?;
+    let name = vm.intern_string(call_stack, &field.name)?;
+    let field_type = vm.get_class_mirror(call_stack, field_type)?;
+    set_fields(
+        vm,
+        &field_object,
+        &[
+            ("clazz", Value::Object(declaring_class)),
+            ("slot", Value::Int(slot as i32)),
+            ("name", Value::Object(name)),
+            ("type", Value::Object(field_type)),
+            ("modifiers", Value::Int(field.flags.bits() as i32)),
+        ],
+    )?;
+    Ok(field_object)
+}
+
+/// Creates an instance of `java.lang.reflect.Method`, or of `java.lang.reflect.Constructor`
+/// for the `<init>` methods
+fn new_method_object<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    class: ClassRef<'a>,
+    slot: usize,
+) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+    let method = &class.methods[slot];
+    let parameter_types =
+        new_class_array(vm, call_stack, &method.parsed_type_descriptor.parameters)?;
+    let parameter_types_index = vm.push_temporary_root(parameter_types);
+    let result = new_class_array(
+        vm,
+        call_stack,
+        &method
+            .thrown_exceptions
+            .iter()
+            .map(|class_name| FieldType::Object(class_name.clone()))
+            .collect::<Vec<_>>(),
+    )
+    .and_then(|exception_types| {
+        let exception_types_index = vm.push_temporary_root(exception_types);
+        new_method_object_with_types(
+            vm,
+            call_stack,
+            class,
+            slot,
+            parameter_types_index,
+            exception_types_index,
+        )
+    });
+    vm.truncate_temporary_roots(parameter_types_index);
+    result
+}
+
+fn new_method_object_with_types<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    class: ClassRef<'a>,
+    slot: usize,
+    parameter_types_index: usize,
+    exception_types_index: usize,
+) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+    let method = &class.methods[slot];
+    let is_constructor = method.name == "<init>";
+    let return_type = match &method.parsed_type_descriptor.return_type {
+        Some(return_type) => MirroredType::of_field_type(vm, call_stack, return_type)?,
+        None => MirroredType::Void,
+    };
+    vm.get_class_mirror(call_stack, MirroredType::Class(class))?;
+    vm.get_class_mirror(call_stack, return_type.clone())?;
+    vm.intern_string(call_stack, &method.name)?;
+
+    let method_object = vm.new_object(
+        call_stack,
+        if is_constructor {
+            "java/lang/reflect/Constructor"
+        } else {
+            "java/lang/reflect/Method"
+        },
+    )?;
+    let declaring_class = vm.get_class_mirror(call_stack, MirroredType::Class(class))?;
+    let mut fields = vec![
+        ("clazz", Value::Object(declaring_class)),
+        ("slot", Value::Int(slot as i32)),
+        (
+            "parameterTypes",
+            Value::Object(vm.get_temporary_root(parameter_types_index)?),
+        ),
+        (
+            "exceptionTypes",
+            Value::Object(vm.get_temporary_root(exception_types_index)?),
+        ),
+        ("modifiers", Value::Int(method.flags.bits() as i32)),
+    ];
+    if !is_constructor {
+        let name = vm.intern_string(call_stack, &method.name)?;
+        let return_type = vm.get_class_mirror(call_stack, return_type)?;
+        fields.push(("name", Value::Object(name)));
+        fields.push(("returnType", Value::Object(return_type)));
+    }
+    set_fields(vm, &method_object, &fields)?;
+    Ok(method_object)
+}
+
+/// Creates a `Class[]` containing the mirrors of the given types
+fn new_class_array<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    types: &[FieldType],
+) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+    let mut mirrored_types = Vec::with_capacity(types.len());
+    for field_type in types {
+        let mirrored_type = MirroredType::of_field_type(vm, call_stack, field_type)?;
+        vm.get_class_mirror(call_stack, mirrored_type.clone())?;
+        mirrored_types.push(mirrored_type);
+    }
+
+    let class_class = vm.get_or_resolve_class(call_stack, "java/lang/Class")?;
+    let array = vm.new_array(
+        call_stack,
+        ArrayEntryType::Object(class_class.id),
+        mirrored_types.len(),
+    )?;
+    for (index, mirrored_type) in mirrored_types.into_iter().enumerate() {
+        let mirror = vm.get_class_mirror(call_stack, mirrored_type)?;
+        array.set_element(index, Value::Object(mirror))?;
+    }
+    Ok(array)
+}
+
+/// Sets the fields of the given object, by name, skipping the ones that do not exist
+fn set_fields<'a>(
+    vm: &Vm<'a>,
+    object: &AbstractObject<'a>,
+    fields: &[(&str, Value<'a>)],
+) -> Result<(), VmError> {
+    let class = vm.get_class_by_id(object.class_id())?;
+    for (name, value) in fields {
+        if let Some((index, _)) = class.find_field(name) {
+            object.set_field(index, value.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the class and the method, or constructor, represented by the given
+/// instance of `java.lang.reflect.Method` or `java.lang.reflect.Constructor`
+fn expect_executable<'a>(
+    vm: &Vm<'a>,
+    executable: &AbstractObject<'a>,
+) -> Result<ClassAndMethod<'a>, VmError> {
+    let (class, slot) = expect_declaring_class_and_slot(vm, executable)?;
+    let method = class
+        .methods
+        .get(slot)
+        .ok_or(VmError::ValidationException)?;
+    Ok(ClassAndMethod { class, method })
+}
+
+/// Reads the fields `clazz` and `slot` of a `Field`, `Method` or `Constructor`
+fn expect_declaring_class_and_slot<'a>(
+    vm: &Vm<'a>,
+    member: &AbstractObject<'a>,
+) -> Result<(ClassRef<'a>, usize), VmError> {
+    let member_class = vm.get_class_by_id(member.class_id())?;
+    let (clazz_index, _) = member_class
+        .find_field("clazz")
+        .ok_or(VmError::ValidationException)?;
+    let (slot_index, _) = member_class
+        .find_field("slot")
+        .ok_or(VmError::ValidationException)?;
+
+    let class = match member.get_field(member_class, clazz_index) {
+        Value::Object(mirror) => match vm.get_mirrored_type(&mirror) {
+            Some(MirroredType::Class(class)) => *class,
+            _ => return Err(VmError::ValidationException),
+        },
+        _ => return Err(VmError::ValidationException),
+    };
+    let slot = match member.get_field(member_class, slot_index) {
+        Value::Int(slot) => slot.into_usize_safe(),
+        _ => return Err(VmError::ValidationException),
+    };
+    Ok((class, slot))
+}
+
+/// Implements `NativeMethodAccessorImpl.invoke0`, used by `Method.invoke`
+pub(crate) fn invoke_method<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let method_object = expect_abstract_object_at(args, 0)?;
+    let class_and_method = expect_executable(vm, &method_object)?;
+
+    let receiver = if class_and_method.is_static() {
+        None
+    } else {
+        match args.get(1) {
+            Some(Value::Object(receiver)) => Some(receiver.clone()),
+            _ => {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::NullPointerException(None),
+                ))
+            }
+        }
+    };
+    if let Some(receiver) = &receiver {
+        let declaring_type = MirroredType::Class(class_and_method.class);
+        if !declaring_type.is_assignable_from(vm, &MirroredType::of_object(vm, receiver)?)? {
+            return Err(illegal_argument(
+                vm,
+                call_stack,
+                "object is not an instance of declaring class",
+            ));
+        }
+    }
+
+    // The receiver and the arguments array are kept as roots, since converting
+    // the arguments can allocate
+    let receiver_index = receiver.map(|receiver| vm.push_temporary_root(receiver));
+    let arguments_index = arguments_array(args, 2).map(|array| vm.push_temporary_root(array));
+    let result = invoke_method_with_roots(
+        vm,
+        call_stack,
+        class_and_method,
+        receiver_index,
+        arguments_index,
+    );
+    if let Some(index) = receiver_index.or(arguments_index) {
+        vm.truncate_temporary_roots(index);
+    }
+    result
+}
+
+fn invoke_method_with_roots<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    class_and_method: ClassAndMethod<'a>,
+    receiver_index: Option<usize>,
+    arguments_index: Option<usize>,
+) -> MethodCallResult<'a> {
+    if class_and_method.is_static() {
+        vm.initialize_class(call_stack, class_and_method.class)?;
+    }
+    let arguments = convert_arguments(vm, call_stack, &class_and_method, arguments_index)?;
+
+    let receiver = receiver_index
+        .map(|index| vm.get_temporary_root(index))
+        .transpose()?;
+    let is_virtual = !class_and_method.is_static()
+        && !class_and_method.method.flags.contains(MethodFlags::PRIVATE);
+    let class_and_method = if is_virtual {
+        CallFrame::resolve_virtual_method(vm, receiver.clone(), class_and_method)?
+    } else {
+        class_and_method
+    };
+
+    let return_type = class_and_method.return_type();
+    let result = vm.invoke(call_stack, class_and_method, receiver, arguments);
+    let result = wrap_in_invocation_target_exception(vm, call_stack, result)?;
+    match (return_type, result) {
+        (Some(return_type @ FieldType::Base(_)), Some(value)) => {
+            let boxed = convert_value(
+                vm,
+                call_stack,
+                value,
+                &return_type,
+                &FieldType::Object("java/lang/Object".to_string()),
+            )?;
+            Ok(Some(boxed))
+        }
+        (Some(_), Some(value)) => Ok(Some(value)),
+        _ => Ok(Some(Value::Null)),
+    }
+}
+
+/// Implements `NativeConstructorAccessorImpl.newInstance0`, used by `Constructor.newInstance`
+pub(crate) fn new_instance<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let constructor_object = expect_abstract_object_at(args, 0)?;
+    let constructor = expect_executable(vm, &constructor_object)?;
+    let class = constructor.class;
+    if class.is_interface() || class.flags.contains(ClassAccessFlags::ABSTRACT) {
+        let exception = vm.new_exception_with_message(
+            call_stack,
+            "java/lang/InstantiationException",
+            &class.name.replace('/', "."),
+        )?;
+        return Err(MethodCallFailed::ExceptionThrown(exception));
+    }
+
+    // The arguments array and the new object are kept as roots, since converting
+    // the arguments can allocate
+    let arguments_index = arguments_array(args, 1).map(|array| vm.push_temporary_root(array));
+    let mut object_index = None;
+    let result = vm
+        .initialize_class(call_stack, class)
+        .and_then(|_| Ok(vm.new_object_of_class(class)?))
+        .and_then(|object| {
+            let index = vm.push_temporary_root(object);
+            object_index = Some(index);
+            new_instance_with_roots(vm, call_stack, constructor, index, arguments_index)
+        });
+    if let Some(index) = arguments_index.or(object_index) {
+        vm.truncate_temporary_roots(index);
+    }
+    result
+}
+
+fn new_instance_with_roots<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    constructor: ClassAndMethod<'a>,
+    object_index: usize,
+    arguments_index: Option<usize>,
+) -> MethodCallResult<'a> {
+    let arguments = convert_arguments(vm, call_stack, &constructor, arguments_index)?;
+    let object = vm.get_temporary_root(object_index)?;
+    let result = vm.invoke(call_stack, constructor, Some(object), arguments);
+    wrap_in_invocation_target_exception(vm, call_stack, result)?;
+    Ok(Some(Value::Object(vm.get_temporary_root(object_index)?)))
+}
+
+/// The array of arguments passed to `invoke0` or `newInstance0`, which is null
+/// when there are no arguments
+fn arguments_array<'a>(args: &[Value<'a>], index: usize) -> Option<AbstractObject<'a>> {
+    match args.get(index) {
+        Some(Value::Object(array)) => Some(array.clone()),
+        _ => None,
+    }
+}
+
+/// Converts the arguments passed via reflection, kept in [Vm::temporary_roots], to the
+/// parameters of the method: primitives get unboxed and references are checked
+fn convert_arguments<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    class_and_method: &ClassAndMethod<'a>,
+    arguments_index: Option<usize>,
+) -> Result<Vec<Value<'a>>, MethodCallFailed<'a>> {
+    let parameters = &class_and_method.method.parsed_type_descriptor.parameters;
+    let arguments_count = match arguments_index {
+        Some(index) => vm.get_temporary_root(index)?.len().into_usize_safe(),
+        None => 0,
+    };
+    if arguments_count != parameters.len() {
+        return Err(illegal_argument(
+            vm,
+            call_stack,
+            "wrong number of arguments",
+        ));
+    }
+
+    let mut parameter_types = Vec::with_capacity(parameters.len());
+    for parameter in parameters {
+        parameter_types.push(MirroredType::of_field_type(vm, call_stack, parameter)?);
+    }
+
+    // Unboxing invokes java code, so we first convert all primitives and then read
+    // the references, which can have been moved in the meantime
+    let object_type = FieldType::Object("java/lang/Object".to_string());
+    let mut arguments = Vec::with_capacity(parameters.len());
+    for (index, parameter) in parameters.iter().enumerate() {
+        let argument = match (parameter, arguments_index) {
+            (FieldType::Base(_), Some(arguments_index)) => {
+                let value = vm.get_temporary_root(arguments_index)?.get_element(index)?;
+                match convert_value(vm, call_stack, value, &object_type, parameter) {
+                    Ok(value) => value,
+                    Err(MethodCallFailed::InternalError(
+                        VmError::NullPointerException(_)
+                        | VmError::ClassCastException(_)
+                        | VmError::WrongMethodTypeException(_),
+                    )) => {
+                        return Err(illegal_argument(vm, call_stack, "argument type mismatch"));
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            _ => Value::Null,
+        };
+        arguments.push(argument);
+    }
+    for (index, parameter_type) in parameter_types.iter().enumerate() {
+        let (MirroredType::Class(_) | MirroredType::Array(_), Some(arguments_index)) =
+            (parameter_type, arguments_index)
+        else {
+            continue;
+        };
+        let value = vm.get_temporary_root(arguments_index)?.get_element(index)?;
+        if let Value::Object(object) = &value {
+            if !parameter_type.is_assignable_from(vm, &MirroredType::of_object(vm, object)?)? {
+                return Err(illegal_argument(vm, call_stack, "argument type mismatch"));
+            }
+        }
+        arguments[index] = value;
+    }
+
+    let mut index = 0;
+    while index < arguments.len() {
+        if matches!(arguments[index], Value::Long(_) | Value::Double(_)) {
+            arguments.insert(index + 1, Value::Uninitialized);
+            index += 1;
+        }
+        index += 1;
+    }
+    Ok(arguments)
+}
+
+/// Exceptions thrown by a method invoked via reflection get wrapped
+/// in a `java.lang.reflect.InvocationTargetException`
+fn wrap_in_invocation_target_exception<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    result: MethodCallResult<'a>,
+) -> MethodCallResult<'a> {
+    match result {
+        Err(MethodCallFailed::ExceptionThrown(JavaException(cause))) => {
+            let cause_index = vm.push_temporary_root(cause);
+            let exception = vm.new_exception_with_cause(
+                call_stack,
+                "java/lang/reflect/InvocationTargetException",
+                cause_index,
+            );
+            vm.truncate_temporary_roots(cause_index);
+            Err(MethodCallFailed::ExceptionThrown(exception?))
+        }
+        result => result,
+    }
+}
+
+fn illegal_argument<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    message: &str,
+) -> MethodCallFailed<'a> {
+    match vm.new_exception_with_message(call_stack, "java/lang/IllegalArgumentException", message) {
+        Ok(exception) => MethodCallFailed::ExceptionThrown(exception),
+        Err(err) => err,
+    }
+}
+
+/// Implements `Unsafe.objectFieldOffset` and `Unsafe.staticFieldOffset`: the offset
+/// of a field is simply its index in the object
+pub(crate) fn field_offset<'a>(vm: &Vm<'a>, args: &[Value<'a>]) -> MethodCallResult<'a> {
+    let field_object = expect_abstract_object_at(args, 0)?;
+    let (class, slot) = expect_declaring_class_and_slot(vm, &field_object)?;
+    Ok(Some(Value::Long((class.first_field_index + slot) as i64)))
+}
+
+/// Implements `Unsafe.staticFieldBase`, returning the object that holds the static fields
+pub(crate) fn static_field_base<'a>(vm: &Vm<'a>, args: &[Value<'a>]) -> MethodCallResult<'a> {
+    let field_object = expect_abstract_object_at(args, 0)?;
+    let (class, _) = expect_declaring_class_and_slot(vm, &field_object)?;
+    let static_instance = vm
+        .get_static_instance(class.id)
+        .ok_or(VmError::ValidationException)?;
+    Ok(Some(Value::Object(static_instance)))
+}
+
+/// Implements `Unsafe.getXxx(Object, long)`
+pub(crate) fn get_field_at_offset<'a>(vm: &Vm<'a>, args: &[Value<'a>]) -> MethodCallResult<'a> {
+    let (object, class, index) = expect_object_and_field_index(vm, args)?;
+    Ok(Some(object.get_field(class, index)))
+}
+
+/// Implements `Unsafe.putXxx(Object, long, xxx)`
+pub(crate) fn put_field_at_offset<'a>(vm: &Vm<'a>, args: &[Value<'a>]) -> MethodCallResult<'a> {
+    let (object, _, index) = expect_object_and_field_index(vm, args)?;
+    // The offset is a long, thus followed by an uninitialized slot
+    let value = args.get(3).cloned().ok_or(VmError::ValidationException)?;
+    object.set_field(index, value);
+    Ok(None)
+}
+
+fn expect_object_and_field_index<'a>(
+    vm: &Vm<'a>,
+    args: &[Value<'a>],
+) -> Result<(AbstractObject<'a>, ClassRef<'a>, usize), VmError> {
+    let object = match args.first() {
+        Some(Value::Object(object)) => object.clone(),
+        _ => return Err(VmError::NullPointerException(None)),
+    };
+    let class = vm.get_class_by_id(object.class_id())?;
+    let index =
+        usize::try_from(expect_long_at(args, 1)?).map_err(|_| VmError::ValidationException)?;
+    class
+        .field_at_index(index)
+        .ok_or(VmError::ValidationException)?;
+    Ok((object, class, index))
+}
+
+/// Implements `Reflection.getCallerClass`: returns the class of the method that has invoked
+/// the one calling `getCallerClass`, skipping the frames of the reflection implementation
+pub(crate) fn get_caller_class<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+) -> MethodCallResult<'a> {
+    let caller = call_stack
+        .get_stack_trace_elements()
+        .into_iter()
+        .skip(1)
+        .find(|element| {
+            !element.class_name.starts_with("java/lang/reflect/")
+                && !element.class_name.starts_with("sun/reflect/")
+        })
+        .and_then(|element| vm.find_class_by_name(element.class_name));
+    match caller {
+        Some(class) => Ok(Some(Value::Object(
+            vm.get_class_mirror(call_stack, MirroredType::Class(class))?,
+        ))),
+        None => Ok(Some(Value::Null)),
+    }
+}
@@ -0,0 +1,271 @@
+use rjvm_reader::{
+    field_type::{BaseType, FieldType},
+    ConstantPoolEntry,
+};
+
+use crate::{
+    call_stack::CallStack,
+    class::ClassRef,
+    class_and_method::ClassAndMethod,
+    exceptions::MethodCallFailed,
+    invoke_dynamic::{get_constant, get_utf8, parse_method_descriptor},
+    java_objects_creation::extract_str_from_java_lang_string,
+    object::Object,
+    value::Value,
+    vm::Vm,
+    vm_error::VmError,
+};
+
+/// In the recipe of `makeConcatWithConstants`, marks the position of an argument
+const ARGUMENT_TAG: char = '\u{1}';
+/// In the recipe of `makeConcatWithConstants`, marks the position of a constant passed
+/// as a further argument of the bootstrap method
+const CONSTANT_TAG: char = '\u{2}';
+
+/// A string concatenation linked with `StringConcatFactory`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StringConcatRecipe {
+    elements: Vec<RecipeElement>,
+    /// The types of the arguments of the call site, i.e. the values to concatenate
+    pub argument_types: Vec<FieldType>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum RecipeElement {
+    Literal(String),
+    Argument(usize),
+}
+
+impl StringConcatRecipe {
+    /// Implements `StringConcatFactory.makeConcatWithConstants`, whose first static argument
+    /// is the recipe, followed by the constants it refers to.
+    pub fn with_constants(
+        class: ClassRef<'_>,
+        descriptor: &str,
+        arguments: &[u16],
+    ) -> Result<Self, VmError> {
+        let argument_types = parse_method_descriptor(descriptor)?.parameters;
+        let (recipe_index, constant_indexes) = arguments
+            .split_first()
+            .ok_or(VmError::ValidationException)?;
+        let recipe = get_string_constant(class, *recipe_index)?;
+        let mut constants = constant_indexes.iter();
+
+        let mut elements = Vec::new();
+        let mut literal = String::new();
+        let mut next_argument = 0;
+        for char in recipe.chars() {
+            match char {
+                ARGUMENT_TAG => {
+                    if !literal.is_empty() {
+                        elements.push(RecipeElement::Literal(std::mem::take(&mut literal)));
+                    }
+                    elements.push(RecipeElement::Argument(next_argument));
+                    next_argument += 1;
+                }
+                CONSTANT_TAG => {
+                    let constant_index = constants.next().ok_or(VmError::ValidationException)?;
+                    literal.push_str(&render_constant(class, *constant_index)?);
+                }
+                _ => literal.push(char),
+            }
+        }
+        if !literal.is_empty() {
+            elements.push(RecipeElement::Literal(literal));
+        }
+
+        if next_argument != argument_types.len() {
+            return Err(VmError::ValidationException);
+        }
+        Ok(Self {
+            elements,
+            argument_types,
+        })
+    }
+
+    /// Implements `StringConcatFactory.makeConcat`, which concatenates all the arguments
+    pub fn without_constants(descriptor: &str) -> Result<Self, VmError> {
+        let argument_types = parse_method_descriptor(descriptor)?.parameters;
+        Ok(Self {
+            elements: (0..argument_types.len())
+                .map(RecipeElement::Argument)
+                .collect(),
+            argument_types,
+        })
+    }
+
+    /// Builds the result, given the string representation of the arguments
+    pub fn concatenate(&self, arguments: &[String]) -> String {
+        let mut result = String::new();
+        for element in self.elements.iter() {
+            match element {
+                RecipeElement::Literal(literal) => result.push_str(literal),
+                RecipeElement::Argument(index) => result.push_str(&arguments[*index]),
+            }
+        }
+        result
+    }
+}
+
+fn get_string_constant(class: ClassRef<'_>, constant_index: u16) -> Result<&str, VmError> {
+    match get_constant(&class.constants, constant_index)? {
+        &ConstantPoolEntry::StringReference(string_index) => {
+            get_utf8(&class.constants, string_index)
+        }
+        _ => Err(VmError::ValidationException),
+    }
+}
+
+fn render_constant(class: ClassRef<'_>, constant_index: u16) -> Result<String, VmError> {
+    match get_constant(&class.constants, constant_index)? {
+        ConstantPoolEntry::StringReference(_) => {
+            get_string_constant(class, constant_index).map(str::to_string)
+        }
+        &ConstantPoolEntry::Integer(value) => Ok(value.to_string()),
+        &ConstantPoolEntry::Long(value) => Ok(value.to_string()),
+        &ConstantPoolEntry::Float(value) => Ok(float_to_string(value)),
+        &ConstantPoolEntry::Double(value) => Ok(double_to_string(value)),
+        _ => Err(VmError::ValidationException),
+    }
+}
+
+/// Converts a value to a string, following the rules of `String.valueOf`.
+/// For objects, this means invoking their `toString` method.
+pub(crate) fn render_argument<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    value: Value<'a>,
+    field_type: &FieldType,
+) -> Result<String, MethodCallFailed<'a>> {
+    let string = match (value, field_type) {
+        (Value::Int(value), FieldType::Base(BaseType::Boolean)) => (value != 0).to_string(),
+        (Value::Int(value), FieldType::Base(BaseType::Char)) => {
+            String::from_utf16_lossy(&[value as u16])
+        }
+        (Value::Int(value), _) => value.to_string(),
+        (Value::Long(value), _) => value.to_string(),
+        (Value::Float(value), _) => float_to_string(value),
+        (Value::Double(value), _) => double_to_string(value),
+        (Value::Null, _) => "null".to_string(),
+        (Value::Object(object), _) => {
+            let class = vm.get_class_by_id(object.class_id())?;
+            if class.name == "java/lang/String" {
+                extract_str_from_java_lang_string(vm, &object)?
+            } else {
+                let to_string = find_to_string(class)?;
+                match vm.invoke(call_stack, to_string, Some(object), Vec::new())? {
+                    Some(Value::Object(string)) => extract_str_from_java_lang_string(vm, &string)?,
+                    Some(Value::Null) => "null".to_string(),
+                    _ => {
+                        return Err(MethodCallFailed::InternalError(
+                            VmError::ValidationException,
+                        ))
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            ))
+        }
+    };
+    Ok(string)
+}
+
+fn find_to_string(class: ClassRef<'_>) -> Result<ClassAndMethod<'_>, VmError> {
+    let mut current_class = Some(class);
+    while let Some(class) = current_class {
+        if let Some(method) = class.find_method("toString", "()Ljava/lang/String;") {
+            return Ok(ClassAndMethod { class, method });
+        }
+        current_class = class.superclass;
+    }
+    Err(VmError::MethodNotFoundException(
+        class.name.clone(),
+        "toString".to_string(),
+        "()Ljava/lang/String;".to_string(),
+    ))
+}
+
+/// Formats a double like `Double.toString`: plain notation for values in the range
+/// [10^-3, 10^7), and computerized scientific notation otherwise.
+fn double_to_string(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else if value == 0.0 || (1e-3..1e7).contains(&value.abs()) {
+        with_decimal_point(value.to_string())
+    } else {
+        scientific_notation(format!("{value:e}"))
+    }
+}
+
+/// Formats a float like `Float.toString`
+fn float_to_string(value: f32) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else if value == 0.0 || (1e-3..1e7).contains(&value.abs()) {
+        with_decimal_point(value.to_string())
+    } else {
+        scientific_notation(format!("{value:e}"))
+    }
+}
+
+fn with_decimal_point(mut number: String) -> String {
+    if !number.contains('.') {
+        number.push_str(".0");
+    }
+    number
+}
+
+/// Converts Rust's `1.5e10` into java's `1.5E10`
+fn scientific_notation(number: String) -> String {
+    match number.split_once('e') {
+        Some((mantissa, exponent)) => {
+            format!("{}E{}", with_decimal_point(mantissa.to_string()), exponent)
+        }
+        None => number,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string_concat::{double_to_string, float_to_string, StringConcatRecipe};
+
+    #[test]
+    fn make_concat_joins_all_arguments() {
+        let recipe =
+            StringConcatRecipe::without_constants("(Ljava/lang/String;IC)Ljava/lang/String;")
+                .expect("should parse the descriptor");
+        assert_eq!(3, recipe.argument_types.len());
+        assert_eq!(
+            "a1c",
+            recipe.concatenate(&["a".to_string(), "1".to_string(), "c".to_string()])
+        );
+    }
+
+    #[test]
+    fn doubles_are_formatted_like_java() {
+        assert_eq!("0.0", double_to_string(0.0));
+        assert_eq!("-0.0", double_to_string(-0.0));
+        assert_eq!("1.0", double_to_string(1.0));
+        assert_eq!("0.5", double_to_string(0.5));
+        assert_eq!("-123.25", double_to_string(-123.25));
+        assert_eq!("1.0E7", double_to_string(1e7));
+        assert_eq!("1.5E-4", double_to_string(0.00015));
+        assert_eq!("NaN", double_to_string(f64::NAN));
+        assert_eq!("-Infinity", double_to_string(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn floats_are_formatted_like_java() {
+        assert_eq!("0.1", float_to_string(0.1));
+        assert_eq!("3.0", float_to_string(3.0));
+        assert_eq!("1.0E10", float_to_string(1e10));
+        assert_eq!("Infinity", float_to_string(f32::INFINITY));
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt::Debug;
 
-use rjvm_reader::field_type::{BaseType, FieldType};
+use rjvm_reader::{
+    field_type::{BaseType, FieldType},
+    program_counter::ProgramCounter,
+};
 
 use crate::{
     abstract_object::{AbstractObject, ObjectKind},
@@ -36,7 +39,10 @@ pub enum Value<'a> {
 
     /// Models a null object
     Null,
-    // TODO: the JVM spec says we need to add return address, which are used to implement `finally`
+
+    /// Models a `returnAddress`, pushed by `jsr` and consumed by `ret`. Used by
+    /// class files older than version 50 to implement `finally` blocks.
+    ReturnAddress(ProgramCounter),
 }
 
 impl<'a> Value<'a> {
@@ -90,6 +96,10 @@ impl<'a> Value<'a> {
                                 false
                             }
                         }
+                        FieldType::Object(expected_class_name) => matches!(
+                            expected_class_name.as_str(),
+                            "java/lang/Object" | "java/lang/Cloneable" | "java/io/Serializable"
+                        ),
                         _ => false,
                     }
                 } else {
@@ -101,7 +111,7 @@ impl<'a> Value<'a> {
                                 class_resolver_by_id.find_class_by_id(object.class_id());
                             if let Some(object_class) = value_class {
                                 let expected_class = class_resolver_by_name(&expected_class_name);
-                                expected_class.map_or(false, |expected_class| {
+                                expected_class.is_some_and(|expected_class| {
                                     object_class.is_subclass_of(expected_class)
                                 })
                             } else {
@@ -118,6 +128,9 @@ impl<'a> Value<'a> {
                 FieldType::Object(_) => true,
                 FieldType::Array(_) => true,
             },
+
+            // Return addresses do not have a corresponding Java type
+            Value::ReturnAddress(_) => false,
         }
     }
 }
@@ -168,6 +181,16 @@ pub fn expect_int_at(vec: &[Value], index: usize) -> Result<i32, VmError> {
     }
 }
 
+/// Checks that the element at the given index is a Long and returns it, or an error.
+pub fn expect_long_at(vec: &[Value], index: usize) -> Result<i64, VmError> {
+    let value = vec.get(index);
+    if let Some(Value::Long(long)) = value {
+        Ok(*long)
+    } else {
+        Err(VmError::ValidationException)
+    }
+}
+
 /// Checks that the element at the given index is a Float and returns it, or an error.
 pub fn expect_float_at(vec: &[Value], index: usize) -> Result<f32, VmError> {
     let value = vec.get(index);
@@ -23,6 +23,8 @@ pub enum ValueStackError {
     MaximumCapacityReached,
     #[error("cannot pop from an empty stack")]
     CannotPopFromEmptyStack,
+    #[error("invalid stack index")]
+    InvalidIndex,
 }
 
 impl<'a> ValueStack<'a> {
@@ -74,11 +76,20 @@ impl<'a> ValueStack<'a> {
         self.stack.get(index)
     }
 
-    pub fn iter(&self) -> Iter<Value<'a>> {
+    pub fn set(&mut self, index: usize, value: Value<'a>) -> Result<(), ValueStackError> {
+        let entry = self
+            .stack
+            .get_mut(index)
+            .ok_or(ValueStackError::InvalidIndex)?;
+        *entry = value;
+        Ok(())
+    }
+
+    pub fn iter(&self) -> Iter<'_, Value<'a>> {
         self.stack.iter()
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<Value<'a>> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, Value<'a>> {
         self.stack.iter_mut()
     }
 
@@ -1,27 +1,41 @@
-use std::{collections::HashMap, string::ToString};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    io::{self, Write},
+    string::ToString,
+};
 
 use log::{debug, error, info};
 use typed_arena::Arena;
 
-use rjvm_reader::type_conversion::ToUsizeSafe;
+use rjvm_reader::{
+    field_flags::FieldFlags, method_flags::MethodFlags, type_conversion::ToUsizeSafe, ClassFile,
+    FieldConstantValue,
+};
 
 use crate::{
-    abstract_object::{AbstractObject, ObjectKind},
+    abstract_object::{AbstractObject, ObjectId, ObjectKind},
     array::Array,
     array_entry_type::ArrayEntryType,
     call_frame::MethodCallResult,
     call_stack::CallStack,
     class::{
-        manager::{ClassManager, ResolvedClass},
+        manager::{ClassInitializationState, ClassManager, ResolvedClass},
         path::ClassPathParseError,
         resolver::ClassByIdResolver,
         ClassId, ClassRef,
     },
     class_and_method::ClassAndMethod,
-    exceptions::MethodCallFailed,
+    class_mirror::MirroredType,
+    direct_method_handle::DirectMethodHandle,
+    exceptions::{JavaException, MethodCallFailed},
     gc::ObjectAllocator,
+    invoke_dynamic::{CallSite, CallSiteId},
+    java_objects_creation::{
+        extract_str_from_java_lang_string, new_java_lang_class_object, new_java_lang_string_object,
+    },
     native_methods_impl::array_copy,
     native_methods_registry::NativeMethodsRegistry,
+    object::Object,
     stack_trace_element::StackTraceElement,
     value::Value,
     vm_error::VmError,
@@ -38,6 +52,9 @@ pub struct Vm<'a> {
     /// Allocated call stacks
     call_stacks: Arena<CallStack<'a>>,
 
+    /// Maximum number of frames of the call stacks
+    max_stack_depth: usize,
+
     /// To model static fields, we will create one special instance of each class
     /// and we will store it in this map. This is a bit hacky, and wastes memory
     /// because we will allocate space for non-static fields, but it works easily!
@@ -52,11 +69,68 @@ pub struct Vm<'a> {
     /// array. Since we have no place to store it inside the actual object, we will
     /// keep it in this weird map.
     /// See the implementation of Throwable::getStackTrace() in our rt.jar for
-    /// clarity.
-    throwable_call_stacks: HashMap<i32, Vec<StackTraceElement<'a>>>,
+    /// clarity. The map is keyed by the unique id of the throwable.
+    throwable_call_stacks: HashMap<ObjectId, Vec<StackTraceElement<'a>>>,
+
+    /// The instance of `java.lang.OutOfMemoryError` thrown when the heap is exhausted.
+    /// It is allocated by [Vm::preallocate_out_of_memory_error] when the first class is
+    /// resolved, since once the memory is full we would not be able to create it.
+    out_of_memory_error: Option<AbstractObject<'a>>,
+
+    /// Whether [Vm::preallocate_out_of_memory_error] has already started, so that it
+    /// runs only once even though it resolves classes itself
+    out_of_memory_error_preallocated: bool,
+
+    /// The `invokedynamic` call sites that have already been linked. They are keyed by
+    /// instruction and do not refer to any object, so the gc never needs to prune them.
+    call_sites: HashMap<CallSiteId, CallSite<'a>>,
+
+    /// Number of classes generated at runtime, for instance to implement lambdas
+    generated_classes_count: u32,
+
+    /// Like for [Vm::throwable_call_stacks], we store the target of the instances of
+    /// `java.lang.invoke.MethodHandle` outside of the object, keyed by its unique id.
+    method_handles: HashMap<ObjectId, DirectMethodHandle<'a>>,
+
+    /// The dynamically-computed constants (`CONSTANT_Dynamic`) that have already been
+    /// resolved, by class and constant pool index. They are gc roots.
+    dynamic_constants: HashMap<(ClassId, u16), Value<'a>>,
+
+    /// The errors thrown by the dynamically-computed constants whose resolution failed, by
+    /// class and constant pool index. Later attempts throw the same error. They are gc roots.
+    failed_dynamic_constants: HashMap<(ClassId, u16), AbstractObject<'a>>,
+
+    /// The strings that have been interned, by content. They are gc roots.
+    interned_strings: HashMap<String, AbstractObject<'a>>,
+
+    /// The unique instance of `java.lang.Class` of each type, by descriptor. They are gc roots.
+    class_mirrors: HashMap<String, AbstractObject<'a>>,
+
+    /// The type represented by each instance of `java.lang.Class`. Like for
+    /// [Vm::throwable_call_stacks], we store it outside the object, keyed by its unique id.
+    mirrored_types: HashMap<ObjectId, MirroredType<'a>>,
+
+    /// The string constants (`CONSTANT_String`) that have already been resolved,
+    /// by class and constant pool index. They are gc roots.
+    string_constants: HashMap<(ClassId, u16), AbstractObject<'a>>,
+
+    /// Objects that are referenced only by the vm's native code, which must survive a
+    /// garbage collection, for instance the cause of an exception that we are creating
+    temporary_roots: Vec<AbstractObject<'a>>,
 
-    /// Since we do not have I/O, we have a fake native method that does a println.
-    /// To check in the tests what the java bytecode printed, we store it here.
+    /// How many times the monitor of each object, identified by its unique id, has been
+    /// entered. Since we are single-threaded, all of them are owned by the only thread.
+    entered_monitors: HashMap<ObjectId, usize>,
+
+    /// Where the bytes written to the standard output, i.e. to the file descriptor 1, go
+    stdout: Box<dyn Write>,
+
+    /// Where the bytes written to the standard error, i.e. to the file descriptor 2, go
+    stderr: Box<dyn Write>,
+
+    /// Our test classes have a fake native method that does a println, which does not
+    /// require the initialization of `System.out`. To check in the tests what the java
+    /// bytecode printed, we store it here.
     pub printed: Vec<Value<'a>>,
 }
 
@@ -65,6 +139,16 @@ const DEFAULT_MAX_MB_OF_MEMORY: usize = 100;
 pub const DEFAULT_MAX_MEMORY: usize = 100 * ONE_MEGABYTE;
 pub const DEFAULT_MAX_MEMORY_MB_STR: &str = const_format::formatcp!("{}", DEFAULT_MAX_MB_OF_MEMORY);
 
+pub const DEFAULT_MAX_STACK_DEPTH: usize = 2048;
+pub const DEFAULT_MAX_STACK_DEPTH_STR: &str =
+    const_format::formatcp!("{}", DEFAULT_MAX_STACK_DEPTH);
+
+/// When there is less than this amount of native stack left, a new segment is allocated
+const NATIVE_STACK_RED_ZONE: usize = 256 * 1024;
+
+/// Size of every additional native stack segment
+const NATIVE_STACK_GROWTH: usize = 8 * ONE_MEGABYTE;
+
 impl<'a> ClassByIdResolver<'a> for Vm<'a> {
     fn find_class_by_id(&self, class_id: ClassId) -> Option<ClassRef<'a>> {
         self.class_manager.find_class_by_id(class_id)
@@ -72,21 +156,81 @@ impl<'a> ClassByIdResolver<'a> for Vm<'a> {
 }
 
 impl<'a> Vm<'a> {
-    pub fn new(max_memory: usize) -> Self {
-        info!("Creating new VM with maximum memory {}", max_memory);
+    pub fn new(max_memory: usize, max_stack_depth: usize) -> Self {
+        info!(
+            "Creating new VM with maximum memory {} and maximum stack depth {}",
+            max_memory, max_stack_depth
+        );
         let mut result = Self {
             class_manager: Default::default(),
             object_allocator: ObjectAllocator::with_maximum_memory(max_memory),
             call_stacks: Arena::new(),
+            max_stack_depth,
             statics: Default::default(),
             native_methods_registry: Default::default(),
             throwable_call_stacks: Default::default(),
+            out_of_memory_error: None,
+            out_of_memory_error_preallocated: false,
+            call_sites: Default::default(),
+            generated_classes_count: 0,
+            method_handles: Default::default(),
+            dynamic_constants: Default::default(),
+            failed_dynamic_constants: Default::default(),
+            interned_strings: Default::default(),
+            string_constants: Default::default(),
+            class_mirrors: Default::default(),
+            mirrored_types: Default::default(),
+            temporary_roots: Vec::new(),
+            entered_monitors: Default::default(),
+            stdout: Box::new(io::stdout()),
+            stderr: Box::new(io::stderr()),
             printed: Vec::new(),
         };
         crate::native_methods_impl::register_natives(&mut result.native_methods_registry);
         result
     }
 
+    /// Replaces the sink of the standard output of the java code, by default the one
+    /// of the process
+    pub fn set_stdout(&mut self, stdout: Box<dyn Write>) {
+        self.stdout = stdout;
+    }
+
+    /// Replaces the sink of the standard error of the java code, by default the one
+    /// of the process
+    pub fn set_stderr(&mut self, stderr: Box<dyn Write>) {
+        self.stderr = stderr;
+    }
+
+    /// Writes the given bytes to a file descriptor. Since we cannot open files,
+    /// only the standard output and error are supported.
+    pub(crate) fn write_to_file_descriptor(&mut self, fd: i32, bytes: &[u8]) -> io::Result<()> {
+        let sink = match fd {
+            1 => &mut self.stdout,
+            2 => &mut self.stderr,
+            _ => return Err(io::Error::other("Bad file descriptor")),
+        };
+        sink.write_all(bytes)?;
+        sink.flush()
+    }
+
+    /// Invokes `System.initializeSystemClass`, which sets up `System.in`, `System.out` and
+    /// `System.err`, like the JVM does at startup. Embedders must call it before running
+    /// code that uses them.
+    pub fn initialize_system_class(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        let initialize_system_class = self.resolve_class_method(
+            call_stack,
+            "java/lang/System",
+            "initializeSystemClass",
+            "()V",
+        )?;
+        self.invoke(call_stack, initialize_system_class, None, vec![])?;
+        Ok(())
+    }
+
     pub(crate) fn get_static_instance(&self, class_id: ClassId) -> Option<AbstractObject<'a>> {
         self.statics.get(&class_id).cloned()
     }
@@ -95,43 +239,407 @@ impl<'a> Vm<'a> {
         self.class_manager.append_class_path(class_path)
     }
 
+    /// Returns the class with the given name, loading and linking it if needed.
+    /// The class is not initialized: see [Vm::get_or_initialize_class].
     pub fn get_or_resolve_class(
         &mut self,
         stack: &mut CallStack<'a>,
         class_name: &str,
     ) -> Result<ClassRef<'a>, MethodCallFailed<'a>> {
+        self.preallocate_out_of_memory_error(stack)?;
         let class = self.class_manager.get_or_resolve_class(class_name)?;
-        if let ResolvedClass::NewClass(classes_to_init) = &class {
-            for class_to_init in classes_to_init.to_initialize.iter() {
-                self.init_class(stack, class_to_init)?;
+        self.prepare_resolved_class(stack, class)
+    }
+
+    /// Returns the class with the given name, loading and initializing it if needed
+    pub fn get_or_initialize_class(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class_name: &str,
+    ) -> Result<ClassRef<'a>, MethodCallFailed<'a>> {
+        let class = self.get_or_resolve_class(stack, class_name)?;
+        self.initialize_class(stack, class)?;
+        Ok(class)
+    }
+
+    /// Defines a class generated at runtime, and initializes it
+    pub(crate) fn define_class(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class_file: ClassFile,
+    ) -> Result<ClassRef<'a>, MethodCallFailed<'a>> {
+        let class = self.class_manager.define_class(class_file)?;
+        let class = self.prepare_resolved_class(stack, class)?;
+        self.initialize_class(stack, class)?;
+        Ok(class)
+    }
+
+    /// Prepares the newly loaded classes, by creating the special instances that store
+    /// their static fields, with their default values or the one of their `ConstantValue`
+    fn prepare_resolved_class(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class: ResolvedClass<'a>,
+    ) -> Result<ClassRef<'a>, MethodCallFailed<'a>> {
+        if let ResolvedClass::NewClass(classes_to_prepare) = &class {
+            for class_to_prepare in classes_to_prepare.to_prepare.iter() {
+                debug!("creating static instance of {}", class_to_prepare.name);
+                let static_instance = self.new_object_of_class(class_to_prepare)?;
+                self.statics.insert(class_to_prepare.id, static_instance);
+            }
+            for class_to_prepare in classes_to_prepare.to_prepare.iter() {
+                self.set_constant_values(stack, class_to_prepare)?;
             }
         }
         Ok(class.get_class())
     }
 
-    fn init_class(
+    /// Sets the static fields that have a `ConstantValue` attribute, as specified in
+    /// https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.5
+    fn set_constant_values(
         &mut self,
         stack: &mut CallStack<'a>,
-        class_to_init: &ClassRef<'a>,
+        class: ClassRef<'a>,
     ) -> Result<(), MethodCallFailed<'a>> {
-        debug!("creating static instance of {}", class_to_init.name);
-        let static_instance = self.new_object_of_class(class_to_init);
-        self.statics.insert(class_to_init.id, static_instance);
-        if let Some(clinit_method) = class_to_init.find_method("<clinit>", "()V") {
-            debug!("invoking {}::<clinit>()", class_to_init.name);
-            self.invoke(
+        for (index, field) in class.fields.iter().enumerate() {
+            if !field.flags.contains(FieldFlags::STATIC) {
+                continue;
+            }
+            let value = match &field.constant_value {
+                None => continue,
+                Some(FieldConstantValue::Int(value)) => Value::Int(*value),
+                Some(FieldConstantValue::Float(value)) => Value::Float(*value),
+                Some(FieldConstantValue::Long(value)) => Value::Long(*value),
+                Some(FieldConstantValue::Double(value)) => Value::Double(*value),
+                Some(FieldConstantValue::String(value)) => {
+                    Value::Object(self.intern_string(stack, value)?)
+                }
+            };
+
+            // Interning the string might have moved the static instance
+            let static_instance = self
+                .get_static_instance(class.id)
+                .ok_or(VmError::ValidationException)?;
+            static_instance.set_field(class.first_field_index + index, value);
+        }
+        Ok(())
+    }
+
+    /// Returns the unique instance of `java.lang.String` with the given content,
+    /// creating it if needed
+    pub(crate) fn intern_string(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        content: &str,
+    ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+        if let Some(string) = self.interned_strings.get(content) {
+            return Ok(string.clone());
+        }
+        let string = new_java_lang_string_object(self, stack, content)?;
+        self.interned_strings
+            .insert(content.to_string(), string.clone());
+        Ok(string)
+    }
+
+    /// Implements `String.intern()`: returns the interned string with the same content
+    /// of the given one, or interns the given one if there is none
+    pub(crate) fn intern_string_object(
+        &mut self,
+        string: AbstractObject<'a>,
+    ) -> Result<AbstractObject<'a>, VmError> {
+        let content = extract_str_from_java_lang_string(self, &string)?;
+        Ok(self
+            .interned_strings
+            .entry(content)
+            .or_insert(string)
+            .clone())
+    }
+
+    pub(crate) fn find_string_constant(
+        &self,
+        class_id: ClassId,
+        constant_index: u16,
+    ) -> Option<AbstractObject<'a>> {
+        self.string_constants
+            .get(&(class_id, constant_index))
+            .cloned()
+    }
+
+    pub(crate) fn register_string_constant(
+        &mut self,
+        class_id: ClassId,
+        constant_index: u16,
+        string: AbstractObject<'a>,
+    ) {
+        self.string_constants
+            .insert((class_id, constant_index), string);
+    }
+
+    /// Returns a progressive number, used to give unique names to generated classes
+    pub(crate) fn next_generated_class_number(&mut self) -> u32 {
+        self.generated_classes_count += 1;
+        self.generated_classes_count
+    }
+
+    pub(crate) fn find_call_site(&self, call_site_id: &CallSiteId) -> Option<CallSite<'a>> {
+        self.call_sites.get(call_site_id).cloned()
+    }
+
+    pub(crate) fn register_call_site(&mut self, call_site_id: CallSiteId, call_site: CallSite<'a>) {
+        self.call_sites.insert(call_site_id, call_site);
+    }
+
+    pub(crate) fn find_dynamic_constant(
+        &self,
+        class_id: ClassId,
+        constant_index: u16,
+    ) -> Option<Value<'a>> {
+        self.dynamic_constants
+            .get(&(class_id, constant_index))
+            .cloned()
+    }
+
+    pub(crate) fn register_dynamic_constant(
+        &mut self,
+        class_id: ClassId,
+        constant_index: u16,
+        value: Value<'a>,
+    ) {
+        self.dynamic_constants
+            .insert((class_id, constant_index), value);
+    }
+
+    pub(crate) fn find_failed_dynamic_constant(
+        &self,
+        class_id: ClassId,
+        constant_index: u16,
+    ) -> Option<JavaException<'a>> {
+        self.failed_dynamic_constants
+            .get(&(class_id, constant_index))
+            .cloned()
+            .map(JavaException)
+    }
+
+    pub(crate) fn register_failed_dynamic_constant(
+        &mut self,
+        class_id: ClassId,
+        constant_index: u16,
+        error: &JavaException<'a>,
+    ) {
+        self.failed_dynamic_constants
+            .insert((class_id, constant_index), error.0.clone());
+    }
+
+    /// Initializes the class on its first active use, if it has not been already, following
+    /// https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.5
+    pub fn initialize_class(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class: ClassRef<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        match self.class_manager.initialization_state(class.id) {
+            ClassInitializationState::Initialized | ClassInitializationState::InProgress => {
+                return Ok(())
+            }
+            ClassInitializationState::Erroneous => {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::NoClassDefFoundError(format!(
+                        "Could not initialize class {}",
+                        class.name.replace('/', ".")
+                    )),
+                ))
+            }
+            ClassInitializationState::NotInitialized => {}
+        }
+
+        self.class_manager
+            .set_initialization_state(class.id, ClassInitializationState::InProgress);
+        let result = self.run_initialization(stack, class);
+        let state = match result {
+            Ok(()) => ClassInitializationState::Initialized,
+            Err(_) => ClassInitializationState::Erroneous,
+        };
+        self.class_manager.set_initialization_state(class.id, state);
+        result
+    }
+
+    /// Initializes the superclass and the superinterfaces that declare default methods
+    /// of a class, and then runs its static initializer
+    fn run_initialization(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class: ClassRef<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        if !class.is_interface() {
+            if let Some(superclass) = class.superclass {
+                self.initialize_class(stack, superclass)?;
+            }
+            let mut interfaces = Vec::new();
+            Self::collect_interfaces_with_default_methods(class, &mut interfaces);
+            for interface in interfaces {
+                self.initialize_class(stack, interface)?;
+            }
+        }
+
+        if let Some(clinit_method) = class.find_method("<clinit>", "()V") {
+            debug!("invoking {}::<clinit>()", class.name);
+            let result = self.invoke(
                 stack,
                 ClassAndMethod {
-                    class: class_to_init,
+                    class,
                     method: clinit_method,
                 },
                 None,
                 Vec::new(),
-            )?;
+            );
+            match result {
+                Err(MethodCallFailed::ExceptionThrown(exception)) => {
+                    return Err(self.wrap_in_exception_in_initializer_error(stack, exception))
+                }
+                result => result?,
+            };
         }
         Ok(())
     }
 
+    /// Collects the superinterfaces, direct or indirect, of a class that declare default
+    /// methods. Each interface comes after its own superinterfaces.
+    fn collect_interfaces_with_default_methods(
+        class: ClassRef<'a>,
+        interfaces: &mut Vec<ClassRef<'a>>,
+    ) {
+        for &interface in class.interfaces.iter() {
+            Self::collect_interfaces_with_default_methods(interface, interfaces);
+            if interface.declares_default_methods()
+                && !interfaces.iter().any(|other| other.id == interface.id)
+            {
+                interfaces.push(interface);
+            }
+        }
+    }
+
+    /// Wraps an exception thrown by a static initializer in an `ExceptionInInitializerError`,
+    /// unless it is already an `Error`
+    fn wrap_in_exception_in_initializer_error(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        exception: JavaException<'a>,
+    ) -> MethodCallFailed<'a> {
+        // The exception is referenced only by us, so we need to keep it in a gc root
+        // until we are done allocating
+        let cause_index = self.push_temporary_root(exception.0);
+        let result = self.new_exception_in_initializer_error(stack, cause_index);
+        self.truncate_temporary_roots(cause_index);
+        match result {
+            Ok(exception) => MethodCallFailed::ExceptionThrown(exception),
+            Err(err) => err,
+        }
+    }
+
+    fn new_exception_in_initializer_error(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        cause_index: usize,
+    ) -> Result<JavaException<'a>, MethodCallFailed<'a>> {
+        let error_class = self.get_or_resolve_class(stack, "java/lang/Error")?;
+        let cause = self.get_temporary_root(cause_index)?;
+        if self
+            .get_class_by_id(cause.class_id())?
+            .is_subclass_of(error_class)
+        {
+            return Ok(JavaException(cause));
+        }
+
+        self.new_exception_with_cause(stack, "java/lang/ExceptionInInitializerError", cause_index)
+    }
+
+    /// Creates an exception of the given class, passing to its constructor the cause,
+    /// which must be kept in [Vm::temporary_roots] at the given index
+    pub(crate) fn new_exception_with_cause(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class_name: &str,
+        cause_index: usize,
+    ) -> Result<JavaException<'a>, MethodCallFailed<'a>> {
+        let exception = self.new_object(stack, class_name)?;
+        let exception_index = self.push_temporary_root(exception);
+        let result = self.invoke_exception_constructor(
+            stack,
+            class_name,
+            "(Ljava/lang/Throwable;)V",
+            exception_index,
+            cause_index,
+        );
+        self.truncate_temporary_roots(exception_index);
+        result
+    }
+
+    /// Creates an exception of the given class, with the given message
+    pub(crate) fn new_exception_with_message(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class_name: &str,
+        message: &str,
+    ) -> Result<JavaException<'a>, MethodCallFailed<'a>> {
+        let exception = self.new_object(stack, class_name)?;
+        let exception_index = self.push_temporary_root(exception);
+        let result = new_java_lang_string_object(self, stack, message).and_then(|message| {
+            let message_index = self.push_temporary_root(message);
+            self.invoke_exception_constructor(
+                stack,
+                class_name,
+                "(Ljava/lang/String;)V",
+                exception_index,
+                message_index,
+            )
+        });
+        self.truncate_temporary_roots(exception_index);
+        result
+    }
+
+    /// Invokes the constructor of the exception, both kept in [Vm::temporary_roots],
+    /// and the argument
+    fn invoke_exception_constructor(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class_name: &str,
+        constructor_descriptor: &str,
+        exception_index: usize,
+        argument_index: usize,
+    ) -> Result<JavaException<'a>, MethodCallFailed<'a>> {
+        let constructor =
+            self.resolve_class_method(stack, class_name, "<init>", constructor_descriptor)?;
+        let exception = self.get_temporary_root(exception_index)?;
+        let argument = self.get_temporary_root(argument_index)?;
+        self.invoke(
+            stack,
+            constructor,
+            Some(exception),
+            vec![Value::Object(argument)],
+        )?;
+        Ok(JavaException(self.get_temporary_root(exception_index)?))
+    }
+
+    /// Keeps the given object alive, and updated, across garbage collections, until
+    /// [Vm::truncate_temporary_roots] is invoked. Returns its index, to be used with
+    /// [Vm::get_temporary_root].
+    pub(crate) fn push_temporary_root(&mut self, object: AbstractObject<'a>) -> usize {
+        self.temporary_roots.push(object);
+        self.temporary_roots.len() - 1
+    }
+
+    /// Removes the temporary roots starting from the given index
+    pub(crate) fn truncate_temporary_roots(&mut self, index: usize) {
+        self.temporary_roots.truncate(index);
+    }
+
+    pub(crate) fn get_temporary_root(&self, index: usize) -> Result<AbstractObject<'a>, VmError> {
+        self.temporary_roots
+            .get(index)
+            .cloned()
+            .ok_or(VmError::ValidationException)
+    }
+
     pub fn get_class_by_id(&self, class_id: ClassId) -> Result<ClassRef<'a>, VmError> {
         self.find_class_by_id(class_id)
             .ok_or(VmError::ValidationException)
@@ -141,6 +649,7 @@ impl<'a> Vm<'a> {
         self.class_manager.find_class_by_name(class_name)
     }
 
+    /// Resolves a method that the vm is going to invoke. Thus, its class gets initialized.
     pub fn resolve_class_method(
         &mut self,
         call_stack: &mut CallStack<'a>,
@@ -148,7 +657,7 @@ impl<'a> Vm<'a> {
         method_name: &str,
         method_type_descriptor: &str,
     ) -> Result<ClassAndMethod<'a>, MethodCallFailed<'a>> {
-        self.get_or_resolve_class(call_stack, class_name)
+        self.get_or_initialize_class(call_stack, class_name)
             .and_then(|class| {
                 class
                     .find_method(method_name, method_type_descriptor)
@@ -175,14 +684,98 @@ impl<'a> Vm<'a> {
         }
 
         // Generic bytecode method
+        let class = class_and_method.class;
+        let is_synchronized = class_and_method
+            .method
+            .flags
+            .contains(MethodFlags::SYNCHRONIZED);
+        let receiver = object.clone();
         let mut frame = call_stack.add_frame(class_and_method, object, args)?;
-        let result = frame.as_mut().execute(self, call_stack);
+
+        let monitor_index = if is_synchronized {
+            match self.enter_method_monitor(call_stack, class, receiver) {
+                Ok(monitor_index) => Some(monitor_index),
+                Err(err) => {
+                    call_stack
+                        .pop_frame()
+                        .expect("should be able to pop the frame we just pushed");
+                    return Err(err);
+                }
+            }
+        } else {
+            None
+        };
+
+        // Every java method call recurses on the native stack. To make sure that we hit the
+        // maximum depth of the call stack before overflowing the native stack, we grow the
+        // latter on the heap when it is about to run out.
+        let result = stacker::maybe_grow(NATIVE_STACK_RED_ZONE, NATIVE_STACK_GROWTH, || {
+            frame.as_mut().execute(self, call_stack)
+        });
         call_stack
             .pop_frame()
             .expect("should be able to pop the frame we just pushed");
+
+        if let Some(monitor_index) = monitor_index {
+            let exit_result = self.exit_method_monitor(monitor_index);
+            if result.is_ok() {
+                exit_result?;
+            }
+        }
         result
     }
 
+    /// Synchronized methods hold the monitor of their receiver, or of the class for static
+    /// methods, while they execute. The monitor is kept in [Vm::temporary_roots],
+    /// and this function returns its index.
+    fn enter_method_monitor(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        class: ClassRef<'a>,
+        receiver: Option<AbstractObject<'a>>,
+    ) -> Result<usize, MethodCallFailed<'a>> {
+        // The arguments are already in the frame, thus they are safe if creating the
+        // class mirror triggers a garbage collection
+        let monitor = match receiver {
+            Some(receiver) => receiver,
+            None => self.get_class_mirror(call_stack, MirroredType::Class(class))?,
+        };
+        self.enter_monitor(&monitor);
+        Ok(self.push_temporary_root(monitor))
+    }
+
+    fn exit_method_monitor(&mut self, monitor_index: usize) -> Result<(), VmError> {
+        let monitor = self.get_temporary_root(monitor_index)?;
+        self.truncate_temporary_roots(monitor_index);
+        self.exit_monitor(&monitor)
+    }
+
+    /// Enters the monitor of the given object, like `monitorenter`
+    pub(crate) fn enter_monitor(&mut self, object: &AbstractObject<'a>) {
+        *self.entered_monitors.entry(object.id()).or_default() += 1;
+    }
+
+    /// Exits the monitor of the given object, like `monitorexit`.
+    /// Fails if the monitor is not owned.
+    pub(crate) fn exit_monitor(&mut self, object: &AbstractObject<'a>) -> Result<(), VmError> {
+        match self.entered_monitors.entry(object.id()) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() -= 1;
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+                Ok(())
+            }
+            Entry::Vacant(_) => Err(VmError::IllegalMonitorStateException),
+        }
+    }
+
+    /// Whether the monitor of the given object is owned, i.e. it has been entered
+    /// more times than it has been exited
+    pub(crate) fn owns_monitor(&self, object: &AbstractObject<'a>) -> bool {
+        self.entered_monitors.contains_key(&object.id())
+    }
+
     fn invoke_native(
         &mut self,
         call_stack: &mut CallStack<'a>,
@@ -213,83 +806,220 @@ impl<'a> Vm<'a> {
     /// Allocates a new call stack. We need to store it to be able to refer it later, for
     /// extracting the gc roots.
     pub fn allocate_call_stack(&mut self) -> &'a mut CallStack<'a> {
-        let stack = self.call_stacks.alloc(CallStack::new());
+        let stack = self.call_stacks.alloc(CallStack::new(self.max_stack_depth));
         unsafe {
             let stack_ptr: *mut CallStack<'a> = stack;
             &mut *stack_ptr
         }
     }
 
+    /// Allocates a new instance of the given class, initializing it if needed,
+    /// like the `new` instruction
     pub fn new_object(
         &mut self,
         call_stack: &mut CallStack<'a>,
         class_name: &str,
     ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
-        let class = self.get_or_resolve_class(call_stack, class_name)?;
-        Ok(self.new_object_of_class(class))
+        let class = self.get_or_initialize_class(call_stack, class_name)?;
+        Ok(self.new_object_of_class(class)?)
     }
 
-    pub fn new_object_of_class(&mut self, class: ClassRef<'a>) -> AbstractObject<'a> {
+    /// Allocates a new instance of the given class, running the garbage collection if the
+    /// memory is full. Fails with [VmError::OutOfMemoryError] if there is still not enough space.
+    pub fn new_object_of_class(
+        &mut self,
+        class: ClassRef<'a>,
+    ) -> Result<AbstractObject<'a>, VmError> {
         debug!("allocating new instance of {}", class.name);
         match self.object_allocator.allocate_object(class) {
-            Some(object) => object,
+            Some(object) => Ok(object),
             None => {
-                self.run_garbage_collection()
-                    .expect("could run garbage collection");
+                self.run_garbage_collection()?;
                 self.object_allocator
                     .allocate_object(class)
-                    .expect("cannot allocate object even after full garbage collection!")
+                    .ok_or(VmError::OutOfMemoryError)
             }
         }
     }
 
+    /// Allocates a new array with the given type of entries, resolving its class if needed
     pub fn new_array(
         &mut self,
+        call_stack: &mut CallStack<'a>,
         elements_type: ArrayEntryType,
         length: usize,
-    ) -> AbstractObject<'a> {
+    ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+        let array_type = elements_type
+            .clone()
+            .array_of()
+            .into_field_type(self)
+            .ok_or(VmError::ValidationException)?;
+        let array_class = self.get_or_resolve_class(call_stack, &array_type.to_descriptor())?;
+        Ok(self.new_array_of_class(array_class, elements_type, length)?)
+    }
+
+    /// Allocates a new array, running the garbage collection if the memory is full.
+    /// Fails with [VmError::OutOfMemoryError] if there is still not enough space.
+    pub fn new_array_of_class(
+        &mut self,
+        array_class: ClassRef<'a>,
+        elements_type: ArrayEntryType,
+        length: usize,
+    ) -> Result<AbstractObject<'a>, VmError> {
         match self
             .object_allocator
-            .allocate_array(elements_type.clone(), length)
+            .allocate_array(array_class, elements_type.clone(), length)
         {
-            Some(array) => array,
+            Some(array) => Ok(array),
             None => {
-                self.run_garbage_collection()
-                    .expect("could run garbage collection");
+                self.run_garbage_collection()?;
                 self.object_allocator
-                    .allocate_array(elements_type, length)
-                    .expect("cannot allocate array even after full garbage collection!")
+                    .allocate_array(array_class, elements_type, length)
+                    .ok_or(VmError::OutOfMemoryError)
             }
         }
     }
 
+    /// Allocates the `java.lang.OutOfMemoryError` thrown when the heap is exhausted, since
+    /// once the memory is full we would not be able to create it. It is invoked whenever a
+    /// class is resolved and does nothing after the first time. Since every allocation
+    /// needs a resolved class, the first time happens before anything else is allocated,
+    /// so a garbage collection cannot move any object held by the caller.
+    fn preallocate_out_of_memory_error(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        if self.out_of_memory_error_preallocated {
+            return Ok(());
+        }
+        // Creating the error resolves classes, which must not get back here
+        self.out_of_memory_error_preallocated = true;
+
+        // We store the error right away, since that makes it a gc root
+        let error = self.new_object(call_stack, "java/lang/OutOfMemoryError")?;
+        self.out_of_memory_error = Some(error);
+
+        let message = new_java_lang_string_object(self, call_stack, "Java heap space")?;
+        let constructor = self.resolve_class_method(
+            call_stack,
+            "java/lang/OutOfMemoryError",
+            "<init>",
+            "(Ljava/lang/String;)V",
+        )?;
+        let error = self.out_of_memory_error.clone();
+        self.invoke(call_stack, constructor, error, vec![Value::Object(message)])?;
+        Ok(())
+    }
+
+    /// Returns the preallocated `java.lang.OutOfMemoryError`, with the stack trace updated
+    /// to the current one. Throwing it does not require allocating any memory on the heap.
+    pub(crate) fn out_of_memory_error(
+        &mut self,
+        call_stack: &CallStack<'a>,
+    ) -> Option<JavaException<'a>> {
+        let error = self.out_of_memory_error.clone()?;
+        self.associate_stack_trace_with_throwable(
+            error.clone(),
+            call_stack.get_stack_trace_elements(),
+        );
+        Some(JavaException(error))
+    }
+
     pub fn clone_array(&mut self, value: Value<'a>) -> Result<Value<'a>, VmError> {
-        match &value {
+        match value {
             Value::Object(array) if array.kind() == ObjectKind::Array => {
+                let array_class = self.get_class_by_id(array.class_id())?;
+                let elements_type = array.elements_type();
+                let length = array.len();
+
+                // Allocating the copy can move the original array
+                let array_index = self.push_temporary_root(array);
                 let new_array =
-                    self.new_array(array.elements_type(), array.len().into_usize_safe());
-                array_copy(array, 0, &new_array, 0, array.len().into_usize_safe())?;
+                    self.new_array_of_class(array_class, elements_type, length.into_usize_safe());
+                let array = self.get_temporary_root(array_index)?;
+                self.truncate_temporary_roots(array_index);
+
+                let new_array = new_array?;
+                array_copy(self, &array, 0, &new_array, 0, length as i32)?;
                 Ok(Value::Object(new_array))
             }
             _ => Err(VmError::ValidationException),
         }
     }
 
+    /// Creates a shallow copy of the given object, which must not be an array
+    pub fn clone_object(
+        &mut self,
+        object: AbstractObject<'a>,
+    ) -> Result<AbstractObject<'a>, VmError> {
+        let class = self.get_class_by_id(object.class_id())?;
+
+        // Allocating the copy can move the original object
+        let object_index = self.push_temporary_root(object);
+        let new_object = self.new_object_of_class(class);
+        let object = self.get_temporary_root(object_index)?;
+        self.truncate_temporary_roots(object_index);
+
+        let new_object = new_object?;
+        new_object.copy_fields_from(&object, class);
+        Ok(new_object)
+    }
+
     pub(crate) fn associate_stack_trace_with_throwable(
         &mut self,
         throwable: AbstractObject<'a>,
         call_stack: Vec<StackTraceElement<'a>>,
     ) {
         self.throwable_call_stacks
-            .insert(throwable.identity_hash_code(), call_stack);
+            .insert(throwable.id(), call_stack);
+    }
+
+    pub(crate) fn associate_method_handle(
+        &mut self,
+        method_handle_object: AbstractObject<'a>,
+        method_handle: DirectMethodHandle<'a>,
+    ) {
+        self.method_handles
+            .insert(method_handle_object.id(), method_handle);
+    }
+
+    pub(crate) fn get_method_handle(
+        &self,
+        method_handle_object: &AbstractObject<'a>,
+    ) -> Option<&DirectMethodHandle<'a>> {
+        self.method_handles.get(&method_handle_object.id())
+    }
+
+    /// Returns the unique instance of `java.lang.Class` representing the given type,
+    /// creating it if needed
+    pub fn get_class_mirror(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        mirrored_type: MirroredType<'a>,
+    ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+        let descriptor = mirrored_type.descriptor();
+        if let Some(class_object) = self.class_mirrors.get(&descriptor) {
+            return Ok(class_object.clone());
+        }
+        let class_object = new_java_lang_class_object(self, call_stack)?;
+        self.mirrored_types.insert(class_object.id(), mirrored_type);
+        self.class_mirrors.insert(descriptor, class_object.clone());
+        Ok(class_object)
+    }
+
+    /// Returns the type represented by the given instance of `java.lang.Class`
+    pub fn get_mirrored_type(
+        &self,
+        class_object: &AbstractObject<'a>,
+    ) -> Option<&MirroredType<'a>> {
+        self.mirrored_types.get(&class_object.id())
     }
 
     pub(crate) fn get_stack_trace_associated_with_throwable(
         &self,
         throwable: AbstractObject<'a>,
     ) -> Option<&Vec<StackTraceElement<'a>>> {
-        self.throwable_call_stacks
-            .get(&throwable.identity_hash_code())
+        self.throwable_call_stacks.get(&throwable.id())
     }
 
     pub fn debug_stats(&self) {
@@ -306,12 +1036,58 @@ impl<'a> Vm<'a> {
                 .iter_mut()
                 .map(|(_, object)| object as *mut AbstractObject<'a>),
         );
+        roots.extend(
+            self.out_of_memory_error
+                .iter_mut()
+                .map(|object| object as *mut AbstractObject<'a>),
+        );
+        roots.extend(
+            self.temporary_roots
+                .iter_mut()
+                .map(|object| object as *mut AbstractObject<'a>),
+        );
+        roots.extend(
+            self.interned_strings
+                .values_mut()
+                .map(|object| object as *mut AbstractObject<'a>),
+        );
+        roots.extend(
+            self.class_mirrors
+                .values_mut()
+                .map(|object| object as *mut AbstractObject<'a>),
+        );
+        roots.extend(
+            self.string_constants
+                .values_mut()
+                .map(|object| object as *mut AbstractObject<'a>),
+        );
+        roots.extend(
+            self.dynamic_constants
+                .values_mut()
+                .filter_map(|value| match value {
+                    Value::Object(object) => Some(object as *mut AbstractObject<'a>),
+                    _ => None,
+                }),
+        );
+        roots.extend(
+            self.failed_dynamic_constants
+                .values_mut()
+                .map(|object| object as *mut AbstractObject<'a>),
+        );
         roots.extend(self.call_stacks.iter_mut().flat_map(|s| s.gc_roots()));
 
-        unsafe {
+        let survivors = unsafe {
             self.object_allocator
-                .do_garbage_collection(roots, &self.class_manager)?;
-        }
+                .do_garbage_collection(roots, &self.class_manager)?
+        };
+
+        // The objects that did not survive cannot be used anymore, so we can forget
+        // what we stored about them outside of the heap
+        self.throwable_call_stacks
+            .retain(|id, _| survivors.contains(id));
+        self.method_handles.retain(|id, _| survivors.contains(id));
+        self.mirrored_types.retain(|id, _| survivors.contains(id));
+        self.entered_monitors.retain(|id, _| survivors.contains(id));
         Ok(())
     }
 }
@@ -10,9 +10,9 @@ pub enum VmError {
     #[error("unexpected error loading class: {0}")]
     ClassLoadingError(String),
 
-    /// TODO: this should become throwing a real `java.lang.NullPointerException`
+    /// Thrown to the java code as a `java.lang.NullPointerException`, with the given message
     #[error("null pointer exception")]
-    NullPointerException,
+    NullPointerException(Option<String>),
 
     /// TODO: this should become throwing a real `java.lang.ClassNotFoundException`
     #[error("class not found: {0}")]
@@ -30,20 +30,113 @@ pub enum VmError {
     #[error("validation exception - invalid class file")]
     ValidationException,
 
-    /// TODO: this should become throwing a real `java.lang.ArithmeticException`
-    #[error("arithmetic exception")]
+    /// Thrown to the java code as a `java.lang.ArithmeticException`. Only integer divisions
+    /// can fail, thus the message is always the same.
+    #[error("arithmetic exception: / by zero")]
     ArithmeticException,
 
     #[error("not yet implemented")]
     NotImplemented,
 
-    /// TODO: this should become throwing a real `java.lang.ArrayIndexOutOfBoundsException`
-    #[error("array index out of bounds")]
-    ArrayIndexOutOfBoundsException,
+    /// Thrown to the java code as a `java.lang.ArrayIndexOutOfBoundsException`, with the given message
+    #[error("array index out of bounds: {0}")]
+    ArrayIndexOutOfBoundsException(String),
 
-    /// TODO: this should become throwing a real `java.lang.ClassCastException`
-    #[error("class cast exception")]
-    ClassCastException,
+    /// Thrown to the java code as a `java.lang.ClassCastException`, with the given message
+    #[error("class cast exception: {0}")]
+    ClassCastException(String),
+
+    /// Thrown to the java code as a `java.lang.NegativeArraySizeException`
+    #[error("negative array size: {0}")]
+    NegativeArraySizeException(i32),
+
+    /// Thrown to the java code as a `java.lang.ArrayStoreException`, with the given message
+    #[error("array store exception: {0}")]
+    ArrayStoreException(String),
+
+    /// Thrown to the java code as a `java.lang.StackOverflowError`, when the call stack
+    /// grows beyond its maximum depth
+    #[error("stack overflow")]
+    StackOverflowError,
+
+    /// Thrown to the java code as a `java.lang.OutOfMemoryError`, when the heap is full
+    /// even after a garbage collection. Since creating a new exception would require
+    /// memory, the vm throws an instance preallocated at startup.
+    #[error("out of memory")]
+    OutOfMemoryError,
+
+    /// Thrown to the java code as a `java.lang.invoke.WrongMethodTypeException`, when
+    /// a method handle is invoked with the wrong arguments
+    #[error("wrong method type: {0}")]
+    WrongMethodTypeException(String),
+
+    /// Thrown to the java code as a `java.lang.BootstrapMethodError`, when a bootstrap
+    /// method cannot be invoked
+    #[error("bootstrap method error: {0}")]
+    BootstrapMethodError(String),
+
+    /// Thrown to the java code as a `java.lang.NoClassDefFoundError`, for instance when
+    /// using a class whose static initializer has failed
+    #[error("no class def found: {0}")]
+    NoClassDefFoundError(String),
+
+    /// Thrown to the java code as a `java.lang.IllegalMonitorStateException`, when
+    /// releasing or waiting on a monitor that the thread does not own
+    #[error("illegal monitor state")]
+    IllegalMonitorStateException,
+}
+
+impl VmError {
+    /// Some errors model runtime faults, that the JVM reports by throwing an exception
+    /// to the java code. For them, returns the class of the exception and its message.
+    pub fn to_java_exception(&self) -> Option<(&'static str, Option<String>)> {
+        match self {
+            VmError::NullPointerException(message) => {
+                Some(("java/lang/NullPointerException", message.clone()))
+            }
+            VmError::ArithmeticException => Some((
+                "java/lang/ArithmeticException",
+                Some("/ by zero".to_string()),
+            )),
+            VmError::ArrayIndexOutOfBoundsException(message) => Some((
+                "java/lang/ArrayIndexOutOfBoundsException",
+                Some(message.clone()),
+            )),
+            VmError::ClassCastException(message) => {
+                Some(("java/lang/ClassCastException", Some(message.clone())))
+            }
+            VmError::NegativeArraySizeException(size) => Some((
+                "java/lang/NegativeArraySizeException",
+                Some(size.to_string()),
+            )),
+            VmError::ArrayStoreException(message) => {
+                Some(("java/lang/ArrayStoreException", Some(message.clone())))
+            }
+            VmError::StackOverflowError => Some(("java/lang/StackOverflowError", None)),
+            VmError::WrongMethodTypeException(message) => Some((
+                "java/lang/invoke/WrongMethodTypeException",
+                Some(message.clone()),
+            )),
+            VmError::BootstrapMethodError(message) => {
+                Some(("java/lang/BootstrapMethodError", Some(message.clone())))
+            }
+            VmError::NoClassDefFoundError(message) => {
+                Some(("java/lang/NoClassDefFoundError", Some(message.clone())))
+            }
+            VmError::IllegalMonitorStateException => Some((
+                "java/lang/IllegalMonitorStateException",
+                Some("current thread is not owner".to_string()),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Creates an [VmError::ArrayIndexOutOfBoundsException] with the same message as HotSpot
+    pub fn array_index_out_of_bounds(index: i32, length: u32) -> Self {
+        VmError::ArrayIndexOutOfBoundsException(format!(
+            "Index {index} out of bounds for length {length}"
+        ))
+    }
 }
 
 // TODO: remove once we implement exceptions
@@ -1,14 +1,20 @@
+use std::{cell::RefCell, io::Write, rc::Rc};
+
 use rjvm_vm::{
     exceptions::MethodCallFailed,
     java_objects_creation::extract_str_from_java_lang_string,
     value::{expect_concrete_object_at, Value},
-    vm::{Vm, DEFAULT_MAX_MEMORY},
+    vm::{Vm, DEFAULT_MAX_MEMORY, DEFAULT_MAX_STACK_DEPTH, ONE_MEGABYTE},
 };
 
 // This file tests the real classes in ../resources/rjvm
 
 fn create_base_vm(max_memory: usize) -> Vm<'static> {
-    let mut vm = Vm::new(max_memory);
+    create_vm(max_memory, DEFAULT_MAX_STACK_DEPTH)
+}
+
+fn create_vm(max_memory: usize, max_stack_depth: usize) -> Vm<'static> {
+    let mut vm = Vm::new(max_memory, max_stack_depth);
 
     let src_dir = env!("CARGO_MANIFEST_DIR");
     vm.append_class_path(&format!("{src_dir}/rt.jar:{src_dir}/tests/resources",))
@@ -40,6 +46,40 @@ fn extract_printed_string(vm: &Vm, index: usize) -> String {
     extract_str_from_java_lang_string(vm, &string).expect("should have a valid string")
 }
 
+/// Renders all the values printed by the java code as strings, to compare them easily
+fn printed_as_strings(vm: &Vm) -> Vec<String> {
+    (0..vm.printed.len())
+        .map(|index| match &vm.printed[index] {
+            Value::Int(value) => value.to_string(),
+            Value::Long(value) => value.to_string(),
+            Value::Float(value) => value.to_string(),
+            Value::Double(value) => value.to_string(),
+            Value::Null => "null".to_string(),
+            _ => extract_printed_string(vm, index),
+        })
+        .collect()
+}
+
+/// A sink for the output of the vm, that can be read while the vm owns it
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).expect("should have written valid utf-8")
+    }
+}
+
 #[test_log::test]
 fn simple_main() {
     let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
@@ -129,6 +169,9 @@ fn numeric_types() {
             Value::Long(1),
             Value::Long(((-1i64) as u64 >> 2) as i64),
             Value::Long(8),
+            Value::Int(-5),
+            Value::Int(-128),
+            Value::Int(100),
         ],
         vm.printed
     );
@@ -370,9 +413,747 @@ fn gabarge_collector() {
     assert_eq!(Ok(None), main_result);
 }
 
+#[test_log::test]
+fn stack_traces_after_garbage_collection() {
+    let mut vm = create_base_vm(ONE_MEGABYTE);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StackTracesAfterGarbageCollection",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(vec![Value::Int(3), Value::Int(2)], vm.printed);
+}
+
 #[test_log::test]
 fn generic() {
     let mut vm = create_base_vm(10_000_000);
     let main_result = invoke(&mut vm, "rjvm/Generic", "main", "([Ljava/lang/String;)V");
     assert_eq!(Ok(None), main_result);
 }
+
+#[test_log::test]
+fn switches() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/Switches", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            // Dense
+            Value::Int(-1),
+            Value::Int(10),
+            Value::Int(11),
+            Value::Int(12),
+            Value::Int(13),
+            Value::Int(-1),
+            // Sparse
+            Value::Int(1),
+            Value::Int(2),
+            Value::Int(3),
+            Value::Int(4),
+            // Negative keys, with fallthrough
+            Value::Int(120),
+            Value::Int(20),
+            Value::Int(3),
+            Value::Int(0),
+            // Strings
+            Value::Int(1),
+            Value::Int(2),
+            Value::Int(0),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn wide_locals() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/WideLocals", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(1000),
+            Value::Int(-29000),
+            Value::Int(3767),
+            Value::Int(2256),
+            Value::Long(2257),
+            Value::Float(2.5),
+            Value::Double(3.5),
+            Value::Int(1),
+            Value::Int(300),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn subroutines() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/Subroutines",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(21),
+            Value::Int(42),
+            Value::Int(1),
+            Value::Int(2),
+            Value::Int(5),
+            Value::Int(10),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn wide_jumps() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/WideJumps", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![Value::Int(55), Value::Int(3), Value::Int(6)],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn multi_dimensional_arrays() {
+    let mut vm = create_base_vm(1_000_000);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/MultiDimensionalArrays",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            // Matrices
+            Value::Int(3),
+            Value::Int(4),
+            Value::Int(23),
+            Value::Long(42),
+            Value::Int(4),
+            // Partially specified dimensions
+            Value::Int(1),
+            Value::Int(3),
+            Value::Int(5),
+            Value::Int(1),
+            // Objects
+            Value::Int(7),
+            Value::Int(1),
+            Value::Int(1),
+            // instanceof and checkcast
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(0),
+            Value::Int(2),
+            Value::Int(2),
+            // Cloning
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(3),
+            // Garbage collection
+            Value::Int(19900),
+            Value::Int(99),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn runtime_exceptions() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/RuntimeExceptions",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    let printed = printed_as_strings(&vm);
+    assert_eq!(
+        vec![
+            // Arithmetic
+            "/ by zero",
+            "/ by zero",
+            "10",
+            // Arrays
+            "Index 3 out of bounds for length 3",
+            "Index -1 out of bounds for length 3",
+            "-2",
+            "rjvm.RuntimeExceptions$Point",
+            // Casts
+            "class java.lang.String cannot be cast to class java.lang.Integer",
+            "1",
+            // Null pointers
+            "Cannot read field \"x\"",
+            "Cannot assign field \"x\"",
+            "Cannot invoke \"rjvm.RuntimeExceptions$Point.move()\"",
+            "Cannot invoke \"String.concat(String)\"",
+            "Cannot read the array length",
+            "Cannot load from int array",
+            "Cannot throw exception",
+            "Cannot enter synchronized block",
+            // Array copies
+            "null",
+            "arraycopy: type mismatch: can not copy int[] into long[]",
+            "arraycopy: last source index 4 out of bounds for int[3]",
+            "arraycopy: element type mismatch: can not cast one of the elements of \
+             java.lang.Object[] to the type of the destination array, java.lang.String",
+            "a",
+            // Stack traces
+            "divide",
+            "48",
+            "stackTraces",
+            "171",
+            "main",
+            "19",
+        ],
+        printed
+    );
+}
+
+#[test_log::test]
+fn stack_overflow() {
+    let mut vm = create_vm(DEFAULT_MAX_MEMORY, 1500);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StackOverflow",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            // One frame is used by main
+            Value::Int(1499),
+            // The stack trace is truncated
+            Value::Int(1024),
+            Value::Int(1),
+            Value::Int(1499),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn out_of_memory() {
+    let mut vm = create_base_vm(10 * ONE_MEGABYTE);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/OutOfMemory",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(3),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn array_classes() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ArrayClasses",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(Value::Int(3), vm.printed[0]);
+    assert_eq!(Value::Int(0), vm.printed[1]);
+    assert_eq!(Value::Int(1), vm.printed[2]);
+    assert_eq!(Value::Int(0), vm.printed[3]);
+    assert_eq!(Value::Int(1), vm.printed[4]);
+    assert_eq!(Value::Int(1), vm.printed[5]);
+    assert_eq!(Value::Int(1), vm.printed[6]);
+    assert_eq!("a", extract_printed_string(&vm, 7));
+    assert_eq!(Value::Int(2), vm.printed[8]);
+    assert_eq!(Value::Int(1), vm.printed[9]);
+    assert_eq!(Value::Int(1), vm.printed[10]);
+    assert_eq!(Value::Int(1), vm.printed[11]);
+    assert_eq!(Value::Int(0), vm.printed[12]);
+    assert_eq!(Value::Int(0), vm.printed[13]);
+
+    let int_array_class = vm
+        .find_class_by_name("[I")
+        .expect("should have created the class of int[]");
+    assert_eq!(
+        Some("java/lang/Object"),
+        int_array_class
+            .superclass
+            .map(|superclass| superclass.name.as_str())
+    );
+    assert_eq!(
+        vec!["java/lang/Cloneable", "java/io/Serializable"],
+        int_array_class
+            .interfaces
+            .iter()
+            .map(|interface| interface.name.as_str())
+            .collect::<Vec<&str>>()
+    );
+}
+
+#[test_log::test]
+fn lambdas() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/Lambdas", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(2),
+            Value::Int(42),
+            Value::Int(12),
+            Value::Int(15),
+            Value::Int(15)
+        ],
+        vm.printed[0..5]
+    );
+    assert_eq!(Value::Int(42), vm.printed[5]);
+    assert_eq!("hello, world", extract_printed_string(&vm, 6));
+    assert_eq!(
+        vec![Value::Int(3), Value::Int(3), Value::Int(4)],
+        vm.printed[7..10]
+    );
+    assert_eq!(
+        vec![Value::Int(81), Value::Int(4), Value::Int(100)],
+        vm.printed[10..13]
+    );
+    assert_eq!(Value::Int(3), vm.printed[13]);
+    assert_eq!("supplied", extract_printed_string(&vm, 14));
+    assert_eq!("supplied", extract_printed_string(&vm, 15));
+    assert_eq!(
+        vec![
+            Value::Int(15),
+            Value::Int(1),
+            Value::Int(20),
+            Value::Int(1),
+            Value::Int(0)
+        ],
+        vm.printed[16..21]
+    );
+    assert_eq!(Value::Int(15), vm.printed[21]);
+
+    // Every call site is linked only once, even if executed many times, and every call site
+    // has its own class
+    assert_eq!(Value::Int(1), vm.printed[22]);
+    assert_eq!(Value::Int(1), vm.printed[23]);
+}
+
+#[test_log::test]
+fn string_concatenation() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StringConcatenation",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(5, vm.printed.len());
+    assert_eq!("true c -1 2 3 4 1.5 0.25", extract_printed_string(&vm, 0));
+    assert_eq!("1.0E10|1.0E-5|12345.0", extract_printed_string(&vm, 1));
+    assert_eq!("point: (1, 2), null: null", extract_printed_string(&vm, 2));
+    assert_eq!("\u{1}-\u{1}-\u{2}-\u{2}", extract_printed_string(&vm, 3));
+    assert_eq!("ab", extract_printed_string(&vm, 4));
+}
+
+#[test_log::test]
+fn method_handles() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/MethodHandles",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(3),
+            Value::Int(10),
+            Value::Int(15),
+            Value::Int(15)
+        ],
+        vm.printed[0..4]
+    );
+    assert_eq!("counter", extract_printed_string(&vm, 4));
+    assert_eq!(Value::Int(16), vm.printed[5]);
+    assert_eq!("method handles", extract_printed_string(&vm, 6));
+    assert_eq!(
+        vec![
+            Value::Int(7),
+            Value::Int(8),
+            Value::Int(100),
+            Value::Int(42),
+            Value::Int(2),
+            Value::Int(1)
+        ],
+        vm.printed[7..13]
+    );
+    assert_eq!(
+        "expected (int,int)int but found (int,int)long",
+        extract_printed_string(&vm, 13)
+    );
+    assert_eq!(
+        vec![Value::Int(2), Value::Int(2), Value::Int(1)],
+        vm.printed[14..17]
+    );
+}
+
+#[test_log::test]
+fn dynamic_constants() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/DynamicConstants",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(Value::Int(42), vm.printed[0]);
+    assert_eq!("greeting: hello, world", extract_printed_string(&vm, 1));
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(1), Value::Int(42), Value::Int(84)],
+        vm.printed[2..6]
+    );
+    assert_eq!(
+        "bootstrap method initialization exception",
+        extract_printed_string(&vm, 6)
+    );
+    assert_eq!("cannot compute failing", extract_printed_string(&vm, 7));
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(1), Value::Int(1)],
+        vm.printed[8..11]
+    );
+}
+
+#[test_log::test]
+fn class_initialization() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ClassInitialization",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    let printed = printed_as_strings(&vm);
+    assert_eq!(
+        vec![
+            "main",
+            "0",
+            "1",
+            "Base",
+            "Derived",
+            "Helper",
+            "WithDefault",
+            "Implementation",
+            "First start",
+            "Second start",
+            "Second end",
+            "First end",
+            "static initializer failed",
+            "Could not initialize class rjvm.ClassInitialization$Failing",
+            "0",
+            "not wrapped",
+        ],
+        printed
+    );
+}
+
+#[test_log::test]
+fn constant_values() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ConstantValues",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    let printed = printed_as_strings(&vm);
+    assert_eq!(
+        vec![
+            "42",
+            "constant",
+            "1234567890123",
+            "1.5",
+            "2.25",
+            "1",
+            "named",
+            "7",
+            "6",
+        ],
+        printed
+    );
+}
+
+#[test_log::test]
+fn string_interning() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StringInterning",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(0),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+        ],
+        vm.printed[..9]
+    );
+    assert_eq!("survives!", extract_printed_string(&vm, 9));
+}
+
+#[test_log::test]
+fn class_mirrors() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ClassMirrors",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    let printed = printed_as_strings(&vm);
+    assert_eq!(
+        vec![
+            // identity
+            "derived.getClass after gc=true",
+            "derived.getClass=true",
+            "new Derived().getClass=true",
+            "string.getClass=true",
+            "int[].getClass=true",
+            "String[][].getClass=true",
+            "Integer.TYPE=true",
+            "Base == derived.getClass=false",
+            // names
+            "Derived.getName=rjvm.ClassMirrors$Derived",
+            "Runnable.toString=interface java.lang.Runnable",
+            "int.getName=int",
+            "void.getName=void",
+            "int[].getName=[I",
+            "String[][].getName=[[Ljava.lang.String;",
+            "Base.getName is interned=true",
+            // hierarchy
+            "Derived.getSuperclass=true",
+            "Object.getSuperclass=true",
+            "Runnable.getSuperclass=true",
+            "int.getSuperclass=true",
+            "int[].getSuperclass=true",
+            "Runnable.isInterface=true",
+            "Derived.isInterface=false",
+            "int[].isArray=true",
+            "Object.isArray=false",
+            "int.isPrimitive=true",
+            "void.isPrimitive=true",
+            "int[].isPrimitive=false",
+            "String[][].getComponentType=true",
+            "int[].getComponentType=true",
+            "String.getComponentType=true",
+            // instances
+            "Base.isInstance(Derived)=true",
+            "Derived.isInstance(Base)=false",
+            "Runnable.isInstance(Derived)=true",
+            "Base.isInstance(null)=false",
+            "Object.isInstance(int[])=true",
+            "Object[].isInstance(String[])=true",
+            "int[].isInstance(long[])=false",
+            "Base.isAssignableFrom(Derived)=true",
+            "Derived.isAssignableFrom(Base)=false",
+            "Object.isAssignableFrom(int)=false",
+            "Cloneable.isAssignableFrom(int[])=true",
+            "Base[].isAssignableFrom(Derived[])=true",
+            "Base[].isAssignableFrom(Derived[][])=false",
+            "Object[].isAssignableFrom(Derived[][])=true",
+            "isAssignableFrom(null) threw npe",
+            // class keys
+            "base",
+            "string",
+            "int array",
+        ],
+        printed
+    );
+}
+
+#[test_log::test]
+fn reflection() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/Reflection", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+
+    let printed = printed_as_strings(&vm);
+    assert_eq!(
+        vec![
+            // injection
+            "1",
+            "42",
+            "configured",
+            "1",
+            // forName
+            "1",
+            "1",
+            "rjvm.Missing",
+            "loaded",
+            "lazy initialized",
+            "5",
+            // fields
+            "2",
+            "20000000000",
+            "payload",
+            "replaced",
+            "4",
+            "1",
+            "1",
+            // methods
+            "5",
+            "1",
+            "2",
+            "derived",
+            "a secret",
+            "1",
+            "boom",
+            "object is not an instance of declaring class",
+            "wrong number of arguments",
+            "argument type mismatch",
+            // constructors
+            "42",
+            "42",
+            "rjvm.Reflection$Shape",
+            // modifiers
+            "1",
+            "1",
+            "1",
+            "1",
+            "1",
+            "4",
+            "1",
+        ],
+        printed
+    );
+}
+
+#[test_log::test]
+fn object_methods() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ObjectMethods",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    let printed = printed_as_strings(&vm);
+    assert_eq!(
+        vec![
+            // hash codes
+            "1",
+            "1",
+            "1",
+            "first",
+            "second",
+            "third",
+            // cloning
+            "1",
+            "1",
+            "1",
+            "10000000000",
+            "point",
+            "1",
+            "1",
+            "1",
+            "label",
+            "3",
+            "1",
+            "5",
+            "rjvm.ObjectMethods$Plain",
+            // monitors
+            "current thread is not owner",
+            "wait without lock",
+            "timeout value is negative",
+            "synchronized block",
+            "released",
+            "synchronized method",
+            "synchronized static method",
+            "class released",
+        ],
+        printed
+    );
+}
+
+#[test_log::test]
+fn system_output() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let stdout = SharedBuffer::default();
+    let stderr = SharedBuffer::default();
+    vm.set_stdout(Box::new(stdout.clone()));
+    vm.set_stderr(Box::new(stderr.clone()));
+
+    let call_stack = vm.allocate_call_stack();
+    vm.initialize_system_class(call_stack)
+        .expect("should be able to initialize the system class");
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/SystemOutput",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        "Hello, world!\nThe answer is 42\na point\nnull\nunicode: àé€\n1\n",
+        stdout.contents()
+    );
+    assert_eq!("an error\n", stderr.contents());
+}
@@ -0,0 +1,297 @@
+import java.io.ByteArrayOutputStream;
+import java.io.DataOutputStream;
+import java.io.FileOutputStream;
+import java.io.IOException;
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * javac never emits a "ldc" of a MethodHandle, MethodType or dynamically-computed constant, so
+ * this program writes the classes rjvm/MethodHandleConstants and rjvm/DynamicConstantValues by
+ * hand. Each of their static methods returns one constant, and they are used respectively by
+ * rjvm/MethodHandles.java and rjvm/DynamicConstants.java. Likewise, javac inlines the constant
+ * fields rather than reading them with a "getstatic", so the class rjvm/ConstantFieldReaders,
+ * used by rjvm/ConstantValues.java, has one static method reading each of them.
+ * Run it from this folder with:
+ *
+ * <pre>java GenerateConstants.java</pre>
+ */
+public class GenerateConstants {
+    private static final int REF_GET_FIELD = 1;
+    private static final int REF_GET_STATIC = 2;
+    private static final int REF_PUT_FIELD = 3;
+    private static final int REF_PUT_STATIC = 4;
+    private static final int REF_INVOKE_VIRTUAL = 5;
+    private static final int REF_INVOKE_STATIC = 6;
+    private static final int REF_INVOKE_SPECIAL = 7;
+    private static final int REF_NEW_INVOKE_SPECIAL = 8;
+    private static final int REF_INVOKE_INTERFACE = 9;
+
+    private static final String METHOD_HANDLE = "()Ljava/lang/invoke/MethodHandle;";
+    private static final String METHOD_TYPE = "()Ljava/lang/invoke/MethodType;";
+    private static final String COUNTER = "rjvm/MethodHandles$Counter";
+    private static final String DYNAMIC_CONSTANTS = "rjvm/DynamicConstants";
+    private static final String CONSTANT_VALUES = "rjvm/ConstantValues";
+    private static final String BOOTSTRAP_PREFIX =
+            "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/Class;";
+
+    private final String className;
+    private final ByteArrayOutputStream constantsBytes = new ByteArrayOutputStream();
+    private final DataOutputStream constants = new DataOutputStream(constantsBytes);
+    private final Map<String, Integer> constantIndexes = new HashMap<>();
+    private int constantsCount = 0;
+    private final List<byte[]> methods = new ArrayList<>();
+    private final List<int[]> bootstrapMethods = new ArrayList<>();
+
+    private GenerateConstants(String className) {
+        this.className = className;
+    }
+
+    public static void main(String[] args) throws IOException {
+        generateMethodHandleConstants();
+        generateDynamicConstantValues();
+        generateConstantFieldReaders();
+    }
+
+    private static void generateMethodHandleConstants() throws IOException {
+        GenerateConstants generator = new GenerateConstants("rjvm/MethodHandleConstants");
+        generator.handle("add", REF_INVOKE_STATIC, "rjvm/MethodHandles", "add", "(II)I");
+        generator.handle("counterConstructor", REF_NEW_INVOKE_SPECIAL, COUNTER, "<init>", "(I)V");
+        generator.handle("increment", REF_INVOKE_VIRTUAL, COUNTER, "increment", "(I)I");
+        generator.handle("describe", REF_INVOKE_SPECIAL, COUNTER, "describe", "()Ljava/lang/String;");
+        generator.handle("getValue", REF_GET_FIELD, COUNTER, "value", "I");
+        generator.handle("setValue", REF_PUT_FIELD, COUNTER, "value", "I");
+        generator.handle("getTotal", REF_GET_STATIC, COUNTER, "total", "I");
+        generator.handle("setTotal", REF_PUT_STATIC, COUNTER, "total", "I");
+        generator.handle("area", REF_INVOKE_INTERFACE, "rjvm/MethodHandles$Shape", "area", "()I");
+        generator.handle("concat", REF_INVOKE_VIRTUAL, "java/lang/String", "concat",
+                "(Ljava/lang/String;)Ljava/lang/String;");
+        generator.type("addType", "(II)I");
+        generator.write();
+    }
+
+    private static void generateDynamicConstantValues() throws IOException {
+        GenerateConstants generator = new GenerateConstants("rjvm/DynamicConstantValues");
+        int answer = generator.dynamic("answer", "I", "answer", ")I");
+        generator.method("answer", "()I", answer);
+        generator.method("greeting", "()Ljava/lang/String;", generator.dynamic(
+                "greeting", "Ljava/lang/String;", "join",
+                "Ljava/lang/String;Ljava/lang/String;)Ljava/lang/Object;",
+                generator.string("hello, "), generator.string("world")));
+        generator.method("counted", "()Ljava/lang/Object;", generator.dynamic(
+                "counted", "Ljava/lang/Object;", "count", ")Ljava/lang/Object;"));
+        generator.method("sum", "()J", generator.dynamic(
+                "sum", "J", "sum", "[I)J",
+                generator.integer(1), generator.integer(2), generator.integer(39)));
+        generator.method("twiceTheAnswer", "()I", generator.dynamic(
+                "twiceTheAnswer", "I", "twice", "I)I", answer));
+        generator.method("failing", "()Ljava/lang/Object;", generator.dynamic(
+                "failing", "Ljava/lang/Object;", "fail", ")Ljava/lang/Object;"));
+        generator.method("wrongType", "()Ljava/lang/String;", generator.dynamic(
+                "wrongType", "Ljava/lang/String;", "count", ")Ljava/lang/Object;"));
+        generator.write();
+    }
+
+    private static void generateConstantFieldReaders() throws IOException {
+        GenerateConstants generator = new GenerateConstants("rjvm/ConstantFieldReaders");
+        generator.getStatic("intValue", CONSTANT_VALUES, "INT", "I");
+        generator.getStatic("longValue", CONSTANT_VALUES, "LONG", "J");
+        generator.getStatic("floatValue", CONSTANT_VALUES, "FLOAT", "F");
+        generator.getStatic("doubleValue", CONSTANT_VALUES, "DOUBLE", "D");
+        generator.getStatic("stringValue", CONSTANT_VALUES, "STRING", "Ljava/lang/String;");
+        generator.getStatic("otherStringValue", CONSTANT_VALUES + "$Other", "STRING",
+                "Ljava/lang/String;");
+        generator.getStatic("interfaceValue", CONSTANT_VALUES + "$Implementation", "NAME",
+                "Ljava/lang/String;");
+        generator.getStatic("superclassValue", CONSTANT_VALUES + "$Implementation", "BASE",
+                "I");
+        generator.write();
+    }
+
+    /** Writes a public static method that returns the value of the given static field */
+    private void getStatic(String methodName, String className, String fieldName, String descriptor)
+            throws IOException {
+        int field = reference("Field", className, fieldName, descriptor);
+        method(methodName, "()" + descriptor, 0xb2, field); // getstatic
+    }
+
+    private void handle(String methodName, int kind, String className, String name, String descriptor)
+            throws IOException {
+        method(methodName, METHOD_HANDLE, handle(kind, className, name, descriptor));
+    }
+
+    private int handle(int kind, String className, String name, String descriptor) throws IOException {
+        String referenceType = kind <= REF_PUT_STATIC ? "Field"
+                : kind == REF_INVOKE_INTERFACE ? "InterfaceMethod" : "Method";
+        int reference = reference(referenceType, className, name, descriptor);
+        return constant("MethodHandle:" + kind + ":" + reference, 15, out -> {
+            out.writeByte(kind);
+            out.writeShort(reference);
+        });
+    }
+
+    private void type(String methodName, String descriptor) throws IOException {
+        int descriptorIndex = utf8(descriptor);
+        int type = constant("MethodType:" + descriptor, 16, out -> out.writeShort(descriptorIndex));
+        method(methodName, METHOD_TYPE, type);
+    }
+
+    /**
+     * Adds a dynamically-computed constant, whose bootstrap method is the given static method of
+     * rjvm/DynamicConstants. Its descriptor is completed by the given suffix.
+     */
+    private int dynamic(String name, String descriptor, String bootstrapMethodName,
+            String bootstrapDescriptorSuffix, int... arguments) throws IOException {
+        int handle = handle(REF_INVOKE_STATIC, DYNAMIC_CONSTANTS, bootstrapMethodName,
+                BOOTSTRAP_PREFIX + bootstrapDescriptorSuffix);
+        int[] bootstrapMethod = new int[arguments.length + 1];
+        bootstrapMethod[0] = handle;
+        System.arraycopy(arguments, 0, bootstrapMethod, 1, arguments.length);
+        int bootstrapMethodIndex = bootstrapMethods.size();
+        bootstrapMethods.add(bootstrapMethod);
+
+        int nameAndType = nameAndType(name, descriptor);
+        return constant("Dynamic:" + bootstrapMethodIndex + ":" + nameAndType, 17, out -> {
+            out.writeShort(bootstrapMethodIndex);
+            out.writeShort(nameAndType);
+        });
+    }
+
+    /** Writes a public static method that returns the given constant */
+    private void method(String name, String descriptor, int constantIndex) throws IOException {
+        boolean wide = descriptor.endsWith("J") || descriptor.endsWith("D");
+        method(name, descriptor, wide ? 0x14 : 0x13, constantIndex); // ldc2_w or ldc_w
+    }
+
+    /**
+     * Writes a public static method that executes the given instruction, whose operand is a
+     * constant pool index, and returns the value it pushes
+     */
+    private void method(String name, String descriptor, int opcode, int constantIndex)
+            throws IOException {
+        int nameIndex = utf8(name);
+        int descriptorIndex = utf8(descriptor);
+        int codeIndex = utf8("Code");
+        boolean wide = descriptor.endsWith("J") || descriptor.endsWith("D");
+        int returnOpcode = descriptor.endsWith("I") ? 0xac // ireturn
+                : descriptor.endsWith("J") ? 0xad // lreturn
+                : descriptor.endsWith("F") ? 0xae // freturn
+                : descriptor.endsWith("D") ? 0xaf // dreturn
+                : 0xb0; // areturn
+
+        ByteArrayOutputStream bytes = new ByteArrayOutputStream();
+        DataOutputStream out = new DataOutputStream(bytes);
+        out.writeShort(0x0009); // public static
+        out.writeShort(nameIndex);
+        out.writeShort(descriptorIndex);
+        out.writeShort(1); // attributes count
+        out.writeShort(codeIndex);
+        out.writeInt(2 + 2 + 4 + 4 + 2 + 2);
+        out.writeShort(wide ? 2 : 1); // max stack
+        out.writeShort(0); // max locals
+        out.writeInt(4); // code length
+        out.writeByte(opcode);
+        out.writeShort(constantIndex);
+        out.writeByte(returnOpcode);
+        out.writeShort(0); // exception table length
+        out.writeShort(0); // attributes count
+        methods.add(bytes.toByteArray());
+    }
+
+    private void write() throws IOException {
+        int thisClass = classReference(className);
+        int superClass = classReference("java/lang/Object");
+        int bootstrapMethodsIndex = bootstrapMethods.isEmpty() ? 0 : utf8("BootstrapMethods");
+        try (DataOutputStream out = new DataOutputStream(new FileOutputStream(className + ".class"))) {
+            out.writeInt(0xCAFEBABE);
+            out.writeShort(0); // minor version
+            // java 11 is the first version supporting dynamically-computed constants
+            out.writeShort(bootstrapMethods.isEmpty() ? 52 : 55);
+            out.writeShort(constantsCount + 1);
+            out.write(constantsBytes.toByteArray());
+            out.writeShort(0x0031); // public final super
+            out.writeShort(thisClass);
+            out.writeShort(superClass);
+            out.writeShort(0); // interfaces count
+            out.writeShort(0); // fields count
+            out.writeShort(methods.size());
+            for (byte[] method : methods) {
+                out.write(method);
+            }
+            if (bootstrapMethods.isEmpty()) {
+                out.writeShort(0); // attributes count
+            } else {
+                out.writeShort(1); // attributes count
+                out.writeShort(bootstrapMethodsIndex);
+                int length = 2;
+                for (int[] bootstrapMethod : bootstrapMethods) {
+                    length += 2 * (bootstrapMethod.length + 1);
+                }
+                out.writeInt(length);
+                out.writeShort(bootstrapMethods.size());
+                for (int[] bootstrapMethod : bootstrapMethods) {
+                    out.writeShort(bootstrapMethod[0]);
+                    out.writeShort(bootstrapMethod.length - 1);
+                    for (int i = 1; i < bootstrapMethod.length; i++) {
+                        out.writeShort(bootstrapMethod[i]);
+                    }
+                }
+            }
+        }
+    }
+
+    private int reference(String type, String className, String name, String descriptor)
+            throws IOException {
+        int classIndex = classReference(className);
+        int nameAndType = nameAndType(name, descriptor);
+        int tag = type.equals("Field") ? 9 : type.equals("Method") ? 10 : 11;
+        return constant(type + ":" + classIndex + ":" + nameAndType, tag, out -> {
+            out.writeShort(classIndex);
+            out.writeShort(nameAndType);
+        });
+    }
+
+    private int nameAndType(String name, String descriptor) throws IOException {
+        int nameIndex = utf8(name);
+        int descriptorIndex = utf8(descriptor);
+        return constant("NameAndType:" + name + ":" + descriptor, 12, out -> {
+            out.writeShort(nameIndex);
+            out.writeShort(descriptorIndex);
+        });
+    }
+
+    private int classReference(String className) throws IOException {
+        int nameIndex = utf8(className);
+        return constant("Class:" + className, 7, out -> out.writeShort(nameIndex));
+    }
+
+    private int string(String value) throws IOException {
+        int valueIndex = utf8(value);
+        return constant("String:" + value, 8, out -> out.writeShort(valueIndex));
+    }
+
+    private int integer(int value) throws IOException {
+        return constant("Integer:" + value, 3, out -> out.writeInt(value));
+    }
+
+    private int utf8(String value) throws IOException {
+        return constant("Utf8:" + value, 1, out -> out.writeUTF(value));
+    }
+
+    private interface ConstantWriter {
+        void write(DataOutputStream out) throws IOException;
+    }
+
+    private int constant(String key, int tag, ConstantWriter writer) throws IOException {
+        Integer existing = constantIndexes.get(key);
+        if (existing != null) {
+            return existing;
+        }
+        constants.writeByte(tag);
+        writer.write(constants);
+        constantIndexes.put(key, ++constantsCount);
+        return constantsCount;
+    }
+}
@@ -0,0 +1,179 @@
+import java.nio.file.Files;
+import java.nio.file.Paths;
+
+import jdk.internal.org.objectweb.asm.ClassWriter;
+import jdk.internal.org.objectweb.asm.Label;
+import jdk.internal.org.objectweb.asm.MethodVisitor;
+
+import static jdk.internal.org.objectweb.asm.Opcodes.*;
+
+/**
+ * Modern javac versions cannot emit jsr/ret anymore, so this generates rjvm/Subroutines.class
+ * with the same shape of code that javac used to emit for `finally` blocks with `-target 1.4`.
+ * The generated class is equivalent to:
+ *
+ * <pre>
+ * public class Subroutines {
+ *     public static void main(String[] args) {
+ *         tempPrint(withFinally(21));
+ *         try {
+ *             throwingThroughFinally();
+ *         } catch (Exception e) {
+ *             tempPrint(2);
+ *         }
+ *         tempPrint(wideRet(5));
+ *     }
+ *
+ *     private static int withFinally(int x) {
+ *         try {
+ *             return x * 2;
+ *         } finally {
+ *             tempPrint(x);
+ *         }
+ *     }
+ *
+ *     private static void throwingThroughFinally() throws Exception {
+ *         try {
+ *             throw new Exception();
+ *         } finally {
+ *             tempPrint(1);
+ *         }
+ *     }
+ *
+ *     // Same as withFinally, but the return address is stored in a local above 255
+ *     private static int wideRet(int x) { ... }
+ *
+ *     private static native void tempPrint(int value);
+ * }
+ * </pre>
+ *
+ * Usage:
+ *     javac --add-exports java.base/jdk.internal.org.objectweb.asm=ALL-UNNAMED SubroutinesGenerator.java
+ *     java --add-exports java.base/jdk.internal.org.objectweb.asm=ALL-UNNAMED SubroutinesGenerator
+ */
+public class SubroutinesGenerator {
+    public static void main(String[] args) throws Exception {
+        ClassWriter cw = new ClassWriter(ClassWriter.COMPUTE_MAXS);
+        cw.visit(V1_4, ACC_PUBLIC | ACC_SUPER, "rjvm/Subroutines", null, "java/lang/Object", null);
+        cw.visitSource("Subroutines.java", null);
+
+        generateMain(cw);
+        generateWithFinally(cw, "withFinally", 1, 2, 3);
+        generateWithFinally(cw, "wideRet", 1, 2, 300);
+        generateThrowingThroughFinally(cw);
+
+        cw.visitMethod(ACC_PRIVATE | ACC_STATIC | ACC_NATIVE, "tempPrint", "(I)V", null, null).visitEnd();
+        cw.visitEnd();
+
+        Files.write(Paths.get("rjvm", "Subroutines.class"), cw.toByteArray());
+    }
+
+    private static void generateMain(ClassWriter cw) {
+        MethodVisitor mv = cw.visitMethod(ACC_PUBLIC | ACC_STATIC, "main", "([Ljava/lang/String;)V", null, null);
+        mv.visitCode();
+
+        mv.visitIntInsn(BIPUSH, 21);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/Subroutines", "withFinally", "(I)I", false);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/Subroutines", "tempPrint", "(I)V", false);
+
+        Label tryStart = new Label();
+        Label tryEnd = new Label();
+        Label handler = new Label();
+        Label afterCatch = new Label();
+        mv.visitTryCatchBlock(tryStart, tryEnd, handler, "java/lang/Exception");
+        mv.visitLabel(tryStart);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/Subroutines", "throwingThroughFinally", "()V", false);
+        mv.visitLabel(tryEnd);
+        mv.visitJumpInsn(GOTO, afterCatch);
+        mv.visitLabel(handler);
+        mv.visitVarInsn(ASTORE, 1);
+        mv.visitInsn(ICONST_2);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/Subroutines", "tempPrint", "(I)V", false);
+        mv.visitLabel(afterCatch);
+
+        mv.visitInsn(ICONST_5);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/Subroutines", "wideRet", "(I)I", false);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/Subroutines", "tempPrint", "(I)V", false);
+
+        mv.visitInsn(RETURN);
+        mv.visitMaxs(0, 0);
+        mv.visitEnd();
+    }
+
+    private static void generateWithFinally(
+            ClassWriter cw, String name, int resultLocal, int exceptionLocal, int returnAddressLocal) {
+        MethodVisitor mv = cw.visitMethod(ACC_PRIVATE | ACC_STATIC, name, "(I)I", null, null);
+        mv.visitCode();
+
+        Label tryStart = new Label();
+        Label tryEnd = new Label();
+        Label anyHandler = new Label();
+        Label subroutine = new Label();
+        mv.visitTryCatchBlock(tryStart, tryEnd, anyHandler, null);
+
+        // try { result = x * 2; jsr finally; return result; }
+        mv.visitLabel(tryStart);
+        mv.visitVarInsn(ILOAD, 0);
+        mv.visitInsn(ICONST_2);
+        mv.visitInsn(IMUL);
+        mv.visitVarInsn(ISTORE, resultLocal);
+        mv.visitLabel(tryEnd);
+        mv.visitJumpInsn(JSR, subroutine);
+        mv.visitVarInsn(ILOAD, resultLocal);
+        mv.visitInsn(IRETURN);
+
+        // catch (any) { jsr finally; rethrow }
+        mv.visitLabel(anyHandler);
+        mv.visitVarInsn(ASTORE, exceptionLocal);
+        mv.visitJumpInsn(JSR, subroutine);
+        mv.visitVarInsn(ALOAD, exceptionLocal);
+        mv.visitInsn(ATHROW);
+
+        // finally { tempPrint(x); }
+        mv.visitLabel(subroutine);
+        mv.visitVarInsn(ASTORE, returnAddressLocal);
+        mv.visitVarInsn(ILOAD, 0);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/Subroutines", "tempPrint", "(I)V", false);
+        mv.visitVarInsn(RET, returnAddressLocal);
+
+        mv.visitMaxs(0, 0);
+        mv.visitEnd();
+    }
+
+    private static void generateThrowingThroughFinally(ClassWriter cw) {
+        MethodVisitor mv = cw.visitMethod(
+                ACC_PRIVATE | ACC_STATIC, "throwingThroughFinally", "()V", null, new String[] {"java/lang/Exception"});
+        mv.visitCode();
+
+        Label tryStart = new Label();
+        Label tryEnd = new Label();
+        Label anyHandler = new Label();
+        Label subroutine = new Label();
+        mv.visitTryCatchBlock(tryStart, tryEnd, anyHandler, null);
+
+        // try { throw new Exception(); }
+        mv.visitLabel(tryStart);
+        mv.visitTypeInsn(NEW, "java/lang/Exception");
+        mv.visitInsn(DUP);
+        mv.visitMethodInsn(INVOKESPECIAL, "java/lang/Exception", "<init>", "()V", false);
+        mv.visitInsn(ATHROW);
+        mv.visitLabel(tryEnd);
+
+        // catch (any) { jsr finally; rethrow }
+        mv.visitLabel(anyHandler);
+        mv.visitVarInsn(ASTORE, 0);
+        mv.visitJumpInsn(JSR, subroutine);
+        mv.visitVarInsn(ALOAD, 0);
+        mv.visitInsn(ATHROW);
+
+        // finally { tempPrint(1); }
+        mv.visitLabel(subroutine);
+        mv.visitVarInsn(ASTORE, 1);
+        mv.visitInsn(ICONST_1);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/Subroutines", "tempPrint", "(I)V", false);
+        mv.visitVarInsn(RET, 1);
+
+        mv.visitMaxs(0, 0);
+        mv.visitEnd();
+    }
+}
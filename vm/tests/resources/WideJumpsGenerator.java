@@ -0,0 +1,158 @@
+import java.nio.file.Files;
+import java.nio.file.Paths;
+
+import jdk.internal.org.objectweb.asm.ClassWriter;
+import jdk.internal.org.objectweb.asm.Label;
+import jdk.internal.org.objectweb.asm.MethodVisitor;
+
+import static jdk.internal.org.objectweb.asm.Opcodes.*;
+
+/**
+ * javac only emits goto_w and jsr_w for methods whose code is too long for a 16 bits offset,
+ * so this generates rjvm/WideJumps.class using them in small methods, like bytecode generators
+ * and obfuscators do. The generated class is equivalent to:
+ *
+ * <pre>
+ * public class WideJumps {
+ *     public static void main(String[] args) {
+ *         tempPrint(sum(10));
+ *         tempPrint(withFinally(3));
+ *     }
+ *
+ *     // Uses goto_w to jump both forward and backward
+ *     private static int sum(int n) {
+ *         int sum = 0;
+ *         for (int i = 1; i <= n; ++i) {
+ *             sum += i;
+ *         }
+ *         return sum;
+ *     }
+ *
+ *     // Uses jsr_w to invoke the finally block
+ *     private static int withFinally(int x) {
+ *         try {
+ *             return x * 2;
+ *         } finally {
+ *             tempPrint(x);
+ *         }
+ *     }
+ *
+ *     private static native void tempPrint(int value);
+ * }
+ * </pre>
+ *
+ * Usage:
+ *     javac --add-exports java.base/jdk.internal.org.objectweb.asm=ALL-UNNAMED WideJumpsGenerator.java
+ *     java --add-exports java.base/jdk.internal.org.objectweb.asm=ALL-UNNAMED WideJumpsGenerator
+ */
+public class WideJumpsGenerator {
+    // ASM emits the wide form of a jump only if given these opcodes explicitly
+    private static final int GOTO_W = 200;
+    private static final int JSR_W = 201;
+
+    public static void main(String[] args) throws Exception {
+        ClassWriter cw = new ClassWriter(ClassWriter.COMPUTE_MAXS);
+        cw.visit(V1_4, ACC_PUBLIC | ACC_SUPER, "rjvm/WideJumps", null, "java/lang/Object", null);
+        cw.visitSource("WideJumps.java", null);
+
+        generateMain(cw);
+        generateSum(cw);
+        generateWithFinally(cw);
+
+        cw.visitMethod(ACC_PRIVATE | ACC_STATIC | ACC_NATIVE, "tempPrint", "(I)V", null, null).visitEnd();
+        cw.visitEnd();
+
+        Files.write(Paths.get("rjvm", "WideJumps.class"), cw.toByteArray());
+    }
+
+    private static void generateMain(ClassWriter cw) {
+        MethodVisitor mv = cw.visitMethod(ACC_PUBLIC | ACC_STATIC, "main", "([Ljava/lang/String;)V", null, null);
+        mv.visitCode();
+
+        mv.visitIntInsn(BIPUSH, 10);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/WideJumps", "sum", "(I)I", false);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/WideJumps", "tempPrint", "(I)V", false);
+
+        mv.visitInsn(ICONST_3);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/WideJumps", "withFinally", "(I)I", false);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/WideJumps", "tempPrint", "(I)V", false);
+
+        mv.visitInsn(RETURN);
+        mv.visitMaxs(0, 0);
+        mv.visitEnd();
+    }
+
+    private static void generateSum(ClassWriter cw) {
+        MethodVisitor mv = cw.visitMethod(ACC_PRIVATE | ACC_STATIC, "sum", "(I)I", null, null);
+        mv.visitCode();
+
+        Label start = new Label();
+        Label loop = new Label();
+        Label end = new Label();
+
+        // Skip some unreachable code with a forward jump
+        mv.visitJumpInsn(GOTO_W, start);
+        mv.visitInsn(ICONST_M1);
+        mv.visitInsn(IRETURN);
+
+        mv.visitLabel(start);
+        mv.visitInsn(ICONST_0);
+        mv.visitVarInsn(ISTORE, 1);
+        mv.visitInsn(ICONST_1);
+        mv.visitVarInsn(ISTORE, 2);
+
+        mv.visitLabel(loop);
+        mv.visitVarInsn(ILOAD, 2);
+        mv.visitVarInsn(ILOAD, 0);
+        mv.visitJumpInsn(IF_ICMPGT, end);
+        mv.visitVarInsn(ILOAD, 1);
+        mv.visitVarInsn(ILOAD, 2);
+        mv.visitInsn(IADD);
+        mv.visitVarInsn(ISTORE, 1);
+        mv.visitIincInsn(2, 1);
+        mv.visitJumpInsn(GOTO_W, loop);
+
+        mv.visitLabel(end);
+        mv.visitVarInsn(ILOAD, 1);
+        mv.visitInsn(IRETURN);
+
+        mv.visitMaxs(0, 0);
+        mv.visitEnd();
+    }
+
+    private static void generateWithFinally(ClassWriter cw) {
+        MethodVisitor mv = cw.visitMethod(ACC_PRIVATE | ACC_STATIC, "withFinally", "(I)I", null, null);
+        mv.visitCode();
+
+        Label tryStart = new Label();
+        Label tryEnd = new Label();
+        Label anyHandler = new Label();
+        Label subroutine = new Label();
+        mv.visitTryCatchBlock(tryStart, tryEnd, anyHandler, null);
+
+        mv.visitLabel(tryStart);
+        mv.visitVarInsn(ILOAD, 0);
+        mv.visitInsn(ICONST_2);
+        mv.visitInsn(IMUL);
+        mv.visitVarInsn(ISTORE, 1);
+        mv.visitLabel(tryEnd);
+        mv.visitJumpInsn(JSR_W, subroutine);
+        mv.visitVarInsn(ILOAD, 1);
+        mv.visitInsn(IRETURN);
+
+        mv.visitLabel(anyHandler);
+        mv.visitVarInsn(ASTORE, 2);
+        mv.visitJumpInsn(JSR_W, subroutine);
+        mv.visitVarInsn(ALOAD, 2);
+        mv.visitInsn(ATHROW);
+
+        mv.visitLabel(subroutine);
+        mv.visitVarInsn(ASTORE, 3);
+        mv.visitVarInsn(ILOAD, 0);
+        mv.visitMethodInsn(INVOKESTATIC, "rjvm/WideJumps", "tempPrint", "(I)V", false);
+        mv.visitVarInsn(RET, 3);
+
+        mv.visitMaxs(0, 0);
+        mv.visitEnd();
+    }
+}
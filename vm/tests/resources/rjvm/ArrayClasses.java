@@ -0,0 +1,40 @@
+package rjvm;
+
+import java.io.Serializable;
+
+class ArrayClasses {
+    public static void main(String[] args) {
+        int[] ints = new int[] {1, 2, 3};
+        int[] copy = ints.clone();
+        tempPrint(copy[2]);
+        tempPrint(copy == ints ? 1 : 0);
+
+        Object object = ints;
+        tempPrint(object.equals(ints) ? 1 : 0);
+        tempPrint(object.equals(copy) ? 1 : 0);
+        tempPrint(object instanceof Cloneable ? 1 : 0);
+        tempPrint(object instanceof Serializable ? 1 : 0);
+
+        // Cloning is shallow
+        String[][] strings = new String[2][2];
+        strings[1][0] = "a";
+        String[][] stringsCopy = strings.clone();
+        tempPrint(stringsCopy[1] == strings[1] ? 1 : 0);
+        tempPrint(stringsCopy[1][0]);
+
+        Object[] objects = stringsCopy;
+        tempPrint(objects.clone().length);
+
+        // Arrays of arrays are arrays of objects, cloneables and serializables
+        Object nested = strings;
+        tempPrint(nested instanceof Object[] ? 1 : 0);
+        tempPrint(nested instanceof Cloneable[] ? 1 : 0);
+        tempPrint(nested instanceof Serializable[][] ? 1 : 0);
+        tempPrint(nested instanceof Cloneable[][] ? 1 : 0);
+        tempPrint(object instanceof Object[] ? 1 : 0);
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(String value);
+}
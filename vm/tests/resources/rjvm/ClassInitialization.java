@@ -0,0 +1,144 @@
+package rjvm;
+
+class ClassInitialization {
+    static class Base {
+        static {
+            tempPrint("Base");
+        }
+    }
+
+    static class Derived extends Base {
+        static {
+            tempPrint("Derived");
+        }
+    }
+
+    static class Helper {
+        static {
+            tempPrint("Helper");
+        }
+
+        static void touch() {}
+    }
+
+    interface WithDefault {
+        int VALUE = init("WithDefault");
+
+        default int value() {
+            return VALUE;
+        }
+    }
+
+    interface WithoutDefault {
+        int VALUE = init("WithoutDefault");
+
+        int value();
+    }
+
+    static class Implementation implements WithoutDefault, WithDefault {
+        static {
+            tempPrint("Implementation");
+        }
+
+        public int value() {
+            return 1;
+        }
+    }
+
+    static class NeverInitialized {
+        static {
+            tempPrint("NeverInitialized");
+        }
+    }
+
+    static class First {
+        static {
+            tempPrint("First start");
+            Second.touch();
+            tempPrint("First end");
+        }
+
+        static void touch() {}
+    }
+
+    static class Second {
+        static {
+            tempPrint("Second start");
+            First.touch();
+            tempPrint("Second end");
+        }
+
+        static void touch() {}
+    }
+
+    static class Failing {
+        static {
+            fail();
+        }
+
+        static void touch() {}
+    }
+
+    static class FailingWithError {
+        static {
+            failWithError();
+        }
+
+        static void touch() {}
+    }
+
+    public static void main(String[] args) {
+        tempPrint("main");
+        notActiveUses();
+        new Derived();
+        new Derived();
+        Helper.touch();
+        new Implementation();
+        First.touch();
+        failures();
+    }
+
+    private static void notActiveUses() {
+        NeverInitialized[] array = new NeverInitialized[1];
+        Object object = NeverInitialized.class;
+        tempPrint(object instanceof NeverInitialized ? 1 : 0);
+        tempPrint(array.length);
+    }
+
+    private static void failures() {
+        try {
+            Failing.touch();
+        } catch (ExceptionInInitializerError e) {
+            tempPrint(e.getCause().getMessage());
+        }
+        try {
+            Failing.touch();
+        } catch (NoClassDefFoundError e) {
+            tempPrint(e.getMessage());
+        }
+
+        try {
+            FailingWithError.touch();
+        } catch (LinkageError e) {
+            tempPrint(e instanceof ExceptionInInitializerError ? 1 : 0);
+            tempPrint(e.getMessage());
+        }
+    }
+
+    static int init(String name) {
+        tempPrint(name);
+        return 1;
+    }
+
+    static void fail() {
+        throw new IllegalStateException("static initializer failed");
+    }
+
+    static void failWithError() {
+        throw new LinkageError("not wrapped");
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,111 @@
+package rjvm;
+
+class ClassMirrors {
+    static class Base {
+    }
+
+    static class Derived extends Base implements Runnable {
+        public void run() {
+        }
+    }
+
+    public static void main(String[] args) {
+        identity();
+        names();
+        hierarchy();
+        instances();
+        classKeys();
+    }
+
+    private static void identity() {
+        Derived derived = new Derived();
+        Class<?> derivedClassBeforeGc = derived.getClass();
+        // Before printing anything, since the printed strings are not gc roots
+        System.gc();
+        check("derived.getClass after gc", derivedClassBeforeGc == derived.getClass());
+
+        check("derived.getClass", Derived.class == derived.getClass());
+        check("new Derived().getClass", Derived.class == new Derived().getClass());
+        check("string.getClass", String.class == "a string".getClass());
+        check("int[].getClass", int[].class == new int[1].getClass());
+        check("String[][].getClass", String[][].class == new String[1][1].getClass());
+        check("Integer.TYPE", int.class == Integer.TYPE);
+        check("Base == derived.getClass", (Object) Base.class == derived.getClass());
+    }
+
+    private static void names() {
+        print("Derived.getName", Derived.class.getName());
+        print("Runnable.toString", Runnable.class.toString());
+        print("int.getName", int.class.getName());
+        print("void.getName", void.class.getName());
+        print("int[].getName", int[].class.getName());
+        print("String[][].getName", String[][].class.getName());
+        check("Base.getName is interned", Base.class.getName() == Base.class.getName());
+    }
+
+    private static void hierarchy() {
+        check("Derived.getSuperclass", Derived.class.getSuperclass() == Base.class);
+        check("Object.getSuperclass", Object.class.getSuperclass() == null);
+        check("Runnable.getSuperclass", Runnable.class.getSuperclass() == null);
+        check("int.getSuperclass", int.class.getSuperclass() == null);
+        check("int[].getSuperclass", int[].class.getSuperclass() == Object.class);
+
+        check("Runnable.isInterface", Runnable.class.isInterface());
+        check("Derived.isInterface", Derived.class.isInterface());
+        check("int[].isArray", int[].class.isArray());
+        check("Object.isArray", Object.class.isArray());
+        check("int.isPrimitive", int.class.isPrimitive());
+        check("void.isPrimitive", void.class.isPrimitive());
+        check("int[].isPrimitive", int[].class.isPrimitive());
+
+        check("String[][].getComponentType", String[][].class.getComponentType() == String[].class);
+        check("int[].getComponentType", int[].class.getComponentType() == int.class);
+        check("String.getComponentType", String.class.getComponentType() == null);
+    }
+
+    private static void instances() {
+        check("Base.isInstance(Derived)", Base.class.isInstance(new Derived()));
+        check("Derived.isInstance(Base)", Derived.class.isInstance(new Base()));
+        check("Runnable.isInstance(Derived)", Runnable.class.isInstance(new Derived()));
+        check("Base.isInstance(null)", Base.class.isInstance(null));
+        check("Object.isInstance(int[])", Object.class.isInstance(new int[0]));
+        check("Object[].isInstance(String[])", Object[].class.isInstance(new String[0]));
+        check("int[].isInstance(long[])", int[].class.isInstance(new long[0]));
+
+        check("Base.isAssignableFrom(Derived)", Base.class.isAssignableFrom(Derived.class));
+        check("Derived.isAssignableFrom(Base)", Derived.class.isAssignableFrom(Base.class));
+        check("Object.isAssignableFrom(int)", Object.class.isAssignableFrom(int.class));
+        check("Cloneable.isAssignableFrom(int[])", Cloneable.class.isAssignableFrom(int[].class));
+        check("Base[].isAssignableFrom(Derived[])", Base[].class.isAssignableFrom(Derived[].class));
+        check("Base[].isAssignableFrom(Derived[][])", Base[].class.isAssignableFrom(Derived[][].class));
+        check("Object[].isAssignableFrom(Derived[][])", Object[].class.isAssignableFrom(Derived[][].class));
+        try {
+            Base.class.isAssignableFrom(null);
+        } catch (NullPointerException e) {
+            tempPrint("isAssignableFrom(null) threw npe");
+        }
+    }
+
+    private static void classKeys() {
+        Class<?>[] keys = {String.class, Base.class, int[].class};
+        String[] values = {"string", "base", "int array"};
+        Object[] objects = {new Base(), "a", new int[2]};
+        for (Object object : objects) {
+            for (int i = 0; i < keys.length; ++i) {
+                if (keys[i] == object.getClass()) {
+                    tempPrint(values[i]);
+                }
+            }
+        }
+    }
+
+    private static void check(String label, boolean value) {
+        tempPrint(label + "=" + value);
+    }
+
+    private static void print(String label, String value) {
+        tempPrint(label + "=" + value);
+    }
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,59 @@
+package rjvm;
+
+// javac inlines the constant fields, so they are read with a "getstatic" by the class
+// ConstantFieldReaders, generated by GenerateConstants.java.
+class ConstantValues {
+    static final int INT = 42;
+    static final long LONG = 1234567890123L;
+    static final float FLOAT = 1.5f;
+    static final double DOUBLE = 2.25;
+    static final String STRING = "constant";
+
+    static {
+        // The constant values are set before the static initializer runs
+        tempPrint(ConstantFieldReaders.intValue());
+        tempPrint(ConstantFieldReaders.stringValue());
+    }
+
+    static class Other {
+        static final String STRING = "constant";
+    }
+
+    interface Named {
+        String NAME = "named";
+    }
+
+    static class Base {
+        static final int BASE = 7;
+    }
+
+    static class Implementation extends Base implements Named {
+    }
+
+    static class Counter {
+        static int count;
+    }
+
+    public static void main(String[] args) {
+        tempPrint(ConstantFieldReaders.longValue());
+        tempPrint(ConstantFieldReaders.floatValue());
+        tempPrint(ConstantFieldReaders.doubleValue());
+        tempPrint(ConstantFieldReaders.stringValue() == ConstantFieldReaders.otherStringValue() ? 1 : 0);
+        tempPrint(ConstantFieldReaders.interfaceValue());
+        tempPrint(ConstantFieldReaders.superclassValue());
+
+        Counter.count += 5;
+        Counter.count++;
+        tempPrint(Counter.count);
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(float value);
+
+    private static native void tempPrint(double value);
+
+    private static native void tempPrint(String value);
+}
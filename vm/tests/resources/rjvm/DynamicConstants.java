@@ -0,0 +1,86 @@
+package rjvm;
+
+import java.lang.invoke.MethodHandles;
+
+// The dynamically-computed constants are loaded by the class DynamicConstantValues, generated by
+// GenerateConstants.java, since javac cannot emit them. The static methods of this class are
+// their bootstrap methods.
+class DynamicConstants {
+    private static int bootstrapCount;
+    private static int failedBootstrapCount;
+
+    public static void main(String[] args) {
+        tempPrint(DynamicConstantValues.answer());
+        tempPrint(DynamicConstantValues.greeting());
+        resolvedOnce();
+        tempPrint((int) DynamicConstantValues.sum());
+        tempPrint(DynamicConstantValues.twiceTheAnswer());
+        failures();
+    }
+
+    private static void resolvedOnce() {
+        Object first = DynamicConstantValues.counted();
+        Object second = DynamicConstantValues.counted();
+        tempPrint(first == second ? 1 : 0);
+        tempPrint(bootstrapCount);
+    }
+
+    private static void failures() {
+        BootstrapMethodError firstError = null;
+        try {
+            DynamicConstantValues.failing();
+        } catch (BootstrapMethodError e) {
+            firstError = e;
+            tempPrint(e.getMessage());
+            tempPrint(e.getCause().getMessage());
+        }
+
+        try {
+            DynamicConstantValues.wrongType();
+        } catch (BootstrapMethodError e) {
+            tempPrint(e.getCause() instanceof ClassCastException ? 1 : 0);
+        }
+
+        // A failed resolution is not retried: the same error is thrown again
+        try {
+            DynamicConstantValues.failing();
+        } catch (BootstrapMethodError e) {
+            tempPrint(e == firstError ? 1 : 0);
+        }
+        tempPrint(failedBootstrapCount);
+    }
+
+    static int answer(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        return 42;
+    }
+
+    static Object join(MethodHandles.Lookup lookup, String name, Class<?> type, String a, String b) {
+        return name.concat(": ").concat(a).concat(b);
+    }
+
+    static Object count(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        ++bootstrapCount;
+        return new Object();
+    }
+
+    static long sum(MethodHandles.Lookup lookup, String name, Class<?> type, int... values) {
+        long sum = 0;
+        for (int value : values) {
+            sum += value;
+        }
+        return sum;
+    }
+
+    static int twice(MethodHandles.Lookup lookup, String name, Class<?> type, int value) {
+        return value * 2;
+    }
+
+    static Object fail(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        ++failedBootstrapCount;
+        throw new IllegalArgumentException("cannot compute ".concat(name));
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,187 @@
+package rjvm;
+
+import java.io.Serializable;
+
+class Lambdas {
+    interface IntOperation {
+        int apply(int value);
+    }
+
+    interface LongFromInt {
+        long apply(int value);
+    }
+
+    interface DoubleSupplier {
+        double get();
+    }
+
+    interface Function<T, R> {
+        R apply(T argument);
+    }
+
+    interface Consumer<T> {
+        void accept(T argument);
+    }
+
+    interface StringConsumer extends Consumer<String> {
+        void accept(String argument);
+    }
+
+    interface ObjectSupplier {
+        Object get();
+    }
+
+    interface StringSupplier {
+        String get();
+    }
+
+    interface BothSuppliers extends ObjectSupplier, StringSupplier {}
+
+    interface PointFactory {
+        Point create(int x, int y);
+    }
+
+    interface Marker {}
+
+    static class Point {
+        final int x;
+        final int y;
+
+        Point(int x, int y) {
+            this.x = x;
+            this.y = y;
+        }
+
+        int sum() {
+            return x + y;
+        }
+    }
+
+    private static int counter;
+
+    private final int base;
+
+    Lambdas(int base) {
+        this.base = base;
+    }
+
+    public static void main(String[] args) {
+        noCapture();
+        capturePrimitives(3, 4L, 0.5);
+        new Lambdas(10).captureThis();
+        methodReferences();
+        conversions();
+        bridges();
+        markersAndSerializable();
+        linkedOnce();
+    }
+
+    private static void noCapture() {
+        Runnable runnable = () -> counter++;
+        runnable.run();
+        runnable.run();
+        tempPrint(counter);
+
+        IntOperation increment = x -> x + 1;
+        tempPrint(increment.apply(41));
+    }
+
+    private static void capturePrimitives(int i, long l, double d) {
+        LongFromInt sum = x -> x + i + l;
+        tempPrint((int) sum.apply(5));
+
+        DoubleSupplier half = () -> d * i;
+        tempPrint((int) (half.get() * 10));
+    }
+
+    private void captureThis() {
+        IntOperation addBase = x -> x + base;
+        tempPrint(addBase.apply(5));
+    }
+
+    private static int twice(int value) {
+        return value * 2;
+    }
+
+    private static void methodReferences() {
+        IntOperation staticReference = Lambdas::twice;
+        tempPrint(staticReference.apply(21));
+
+        Function<String, String> boundReference = "hello, "::concat;
+        tempPrint(boundReference.apply("world"));
+
+        Function<Point, Integer> unboundReference = Point::sum;
+        tempPrint(unboundReference.apply(new Point(1, 2)).intValue());
+
+        PointFactory constructorReference = Point::new;
+        Point point = constructorReference.create(3, 4);
+        tempPrint(point.x);
+        tempPrint(point.y);
+    }
+
+    private static long square(long value) {
+        return value * value;
+    }
+
+    private static void conversions() {
+        // int -> long widening of the argument
+        LongFromInt widening = Lambdas::square;
+        tempPrint((int) widening.apply(9));
+
+        // boxing of the int returned by String::length
+        Function<String, Integer> boxing = String::length;
+        tempPrint(boxing.apply("four").intValue());
+
+        // unboxing of the argument of twice
+        Function<Integer, Integer> unboxing = Lambdas::twice;
+        tempPrint(unboxing.apply(Integer.valueOf(50)).intValue());
+    }
+
+    private static void bridges() {
+        StringConsumer consumer = s -> tempPrint(s.length());
+        consumer.accept("abc");
+
+        // The lambda class must implement both get() methods
+        BothSuppliers suppliers = () -> "supplied";
+        StringSupplier stringSupplier = suppliers;
+        tempPrint(stringSupplier.get());
+        ObjectSupplier objectSupplier = suppliers;
+        tempPrint((String) objectSupplier.get());
+    }
+
+    private static void markersAndSerializable() {
+        IntOperation marked = (IntOperation & Marker) x -> x * 3;
+        tempPrint(marked.apply(5));
+        tempPrint(marked instanceof Marker ? 1 : 0);
+
+        IntOperation serializable = (IntOperation & Serializable) x -> x * 4;
+        tempPrint(serializable.apply(5));
+        tempPrint(serializable instanceof Serializable ? 1 : 0);
+        tempPrint(marked instanceof Serializable ? 1 : 0);
+    }
+
+    private static void linkedOnce() {
+        int total = 0;
+        Class<?> lambdaClass = null;
+        boolean sameClass = true;
+        for (int i = 0; i < 5; ++i) {
+            final int captured = i;
+            IntOperation operation = x -> x + captured;
+            total += operation.apply(1);
+            if (lambdaClass == null) {
+                lambdaClass = operation.getClass();
+            } else {
+                sameClass &= lambdaClass == operation.getClass();
+            }
+        }
+        tempPrint(total);
+        tempPrint(sameClass ? 1 : 0);
+
+        IntOperation otherCallSite = x -> x;
+        tempPrint(otherCallSite.getClass() != lambdaClass ? 1 : 0);
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,126 @@
+package rjvm;
+
+import java.lang.invoke.MethodHandle;
+import java.lang.invoke.MethodType;
+import java.lang.invoke.WrongMethodTypeException;
+
+// The method handles are loaded by the class MethodHandleConstants, generated by
+// GenerateConstants.java, since javac cannot emit them.
+class MethodHandles {
+    interface Shape {
+        int area();
+    }
+
+    static class Square implements Shape {
+        private final int side;
+
+        Square(int side) {
+            this.side = side;
+        }
+
+        public int area() {
+            return side * side;
+        }
+    }
+
+    static class Counter {
+        static int total;
+
+        int value;
+
+        Counter(int value) {
+            this.value = value;
+        }
+
+        int increment(int by) {
+            value += by;
+            return value;
+        }
+
+        private String describe() {
+            return "counter";
+        }
+    }
+
+    public static void main(String[] args) throws Throwable {
+        invokeStatic();
+        constructorsAndVirtualMethods();
+        fields();
+        conversions();
+        wrongType();
+        methodTypes();
+    }
+
+    static int add(int a, int b) {
+        return a + b;
+    }
+
+    private static void invokeStatic() throws Throwable {
+        MethodHandle add = MethodHandleConstants.add();
+        tempPrint((int) add.invokeExact(1, 2));
+    }
+
+    private static void constructorsAndVirtualMethods() throws Throwable {
+        Counter counter = (Counter) MethodHandleConstants.counterConstructor().invokeExact(10);
+        tempPrint(counter.value);
+
+        MethodHandle increment = MethodHandleConstants.increment();
+        tempPrint((int) increment.invokeExact(counter, 5));
+        tempPrint(counter.value);
+
+        tempPrint((String) MethodHandleConstants.describe().invokeExact(counter));
+
+        Shape square = new Square(4);
+        tempPrint((int) MethodHandleConstants.area().invokeExact(square));
+
+        tempPrint((String) MethodHandleConstants.concat().invokeExact("method ", "handles"));
+    }
+
+    private static void fields() throws Throwable {
+        Counter counter = new Counter(7);
+        tempPrint((int) MethodHandleConstants.getValue().invokeExact(counter));
+        MethodHandleConstants.setValue().invokeExact(counter, 8);
+        tempPrint(counter.value);
+
+        MethodHandleConstants.setTotal().invokeExact(100);
+        tempPrint((int) MethodHandleConstants.getTotal().invokeExact());
+    }
+
+    private static void conversions() throws Throwable {
+        MethodHandle add = MethodHandleConstants.add();
+
+        // Unboxing of the first argument, and boxing of the result
+        Object first = Integer.valueOf(20);
+        Integer boxed = (Integer) add.invoke(first, 22);
+        tempPrint(boxed.intValue());
+
+        // Widening of the result
+        long widened = (long) add.invoke(1, 1);
+        tempPrint((int) widened);
+
+        // Dropping the result
+        add.invoke(1, 1);
+        tempPrint(1);
+    }
+
+    private static void wrongType() throws Throwable {
+        MethodHandle add = MethodHandleConstants.add();
+        try {
+            long result = (long) add.invokeExact(1, 2);
+            tempPrint((int) result);
+        } catch (WrongMethodTypeException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static void methodTypes() {
+        MethodType type = MethodHandleConstants.addType();
+        tempPrint(type.parameterCount());
+        tempPrint(MethodHandleConstants.add().type().parameterCount());
+        tempPrint(MethodHandleConstants.getValue().type().parameterCount());
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,108 @@
+package rjvm;
+
+public class MultiDimensionalArrays {
+    public static void main(String[] args) {
+        matrices();
+        partiallySpecifiedDimensions();
+        objectMatrices();
+        typeChecks();
+        cloning();
+        garbageCollection();
+    }
+
+    private static void matrices() {
+        int[][] matrix = new int[3][4];
+        for (int i = 0; i < matrix.length; ++i) {
+            for (int j = 0; j < matrix[i].length; ++j) {
+                matrix[i][j] = i * 10 + j;
+            }
+        }
+        tempPrint(matrix.length);
+        tempPrint(matrix[0].length);
+        tempPrint(matrix[2][3]);
+
+        long[][][] cube = new long[2][3][4];
+        cube[1][2][3] = 42L;
+        tempPrint(cube[1][2][3]);
+        tempPrint(cube[1][2].length);
+    }
+
+    private static void partiallySpecifiedDimensions() {
+        int[][] rows = new int[3][];
+        tempPrint(rows[0] == null);
+        rows[1] = new int[]{1, 2, 3};
+        tempPrint(rows[1][2]);
+
+        double[][][] partial = new double[2][5][];
+        tempPrint(partial[1].length);
+        tempPrint(partial[1][4] == null);
+    }
+
+    private static void objectMatrices() {
+        Square[][] squares = new Square[2][2];
+        squares[1][1] = new Square(7);
+        tempPrint(squares[1][1].side);
+        tempPrint(squares[0][0] == null);
+
+        String[][] strings = {{"a", "b"}, {"c"}};
+        tempPrint(strings[1].length);
+    }
+
+    private static void typeChecks() {
+        Object matrix = new int[2][2];
+        tempPrint(matrix instanceof int[][]);
+        tempPrint(matrix instanceof Object[]);
+        tempPrint(matrix instanceof long[][]);
+        tempPrint(matrix instanceof int[]);
+        tempPrint(matrix instanceof Cloneable);
+
+        Object squares = new Square[1][1];
+        tempPrint(squares instanceof Object[][]);
+        tempPrint(squares instanceof Square[][]);
+        tempPrint(squares instanceof Square[]);
+
+        int[][] casted = (int[][]) matrix;
+        tempPrint(casted.length);
+        Object[] rows = (Object[]) matrix;
+        tempPrint(((int[]) rows[1]).length);
+    }
+
+    private static void cloning() {
+        int[][] matrix = new int[2][2];
+        matrix[0][1] = 3;
+        int[][] copy = matrix.clone();
+        tempPrint(copy != matrix);
+        // Shallow clone: the rows are shared
+        tempPrint(copy[0] == matrix[0]);
+        tempPrint(copy[0][1]);
+    }
+
+    // Allocates enough matrices to trigger some garbage collections, also while
+    // allocating the rows of a matrix
+    private static void garbageCollection() {
+        int[][] kept = new int[10][10];
+        kept[9][9] = 99;
+        int sum = 0;
+        for (int i = 0; i < 200; ++i) {
+            int[][] matrix = new int[50][50];
+            matrix[49][49] = i;
+            sum += matrix[49][49];
+        }
+        tempPrint(sum);
+        tempPrint(kept[9][9]);
+    }
+
+    static class Square {
+        final int side;
+
+        Square(int side) {
+            this.side = side;
+        }
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(boolean value);
+}
@@ -8,6 +8,7 @@ public class NumericTypes {
         doubleMath(1, 3.45);
         negate(returnOneInt(), returnOneLong(), returnOneFloat(), returnOneDouble());
         logicalShifts(4, 4);
+        byteConstants();
     }
 
     private static void shortAndCharMath(short s, char c) {
@@ -52,6 +53,12 @@ public class NumericTypes {
         tempPrint(l << 1);
     }
 
+    private static void byteConstants() {
+        tempPrint(-5);
+        tempPrint(-128);
+        tempPrint(100);
+    }
+
     private static int returnOneInt() {
         return 1;
     }
@@ -0,0 +1,179 @@
+package rjvm;
+
+class ObjectMethods {
+    static class Point implements Cloneable {
+        int x;
+        long y;
+        String name;
+        int[] values;
+
+        Point copy() throws CloneNotSupportedException {
+            return (Point) clone();
+        }
+    }
+
+    static class NamedPoint extends Point {
+        String label = "label";
+    }
+
+    static class Plain {
+        Object copy() throws CloneNotSupportedException {
+            return clone();
+        }
+    }
+
+    // A minimal hash table relying on the identity semantics of hashCode and equals
+    static class IdentityTable {
+        private final Object[] keys = new Object[16];
+        private final String[] values = new String[16];
+
+        void put(Object key, String value) {
+            int index = indexOf(key);
+            keys[index] = key;
+            values[index] = value;
+        }
+
+        String get(Object key) {
+            return values[indexOf(key)];
+        }
+
+        private int indexOf(Object key) {
+            int index = (key.hashCode() & 0x7fffffff) % keys.length;
+            while (keys[index] != null && !keys[index].equals(key)) {
+                index = (index + 1) % keys.length;
+            }
+            return index;
+        }
+    }
+
+    public static void main(String[] args) throws Exception {
+        hashCodes();
+        cloning();
+        monitors();
+    }
+
+    private static void hashCodes() {
+        Object object = new Object();
+        int hashCode = object.hashCode();
+        tempPrint(hashCode == System.identityHashCode(object));
+        tempPrint(hashCode == object.hashCode());
+
+        IdentityTable table = new IdentityTable();
+        Object first = new Object();
+        Object second = new Object();
+        Point third = new Point();
+        table.put(first, "first");
+        table.put(second, "second");
+        table.put(third, "third");
+
+        for (int i = 0; i < 1000; ++i) {
+            new Object();
+        }
+        System.gc();
+
+        tempPrint(hashCode == object.hashCode());
+        tempPrint(table.get(first));
+        tempPrint(table.get(second));
+        tempPrint(table.get(third));
+    }
+
+    private static void cloning() throws Exception {
+        Point point = new Point();
+        point.x = 1;
+        point.y = 10000000000L;
+        point.name = "point";
+        point.values = new int[] {1, 2};
+
+        Point copy = point.copy();
+        tempPrint(copy != point);
+        tempPrint(copy.getClass() == Point.class);
+        tempPrint(copy.x);
+        tempPrint(copy.y);
+        tempPrint(copy.name);
+        tempPrint(copy.values == point.values);
+        copy.x = 2;
+        tempPrint(point.x);
+
+        NamedPoint named = new NamedPoint();
+        named.x = 3;
+        Point namedCopy = named.copy();
+        tempPrint(namedCopy.getClass() == NamedPoint.class);
+        tempPrint(((NamedPoint) namedCopy).label);
+        tempPrint(namedCopy.x);
+
+        int[] array = {4, 5};
+        int[] arrayCopy = array.clone();
+        tempPrint(arrayCopy != array);
+        tempPrint(arrayCopy[1]);
+
+        try {
+            new Plain().copy();
+        } catch (CloneNotSupportedException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static void monitors() throws Exception {
+        Object lock = new Object();
+        try {
+            lock.notify();
+        } catch (IllegalMonitorStateException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            lock.wait();
+        } catch (IllegalMonitorStateException e) {
+            tempPrint("wait without lock");
+        }
+
+        synchronized (lock) {
+            lock.notify();
+            lock.notifyAll();
+            lock.wait(1);
+            synchronized (lock) {
+                lock.notify();
+            }
+            // Still owned by the outer block
+            lock.notifyAll();
+            try {
+                lock.wait(-1);
+            } catch (IllegalArgumentException e) {
+                tempPrint(e.getMessage());
+            }
+        }
+        tempPrint("synchronized block");
+
+        try {
+            lock.notifyAll();
+        } catch (IllegalMonitorStateException e) {
+            tempPrint("released");
+        }
+
+        new ObjectMethods().synchronizedMethod();
+        synchronizedStaticMethod();
+        try {
+            ObjectMethods.class.notify();
+        } catch (IllegalMonitorStateException e) {
+            tempPrint("class released");
+        }
+    }
+
+    private synchronized void synchronizedMethod() throws InterruptedException {
+        notify();
+        wait(1);
+        tempPrint("synchronized method");
+    }
+
+    private static synchronized void synchronizedStaticMethod() {
+        ObjectMethods.class.notifyAll();
+        tempPrint("synchronized static method");
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,46 @@
+package rjvm;
+
+class OutOfMemory {
+    static class Node {
+        long[] data;
+        Node next;
+    }
+
+    public static void main(String[] args) {
+        fillHeap();
+
+        // After the error, the memory should be usable again
+        long[] array = new long[1024 * 128];
+        array[0] = 3;
+        tempPrint((int) array[0]);
+
+        try {
+            tempPrint(new int[Integer.MAX_VALUE].length);
+        } catch (OutOfMemoryError e) {
+            tempPrint(e.getMessage().equals("Java heap space") ? 1 : 0);
+            tempPrint(e.getStackTrace()[0].getMethodName().equals("main") ? 1 : 0);
+        }
+
+        fillHeap();
+    }
+
+    private static void fillHeap() {
+        Node head = null;
+        int count = 0;
+        try {
+            while (true) {
+                Node node = new Node();
+                node.data = new long[1024 * 128];
+                node.next = head;
+                head = node;
+                ++count;
+            }
+        } catch (OutOfMemoryError e) {
+            head = null;
+            tempPrint(e.getMessage().equals("Java heap space") ? 1 : 0);
+            tempPrint(count > 0 ? 1 : 0);
+        }
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,238 @@
+package rjvm;
+
+import java.lang.reflect.Constructor;
+import java.lang.reflect.Field;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+import java.lang.reflect.Modifier;
+
+class Reflection {
+    // A tiny dependency injection container: instances are created by invoking the first
+    // constructor of their class, resolving its parameters recursively, and fields of type
+    // String are then filled with the configured value
+    static class Container {
+        private final Class<?>[] types = new Class<?>[8];
+        private final Object[] instances = new Object[8];
+        private int count;
+        private final String configuration;
+
+        Container(String configuration) {
+            this.configuration = configuration;
+        }
+
+        Object get(Class<?> type) throws Exception {
+            for (int i = 0; i < count; ++i) {
+                if (types[i] == type) {
+                    return instances[i];
+                }
+            }
+
+            Constructor<?> constructor = type.getDeclaredConstructors()[0];
+            Class<?>[] parameterTypes = constructor.getParameterTypes();
+            Object[] arguments = new Object[parameterTypes.length];
+            for (int i = 0; i < parameterTypes.length; ++i) {
+                arguments[i] = get(parameterTypes[i]);
+            }
+            Object instance = constructor.newInstance(arguments);
+
+            for (Field field : type.getDeclaredFields()) {
+                if (field.getType() == String.class && !Modifier.isStatic(field.getModifiers())) {
+                    field.setAccessible(true);
+                    field.set(instance, configuration);
+                }
+            }
+
+            types[count] = type;
+            instances[count] = instance;
+            ++count;
+            return instance;
+        }
+    }
+
+    static class Clock {
+        int now() {
+            return 42;
+        }
+    }
+
+    static class Repository {
+        private final Clock clock;
+
+        Repository(Clock clock) {
+            this.clock = clock;
+        }
+    }
+
+    static class Service {
+        private final Repository repository;
+        private final Clock clock;
+        private String name;
+
+        Service(Repository repository, Clock clock) {
+            this.repository = repository;
+            this.clock = clock;
+        }
+    }
+
+    static class Base {
+        String describe() {
+            return "base";
+        }
+    }
+
+    static class Derived extends Base {
+        private int counter = 1;
+        private long big = 10000000000L;
+        private Object payload = "payload";
+        static int instances = 3;
+
+        @Override
+        String describe() {
+            return "derived";
+        }
+
+        static int add(int a, int b) {
+            return a + b;
+        }
+
+        private String secret(String prefix) {
+            return prefix.concat(" secret");
+        }
+
+        void fail() throws IllegalStateException, CloneNotSupportedException {
+            throw new IllegalStateException("boom");
+        }
+    }
+
+    static abstract class Shape {
+    }
+
+    static class Lazy {
+        static int value = 5;
+
+        static {
+            tempPrint("lazy initialized");
+        }
+    }
+
+    public static void main(String[] args) throws Exception {
+        injection();
+        forName();
+        fields();
+        methods();
+        constructors();
+        modifiers();
+    }
+
+    private static void injection() throws Exception {
+        Container container = new Container("configured");
+        Service service = (Service) container.get(Service.class);
+        tempPrint(service.repository.clock == service.clock);
+        tempPrint(service.clock.now());
+        tempPrint(service.name);
+        tempPrint(container.get(Service.class) == service);
+    }
+
+    private static void forName() throws Exception {
+        tempPrint(Class.forName("rjvm.Reflection$Clock") == Clock.class);
+        tempPrint(Class.forName("[Ljava.lang.String;") == String[].class);
+        try {
+            Class.forName("rjvm.Missing");
+        } catch (ClassNotFoundException e) {
+            tempPrint(e.getMessage());
+        }
+
+        Class<?> lazy = Class.forName("rjvm.Reflection$Lazy", false, null);
+        tempPrint("loaded");
+        tempPrint(lazy.getDeclaredField("value").getInt(null));
+    }
+
+    private static void fields() throws Exception {
+        Derived derived = new Derived();
+        Field counter = Derived.class.getDeclaredField("counter");
+        counter.setInt(derived, counter.getInt(derived) + 1);
+        tempPrint(derived.counter);
+
+        Field big = Derived.class.getDeclaredField("big");
+        big.setLong(derived, big.getLong(derived) * 2);
+        tempPrint(derived.big);
+
+        Field payload = Derived.class.getDeclaredField("payload");
+        tempPrint((String) payload.get(derived));
+        payload.set(derived, "replaced");
+        tempPrint((String) derived.payload);
+
+        Field instances = Derived.class.getDeclaredField("instances");
+        instances.set(null, 4);
+        tempPrint(Derived.instances);
+        tempPrint(instances.getDeclaringClass() == Derived.class);
+        tempPrint(instances.getType() == int.class);
+    }
+
+    private static void methods() throws Exception {
+        Method add = Derived.class.getDeclaredMethod("add", int.class, int.class);
+        tempPrint(((Integer) add.invoke(null, 2, 3)).intValue());
+        tempPrint(add.getReturnType() == int.class);
+        tempPrint(add.getParameterTypes().length);
+
+        Method describe = Base.class.getDeclaredMethod("describe");
+        tempPrint((String) describe.invoke(new Derived()));
+
+        Method secret = Derived.class.getDeclaredMethod("secret", String.class);
+        tempPrint((String) secret.invoke(new Derived(), "a"));
+
+        Method fail = Derived.class.getDeclaredMethod("fail");
+        tempPrint(fail.getExceptionTypes()[1] == CloneNotSupportedException.class);
+        try {
+            fail.invoke(new Derived());
+        } catch (InvocationTargetException e) {
+            tempPrint(e.getCause().getMessage());
+        }
+
+        try {
+            describe.invoke("not a base");
+        } catch (IllegalArgumentException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            add.invoke(null, 1);
+        } catch (IllegalArgumentException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            add.invoke(null, 1, "two");
+        } catch (IllegalArgumentException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static void constructors() throws Exception {
+        Constructor<?> constructor = Repository.class.getDeclaredConstructor(Clock.class);
+        Repository repository = (Repository) constructor.newInstance(new Clock());
+        tempPrint(repository.clock.now());
+        tempPrint(Clock.class.newInstance().now());
+        try {
+            Shape.class.getDeclaredConstructors()[0].newInstance();
+        } catch (InstantiationException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static void modifiers() throws Exception {
+        tempPrint(Modifier.isStatic(Derived.class.getDeclaredMethod("add", int.class, int.class).getModifiers()));
+        tempPrint(Modifier.isPrivate(Derived.class.getDeclaredField("counter").getModifiers()));
+        tempPrint(Modifier.isInterface(Runnable.class.getModifiers()));
+        tempPrint(Modifier.isAbstract(Shape.class.getModifiers()));
+        tempPrint(Modifier.isFinal(int[].class.getModifiers()));
+        tempPrint(Derived.class.getDeclaredMethods().length);
+        tempPrint(Derived.class.getDeclaredConstructors().length);
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,185 @@
+package rjvm;
+
+class RuntimeExceptions {
+    static class Point {
+        int x;
+
+        void move() {
+            x++;
+        }
+    }
+
+    public static void main(String[] args) {
+        arithmetic();
+        arrays();
+        casts();
+        nullPointers();
+        arrayCopies();
+        stackTraces();
+    }
+
+    private static void arithmetic() {
+        try {
+            tempPrint(divide(1, 0));
+        } catch (ArithmeticException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            long zero = 0;
+            tempPrint((int) (1L % zero));
+        } catch (ArithmeticException e) {
+            tempPrint(e.getMessage());
+        }
+
+        // The handler must start with an empty stack, otherwise it would overflow
+        int sum = 0;
+        int caught = 0;
+        for (int i = 0; i < 10; ++i) {
+            try {
+                sum += 10 / (i - i);
+            } catch (ArithmeticException e) {
+                ++caught;
+            }
+        }
+        tempPrint(caught);
+    }
+
+    private static int divide(int a, int b) {
+        return a / b;
+    }
+
+    private static void arrays() {
+        int[] ints = new int[3];
+        try {
+            ints[3] = 1;
+        } catch (ArrayIndexOutOfBoundsException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            tempPrint(ints[-1]);
+        } catch (IndexOutOfBoundsException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            ints = new int[-2];
+        } catch (NegativeArraySizeException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            Object[] objects = new String[1];
+            objects[0] = new Point();
+        } catch (ArrayStoreException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static void casts() {
+        Object object = "a string";
+        try {
+            Integer integer = (Integer) object;
+            tempPrint(integer.intValue());
+        } catch (ClassCastException e) {
+            tempPrint(e.getMessage());
+        }
+
+        object = null;
+        Integer integer = (Integer) object;
+        tempPrint(integer == null ? 1 : 0);
+    }
+
+    private static void nullPointers() {
+        Point point = null;
+        try {
+            tempPrint(point.x);
+        } catch (NullPointerException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            point.x = 1;
+        } catch (NullPointerException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            point.move();
+        } catch (NullPointerException e) {
+            tempPrint(e.getMessage());
+        }
+        String string = null;
+        try {
+            tempPrint(string.concat("x"));
+        } catch (NullPointerException e) {
+            tempPrint(e.getMessage());
+        }
+
+        int[] array = null;
+        try {
+            tempPrint(array.length);
+        } catch (NullPointerException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            tempPrint(array[0]);
+        } catch (NullPointerException e) {
+            tempPrint(e.getMessage());
+        }
+
+        RuntimeException exception = null;
+        try {
+            throw exception;
+        } catch (NullPointerException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            synchronized (point) {
+                tempPrint(0);
+            }
+        } catch (NullPointerException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static void arrayCopies() {
+        int[] ints = new int[3];
+        try {
+            System.arraycopy(null, 0, ints, 0, 1);
+        } catch (NullPointerException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            System.arraycopy(ints, 0, new long[3], 0, 1);
+        } catch (ArrayStoreException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            System.arraycopy(ints, 2, ints, 0, 2);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            tempPrint(e.getMessage());
+        }
+
+        Object[] objects = new Object[] {"a", new Point()};
+        String[] strings = new String[2];
+        try {
+            System.arraycopy(objects, 0, strings, 0, 2);
+        } catch (ArrayStoreException e) {
+            tempPrint(e.getMessage());
+        }
+        tempPrint(strings[0]);
+    }
+
+    private static void stackTraces() {
+        try {
+            tempPrint(divide(1, 0));
+        } catch (ArithmeticException e) {
+            for (StackTraceElement element : e.getStackTrace()) {
+                if (element.getClassName().equals("rjvm/RuntimeExceptions")) {
+                    tempPrint(element.getMethodName());
+                    tempPrint(element.getLineNumber());
+                }
+            }
+        }
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,30 @@
+package rjvm;
+
+class StackOverflow {
+    private static int depth = 0;
+
+    public static void main(String[] args) {
+        try {
+            recurse();
+        } catch (StackOverflowError e) {
+            tempPrint(depth);
+            tempPrint(e.getStackTrace().length);
+            tempPrint(e.getMessage() == null ? 1 : 0);
+        }
+
+        // The stack is usable again after the error has been caught
+        depth = 0;
+        try {
+            recurse();
+        } catch (StackOverflowError e) {
+            tempPrint(depth);
+        }
+    }
+
+    private static void recurse() {
+        ++depth;
+        recurse();
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,34 @@
+package rjvm;
+
+public class StackTracesAfterGarbageCollection {
+    public static void main(String[] args) {
+        Throwable survivor = createAtDepth(3);
+
+        // Trigger gc repeatedly, with throwables that reuse the memory of the dead ones
+        for (int i = 0; i < 20000; ++i) {
+            createAtDepth(1);
+        }
+
+        tempPrint(countFrames(survivor, "createAtDepth"));
+        tempPrint(countFrames(createAtDepth(2), "createAtDepth"));
+    }
+
+    private static Throwable createAtDepth(int depth) {
+        if (depth == 1) {
+            return new Exception();
+        }
+        return createAtDepth(depth - 1);
+    }
+
+    private static int countFrames(Throwable throwable, String methodName) {
+        int count = 0;
+        for (StackTraceElement element : throwable.getStackTrace()) {
+            if (element.getMethodName().equals(methodName)) {
+                ++count;
+            }
+        }
+        return count;
+    }
+
+    private static native void tempPrint(int value);
+}
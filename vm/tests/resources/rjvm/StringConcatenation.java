@@ -0,0 +1,50 @@
+package rjvm;
+
+class StringConcatenation {
+    static class Point {
+        final int x;
+        final int y;
+
+        Point(int x, int y) {
+            this.x = x;
+            this.y = y;
+        }
+
+        @Override
+        public String toString() {
+            return "(" + x + ", " + y + ")";
+        }
+    }
+
+    public static void main(String[] args) {
+        primitives(true, 'c', (byte) -1, (short) 2, 3, 4L, 1.5f, 0.25);
+        floatingPoint(1e10, 1e-5, 12345.0f);
+        objects(new Point(1, 2), null);
+        constants("\u0001", "\u0002");
+        onlyArguments("a", "b");
+    }
+
+    private static void primitives(
+            boolean z, char c, byte b, short s, int i, long l, float f, double d) {
+        tempPrint(z + " " + c + " " + b + " " + s + " " + i + " " + l + " " + f + " " + d);
+    }
+
+    private static void floatingPoint(double large, double small, float exact) {
+        tempPrint(large + "|" + small + "|" + exact);
+    }
+
+    private static void objects(Point point, String nullString) {
+        tempPrint("point: " + point + ", null: " + nullString);
+    }
+
+    private static void constants(String one, String two) {
+        // javac passes the literals that contain the tag characters as constants
+        tempPrint("\u0001-" + one + "-\u0002-" + two);
+    }
+
+    private static void onlyArguments(String a, String b) {
+        tempPrint(a + b);
+    }
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,61 @@
+package rjvm;
+
+class StringInterning {
+    static class Other {
+        static String hello() {
+            return "hello";
+        }
+    }
+
+    public static void main(String[] args) {
+        literals();
+        intern();
+        afterGarbageCollection();
+    }
+
+    private static void literals() {
+        String a = "hello";
+        String b = "hello";
+        tempPrint(a == b);
+        tempPrint(a == Other.hello());
+        tempPrint(a == "hel" + "lo");
+
+        boolean sameEveryTime = true;
+        String previous = literal();
+        for (int i = 0; i < 3; ++i) {
+            String current = literal();
+            sameEveryTime &= current == previous;
+            previous = current;
+        }
+        tempPrint(sameEveryTime);
+    }
+
+    private static String literal() {
+        return "in a loop";
+    }
+
+    private static void intern() {
+        String built = "hel".concat("lo");
+        tempPrint(built == "hello");
+        tempPrint(built.intern() == "hello");
+
+        // The first string interned with a given content is the one returned by the literals
+        String fresh = "never".concat(" seen");
+        tempPrint(fresh.intern() == fresh);
+        tempPrint(fresh == "never seen");
+    }
+
+    private static void afterGarbageCollection() {
+        String before = "survives";
+        for (int i = 0; i < 1000; ++i) {
+            new Object();
+        }
+        System.gc();
+        tempPrint(before == "survives");
+        tempPrint(before.concat("!"));
+    }
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,80 @@
+package rjvm;
+
+public class Switches {
+    public static void main(String[] args) {
+        for (int i = -1; i <= 4; ++i) {
+            tempPrint(dense(i));
+        }
+
+        tempPrint(sparse(-1000));
+        tempPrint(sparse(7));
+        tempPrint(sparse(1000000));
+        tempPrint(sparse(8));
+
+        for (int i = -3; i <= 0; ++i) {
+            tempPrint(negative(i));
+        }
+
+        tempPrint(strings("one"));
+        tempPrint(strings("two"));
+        tempPrint(strings("three"));
+    }
+
+    // Compiled to a tableswitch
+    private static int dense(int value) {
+        switch (value) {
+            case 0:
+                return 10;
+            case 1:
+                return 11;
+            case 2:
+                return 12;
+            case 3:
+                return 13;
+            default:
+                return -1;
+        }
+    }
+
+    // Compiled to a lookupswitch
+    private static int sparse(int value) {
+        switch (value) {
+            case -1000:
+                return 1;
+            case 7:
+                return 2;
+            case 1000000:
+                return 3;
+            default:
+                return 4;
+        }
+    }
+
+    private static int negative(int value) {
+        int result = 0;
+        switch (value) {
+            case -3:
+                result += 100;
+            case -2:
+                result += 20;
+                break;
+            case -1:
+                result += 3;
+                break;
+        }
+        return result;
+    }
+
+    private static int strings(String value) {
+        switch (value) {
+            case "one":
+                return 1;
+            case "two":
+                return 2;
+            default:
+                return 0;
+        }
+    }
+
+    private static native void tempPrint(int value);
+}
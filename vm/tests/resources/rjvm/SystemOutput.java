@@ -0,0 +1,21 @@
+package rjvm;
+
+class SystemOutput {
+    static class Point {
+        @Override
+        public String toString() {
+            return "a point";
+        }
+    }
+
+    public static void main(String[] args) {
+        System.out.println("Hello, world!");
+        System.out.print("The answer is ");
+        System.out.println(42);
+        System.out.println(new Point());
+        System.out.println((Object) null);
+        System.out.println("unicode: \u00e0\u00e9\u20ac");
+        System.err.println("an error");
+        System.out.println(System.lineSeparator().length());
+    }
+}
@@ -0,0 +1,74 @@
+package rjvm;
+
+public class WideLocals {
+    public static void main(String[] args) {
+        largeIncrements();
+        manyLocals();
+    }
+
+    private static void largeIncrements() {
+        int x = 0;
+        x += 1000;
+        tempPrint(x);
+        x -= 30000;
+        tempPrint(x);
+        x += Short.MAX_VALUE;
+        tempPrint(x);
+    }
+
+    // Has more than 256 local slots, so that the last ones need to be accessed via wide
+    private static void manyLocals() {
+        int v0 = 0, v1 = 1, v2 = 2, v3 = 3, v4 = 4, v5 = 5, v6 = 6, v7 = 7;
+        int v8 = 8, v9 = 9, v10 = 10, v11 = 11, v12 = 12, v13 = 13, v14 = 14, v15 = 15;
+        int v16 = 16, v17 = 17, v18 = 18, v19 = 19, v20 = 20, v21 = 21, v22 = 22, v23 = 23;
+        int v24 = 24, v25 = 25, v26 = 26, v27 = 27, v28 = 28, v29 = 29, v30 = 30, v31 = 31;
+        int v32 = 32, v33 = 33, v34 = 34, v35 = 35, v36 = 36, v37 = 37, v38 = 38, v39 = 39;
+        int v40 = 40, v41 = 41, v42 = 42, v43 = 43, v44 = 44, v45 = 45, v46 = 46, v47 = 47;
+        int v48 = 48, v49 = 49, v50 = 50, v51 = 51, v52 = 52, v53 = 53, v54 = 54, v55 = 55;
+        int v56 = 56, v57 = 57, v58 = 58, v59 = 59, v60 = 60, v61 = 61, v62 = 62, v63 = 63;
+        int v64 = 64, v65 = 65, v66 = 66, v67 = 67, v68 = 68, v69 = 69, v70 = 70, v71 = 71;
+        int v72 = 72, v73 = 73, v74 = 74, v75 = 75, v76 = 76, v77 = 77, v78 = 78, v79 = 79;
+        int v80 = 80, v81 = 81, v82 = 82, v83 = 83, v84 = 84, v85 = 85, v86 = 86, v87 = 87;
+        int v88 = 88, v89 = 89, v90 = 90, v91 = 91, v92 = 92, v93 = 93, v94 = 94, v95 = 95;
+        int v96 = 96, v97 = 97, v98 = 98, v99 = 99, v100 = 100, v101 = 101, v102 = 102, v103 = 103;
+        int v104 = 104, v105 = 105, v106 = 106, v107 = 107, v108 = 108, v109 = 109, v110 = 110, v111 = 111;
+        int v112 = 112, v113 = 113, v114 = 114, v115 = 115, v116 = 116, v117 = 117, v118 = 118, v119 = 119;
+        int v120 = 120, v121 = 121, v122 = 122, v123 = 123, v124 = 124, v125 = 125, v126 = 126, v127 = 127;
+        int v128 = 128, v129 = 129, v130 = 130, v131 = 131, v132 = 132, v133 = 133, v134 = 134, v135 = 135;
+        int v136 = 136, v137 = 137, v138 = 138, v139 = 139, v140 = 140, v141 = 141, v142 = 142, v143 = 143;
+        int v144 = 144, v145 = 145, v146 = 146, v147 = 147, v148 = 148, v149 = 149, v150 = 150, v151 = 151;
+        int v152 = 152, v153 = 153, v154 = 154, v155 = 155, v156 = 156, v157 = 157, v158 = 158, v159 = 159;
+        int v160 = 160, v161 = 161, v162 = 162, v163 = 163, v164 = 164, v165 = 165, v166 = 166, v167 = 167;
+        int v168 = 168, v169 = 169, v170 = 170, v171 = 171, v172 = 172, v173 = 173, v174 = 174, v175 = 175;
+        int v176 = 176, v177 = 177, v178 = 178, v179 = 179, v180 = 180, v181 = 181, v182 = 182, v183 = 183;
+        int v184 = 184, v185 = 185, v186 = 186, v187 = 187, v188 = 188, v189 = 189, v190 = 190, v191 = 191;
+        int v192 = 192, v193 = 193, v194 = 194, v195 = 195, v196 = 196, v197 = 197, v198 = 198, v199 = 199;
+        int v200 = 200, v201 = 201, v202 = 202, v203 = 203, v204 = 204, v205 = 205, v206 = 206, v207 = 207;
+        int v208 = 208, v209 = 209, v210 = 210, v211 = 211, v212 = 212, v213 = 213, v214 = 214, v215 = 215;
+        int v216 = 216, v217 = 217, v218 = 218, v219 = 219, v220 = 220, v221 = 221, v222 = 222, v223 = 223;
+        int v224 = 224, v225 = 225, v226 = 226, v227 = 227, v228 = 228, v229 = 229, v230 = 230, v231 = 231;
+        int v232 = 232, v233 = 233, v234 = 234, v235 = 235, v236 = 236, v237 = 237, v238 = 238, v239 = 239;
+        int v240 = 240, v241 = 241, v242 = 242, v243 = 243, v244 = 244, v245 = 245, v246 = 246, v247 = 247;
+        int v248 = 248, v249 = 249, v250 = 250, v251 = 251, v252 = 252, v253 = 253, v254 = 254, v255 = 255;
+        int i = v255 + v1;
+        i += 2000;
+        long l = 1L + i;
+        float f = 2.5f;
+        double d = 3.5;
+        Object o = null;
+        tempPrint(i);
+        tempPrint(l);
+        tempPrint(f);
+        tempPrint(d);
+        tempPrint(o == null ? 1 : 0);
+        tempPrint(v0 + v100 + v200);
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(float value);
+
+    private static native void tempPrint(double value);
+}
@@ -8,7 +8,7 @@ use rjvm_vm::{
     exceptions::MethodCallFailed,
     java_objects_creation::new_java_lang_string_object,
     value::Value,
-    vm::{Vm, DEFAULT_MAX_MEMORY_MB_STR, ONE_MEGABYTE},
+    vm::{Vm, DEFAULT_MAX_MEMORY_MB_STR, DEFAULT_MAX_STACK_DEPTH_STR, ONE_MEGABYTE},
     vm_error::VmError,
 };
 
@@ -26,6 +26,11 @@ struct Args {
     #[arg(short, long, default_value = DEFAULT_MAX_MEMORY_MB_STR)]
     maximum_mb_of_memory: usize,
 
+    /// Maximum depth of the call stack, in number of frames. Deeper calls will throw a
+    /// java.lang.StackOverflowError, similarly to the -Xss option of java
+    #[arg(short = 's', long, default_value = DEFAULT_MAX_STACK_DEPTH_STR)]
+    maximum_stack_depth: usize,
+
     /// Java program arguments
     java_program_arguments: Vec<String>,
 }
@@ -56,9 +61,9 @@ fn append_classpath(vm: &mut Vm, args: &Args) -> Result<(), String> {
 
 fn resolve_class_and_main_method<'a>(
     vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
     args: &Args,
-) -> Result<(&'a mut CallStack<'a>, ClassAndMethod<'a>), String> {
-    let call_stack = vm.allocate_call_stack();
+) -> Result<ClassAndMethod<'a>, String> {
     let main_method = vm
         .resolve_class_method(
             call_stack,
@@ -75,14 +80,20 @@ fn resolve_class_and_main_method<'a>(
             }
             _ => format!("unexpected error: {:?}", v),
         })?;
-    Ok((call_stack, main_method))
+    Ok(main_method)
 }
 
 fn run(args: Args) -> Result<i32, String> {
-    let mut vm = Vm::new(args.maximum_mb_of_memory * ONE_MEGABYTE);
+    let mut vm = Vm::new(
+        args.maximum_mb_of_memory * ONE_MEGABYTE,
+        args.maximum_stack_depth,
+    );
     append_classpath(&mut vm, &args)?;
 
-    let (call_stack, main_method) = resolve_class_and_main_method(&mut vm, &args)?;
+    let call_stack = vm.allocate_call_stack();
+    let main_method = resolve_class_and_main_method(&mut vm, call_stack, &args)?;
+    vm.initialize_system_class(call_stack)
+        .map_err(|v| format!("error initializing the system class: {:?}", v))?;
 
     let main_args = allocate_java_args(&mut vm, call_stack, &args.java_program_arguments)
         .map_err(|err| format!("{err:?}"))?;
@@ -112,9 +123,10 @@ fn allocate_java_args<'a>(
 
     let strings = strings?;
     let array = vm.new_array(
+        call_stack,
         ArrayEntryType::Object(class_id_java_lang_string),
         strings.len(),
-    );
+    )?;
 
     for (index, string) in strings.into_iter().enumerate() {
         array.set_element(index, string)?;